@@ -1,187 +1,292 @@
-#[derive(Copy, Clone)]
-pub struct Backend<'a> {
-  pub name: &'a str,
-  pub mdb_bool: BackendType<'a>,
-  pub mdb_byte: BackendType<'a>,
-  pub mdb_int: BackendType<'a>,
-  pub mdb_longint: BackendType<'a>,
-  pub mdb_money: BackendType<'a>,
-  pub mdb_float: BackendType<'a>,
-  pub mdb_double: BackendType<'a>,
-  pub mdb_datetime: BackendType<'a>,
-  pub mdb_binary: BackendType<'a>,
-  pub mdb_text: BackendType<'a>,
-  pub mdb_ole: BackendType<'a>,
-  pub mdb_memo: BackendType<'a>,
-  pub mdb_replication_id: BackendType<'a>,
-  pub mdb_numeric: BackendType<'a>,
-  pub mdb_extended_datetime: BackendType<'a>,
-  pub mdb_complex: BackendType<'a>,
-
-  pub quote_name: fn(name: &str) -> String,
-  pub default_quote_str: &'a str,
-  pub default_null_str: &'a str,
-
-  pub create_table_string: &'a str,
-  pub column_string: &'a str,
-}
-
-impl<'a> PartialEq<Self> for Backend<'a> {
-  fn eq(&self, other: &Self) -> bool {
-    self.name.eq(other.name)
-  }
-}
-
-impl<'a> Eq for Backend<'a> {
-
-}
-
-pub trait QuoteName {
-  fn quote_name(&self, name: String) -> String;
-}
-
-#[derive(Copy, Clone)]
-pub struct BackendType<'a> {
-  pub name: &'a str,
-  pub needs_precision: bool,
-  pub needs_scale: bool,
-  pub needs_byte_length: bool,
-  pub needs_char_length: bool
-}
-
-impl<'a> BackendType<'a> {
-
-  const fn new(name: &'a str) -> Self {
-    Self {
-      name,
-      needs_precision: false,
-      needs_scale: false,
-      needs_byte_length: false,
-      needs_char_length: false,
-    }
-  }
-
-  const fn needs_precision(mut self) -> Self {
-    self.needs_precision = true;
-    self
-  }
-
-  const fn needs_scale(mut self) -> Self {
-    self.needs_scale = true;
-    self
-  }
-
-  const fn needs_byte_length(mut self) -> Self {
-    self.needs_byte_length = true;
-    self
-  }
-
-  const fn needs_char_length(mut self) -> Self {
-    self.needs_char_length = true;
-    self
-  }
-
-
-}
-
-pub const POSTGRES_BACKEND: Backend = Backend {
-  name: "Postgres",
-  mdb_bool: BackendType::new("BOOLEAN"),
-  mdb_byte: BackendType::new("SMALLINT"),
-  mdb_int: BackendType::new("INTEGER"),
-  mdb_longint: BackendType::new("INTEGER"),
-  mdb_money: BackendType::new("NUMERIC(15,4)"),
-  mdb_float: BackendType::new("REAL"),
-  mdb_double: BackendType::new("DOUBLE PRECISION"),
-  mdb_datetime: BackendType::new("TIMESTAMP WITHOUT TIME ZONE"),
-  mdb_binary: BackendType::new("BYTEA"),
-  mdb_text: BackendType::new("VARCHAR").needs_char_length(),
-  mdb_ole: BackendType::new("BYTEA"),
-  mdb_memo: BackendType::new("TEXT"),
-  mdb_replication_id: BackendType::new("UUID"),
-  mdb_numeric: BackendType::new("NUMERIC").needs_precision().needs_scale(),
-  mdb_extended_datetime: BackendType::new("TIMESTAMP"),
-  mdb_complex: BackendType::new("complex?"),
-  quote_name: quote_double_quotes,
-
-  default_quote_str: "'",
-  default_null_str: "NULL",
-  create_table_string: "CREATE TABLE IF NOT EXISTS {quoted_table_name} (",
-  column_string: r#"{quoted_column_name} {column_type}"#
-};
-
-/// Double the quote char and surround with quote.
-fn quote_double_quotes(name: &str) -> String {
-  format!("\"{}\"", name.replace('"', "\"\""))
-}
-
-fn quote_name_brackets(name: &str) -> String {
-  format!("[{}]", name.replace(']', "]]"))
-}
-
-pub const MSSQL_BACKEND: Backend = Backend {
-  name: "mssql",
-  mdb_bool: BackendType::new("BIT"),
-  mdb_byte: BackendType::new("CHAR"),
-  mdb_int: BackendType::new("SMALLINT"),
-  mdb_longint: BackendType::new("INT"),
-  mdb_money: BackendType::new("MONEY"),
-  mdb_float: BackendType::new("REAL"),
-  mdb_double: BackendType::new("FLOAT"),
-  mdb_datetime: BackendType::new("SMALLDATETIME"),
-  mdb_binary: BackendType::new("VARBINARY").needs_byte_length(),
-  mdb_text: BackendType::new("NVARCHAR").needs_char_length(),
-  mdb_ole: BackendType::new("VARBINARY(MAX)"),
-  mdb_memo: BackendType::new("NVARCHAR(MAX)"),
-  mdb_replication_id: BackendType::new("UNIQUEIDENTIFIER"),
-  mdb_numeric: BackendType::new("NUMERIC").needs_precision().needs_scale(),
-  mdb_extended_datetime: BackendType::new("DATETIME2"),
-  mdb_complex: BackendType::new("complex?"),
-  quote_name: quote_name_brackets,
-
-  default_quote_str: "'",
-  default_null_str: "NULL",
-  create_table_string: "IF OBJECT_ID(N'{quoted_table_name}', N'U') IS NULL\n  create table {quoted_table_name} (",
-
-  column_string: "  {quoted_column_name} {column_type}"
-};
-
-pub const CSV_BACKEND: Backend = Backend {
-  name: "csv",
-  mdb_bool: BackendType::new("bool"),
-  mdb_byte: BackendType::new("byte"),
-  mdb_int: BackendType::new("int"),
-  mdb_longint: BackendType::new("longint"),
-  mdb_money: BackendType::new("money"),
-  mdb_float: BackendType::new("float"),
-  mdb_double: BackendType::new("double"),
-  mdb_datetime: BackendType::new("datetime"),
-  mdb_binary: BackendType::new("binary").needs_byte_length(),
-  mdb_text: BackendType::new("text").needs_char_length(),
-  mdb_ole: BackendType::new("ole"),
-  mdb_memo: BackendType::new("memo"),
-  mdb_replication_id: BackendType::new("replication_id"),
-  mdb_numeric: BackendType::new("numeric").needs_precision().needs_scale(),
-  quote_name: quote_double_quotes,
-  mdb_extended_datetime: BackendType::new("DATETIME2"),
-  mdb_complex: BackendType::new("complex?"),
-  create_table_string: "",
-  column_string: "",
-  default_quote_str: "\"",
-  default_null_str: "",
-};
-
-/// Double the quote char and surround with quote.
-pub fn quote_generic(name: &str, quote: &str, escape: &Option<String>) -> String {
-  let escape_string = if escape.is_some() {
-    escape.clone().unwrap() + quote
-  } else {
-    quote.to_owned() + quote
-  };
-
-  format!("{quote}{}{quote}", name.replace(quote, &escape_string))
-}
-
-// quoted_table_name
-// table_name
+use crate::column::Column;
+use crate::property::PropertyBlock;
+use crate::sql::translate;
+use crate::value::Value;
+
+#[derive(Copy, Clone)]
+pub struct Backend<'a> {
+  pub name: &'a str,
+  pub mdb_bool: BackendType<'a>,
+  pub mdb_byte: BackendType<'a>,
+  pub mdb_int: BackendType<'a>,
+  pub mdb_longint: BackendType<'a>,
+  pub mdb_money: BackendType<'a>,
+  pub mdb_float: BackendType<'a>,
+  pub mdb_double: BackendType<'a>,
+  pub mdb_datetime: BackendType<'a>,
+  pub mdb_binary: BackendType<'a>,
+  pub mdb_text: BackendType<'a>,
+  pub mdb_ole: BackendType<'a>,
+  pub mdb_memo: BackendType<'a>,
+  pub mdb_replication_id: BackendType<'a>,
+  pub mdb_numeric: BackendType<'a>,
+  pub mdb_extended_datetime: BackendType<'a>,
+  /// Complex columns hold the id of their values in the hidden flat table; see `array_string` for multivalued fields.
+  pub mdb_complex: BackendType<'a>,
+  /// The type of a multivalued field, from `{column_type}`, the type of its values. Backends without arrays store the
+  /// values as a JSON array.
+  pub array_string: &'a str,
+
+  pub quote_name: fn(name: &str) -> String,
+  pub default_quote_str: &'a str,
+  pub default_null_str: &'a str,
+
+  pub create_table_string: &'a str,
+  pub column_string: &'a str,
+  pub create_view_string: &'a str,
+  /// Printed before and after statements that must be alone in their batch, such as CREATE VIEW.
+  pub batch_separator: &'a str,
+  /// Appended to the type of AutoNumber long integer columns.
+  pub identity_string: &'a str,
+  /// The default of AutoNumber replication ID columns.
+  pub uuid_default_string: &'a str,
+  /// Appended to the type of calculated columns, with their translated expression in `{expression}`. Empty when the
+  /// backend has no generated columns, in which case their stored results are kept.
+  pub generated_string: &'a str,
+  /// Attaches the Description property of a table. `{comment}` is a quoted string literal.
+  pub table_comment_string: &'a str,
+  /// Attaches the Description property of a column.
+  pub column_comment_string: &'a str,
+
+  /// The dialect Access SQL is translated into.
+  pub sql_dialect: SqlDialect,
+  /// The schema tables are created in when none is given.
+  pub default_schema: &'a str,
+}
+
+/// The SQL dialect of a backend.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum SqlDialect {
+  Access,
+  Postgres,
+  TSql,
+}
+
+impl<'a> PartialEq<Self> for Backend<'a> {
+  fn eq(&self, other: &Self) -> bool {
+    self.name.eq(other.name)
+  }
+}
+
+impl<'a> Eq for Backend<'a> {
+
+}
+
+pub trait QuoteName {
+  fn quote_name(&self, name: String) -> String;
+}
+
+#[derive(Copy, Clone)]
+pub struct BackendType<'a> {
+  pub name: &'a str,
+  pub needs_precision: bool,
+  pub needs_scale: bool,
+  pub needs_byte_length: bool,
+  pub needs_char_length: bool
+}
+
+impl<'a> BackendType<'a> {
+
+  const fn new(name: &'a str) -> Self {
+    Self {
+      name,
+      needs_precision: false,
+      needs_scale: false,
+      needs_byte_length: false,
+      needs_char_length: false,
+    }
+  }
+
+  const fn needs_precision(mut self) -> Self {
+    self.needs_precision = true;
+    self
+  }
+
+  const fn needs_scale(mut self) -> Self {
+    self.needs_scale = true;
+    self
+  }
+
+  const fn needs_byte_length(mut self) -> Self {
+    self.needs_byte_length = true;
+    self
+  }
+
+  const fn needs_char_length(mut self) -> Self {
+    self.needs_char_length = true;
+    self
+  }
+
+
+}
+
+pub const POSTGRES_BACKEND: Backend = Backend {
+  name: "Postgres",
+  mdb_bool: BackendType::new("BOOLEAN"),
+  mdb_byte: BackendType::new("SMALLINT"),
+  mdb_int: BackendType::new("INTEGER"),
+  mdb_longint: BackendType::new("INTEGER"),
+  mdb_money: BackendType::new("NUMERIC(15,4)"),
+  mdb_float: BackendType::new("REAL"),
+  mdb_double: BackendType::new("DOUBLE PRECISION"),
+  mdb_datetime: BackendType::new("TIMESTAMP WITHOUT TIME ZONE"),
+  mdb_binary: BackendType::new("BYTEA"),
+  mdb_text: BackendType::new("VARCHAR").needs_char_length(),
+  mdb_ole: BackendType::new("BYTEA"),
+  mdb_memo: BackendType::new("TEXT"),
+  mdb_replication_id: BackendType::new("UUID"),
+  mdb_numeric: BackendType::new("NUMERIC").needs_precision().needs_scale(),
+  mdb_extended_datetime: BackendType::new("TIMESTAMP"),
+  mdb_complex: BackendType::new("INTEGER"),
+  array_string: "{column_type}[]",
+  quote_name: quote_double_quotes,
+
+  default_quote_str: "'",
+  default_null_str: "NULL",
+  create_table_string: "CREATE TABLE IF NOT EXISTS {quoted_table_name} (",
+  column_string: r#"{quoted_column_name} {column_type}"#,
+  create_view_string: "CREATE OR REPLACE VIEW {quoted_view_name} AS",
+  batch_separator: "",
+  identity_string: "GENERATED BY DEFAULT AS IDENTITY",
+  uuid_default_string: "gen_random_uuid()",
+  generated_string: "GENERATED ALWAYS AS ({expression}) STORED",
+  table_comment_string: "COMMENT ON TABLE {quoted_table_name} IS {comment};",
+  column_comment_string: "COMMENT ON COLUMN {quoted_table_name}.{quoted_column_name} IS {comment};",
+  sql_dialect: SqlDialect::Postgres,
+  default_schema: "public",
+};
+
+/// Double the quote char and surround with quote.
+fn quote_double_quotes(name: &str) -> String {
+  format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_name_brackets(name: &str) -> String {
+  format!("[{}]", name.replace(']', "]]"))
+}
+
+pub const MSSQL_BACKEND: Backend = Backend {
+  name: "mssql",
+  mdb_bool: BackendType::new("BIT"),
+  mdb_byte: BackendType::new("CHAR"),
+  mdb_int: BackendType::new("SMALLINT"),
+  mdb_longint: BackendType::new("INT"),
+  mdb_money: BackendType::new("MONEY"),
+  mdb_float: BackendType::new("REAL"),
+  mdb_double: BackendType::new("FLOAT"),
+  mdb_datetime: BackendType::new("SMALLDATETIME"),
+  mdb_binary: BackendType::new("VARBINARY").needs_byte_length(),
+  mdb_text: BackendType::new("NVARCHAR").needs_char_length(),
+  mdb_ole: BackendType::new("VARBINARY(MAX)"),
+  mdb_memo: BackendType::new("NVARCHAR(MAX)"),
+  mdb_replication_id: BackendType::new("UNIQUEIDENTIFIER"),
+  mdb_numeric: BackendType::new("NUMERIC").needs_precision().needs_scale(),
+  mdb_extended_datetime: BackendType::new("DATETIME2"),
+  mdb_complex: BackendType::new("INT"),
+  array_string: "NVARCHAR(MAX)",
+  quote_name: quote_name_brackets,
+
+  default_quote_str: "'",
+  default_null_str: "NULL",
+  create_table_string: "IF OBJECT_ID(N'{quoted_table_name}', N'U') IS NULL\n  create table {quoted_table_name} (",
+
+  column_string: "  {quoted_column_name} {column_type}",
+  create_view_string: "CREATE VIEW {quoted_view_name} AS",
+  batch_separator: "GO",
+  identity_string: "IDENTITY(1,1)",
+  uuid_default_string: "NEWID()",
+  generated_string: "",
+  table_comment_string: "EXEC sys.sp_addextendedproperty @name = N'MS_Description', @value = N{comment}, \
+                         @level0type = N'SCHEMA', @level0name = N{schema_literal}, \
+                         @level1type = N'TABLE', @level1name = N{table_literal};",
+  column_comment_string: "EXEC sys.sp_addextendedproperty @name = N'MS_Description', @value = N{comment}, \
+                          @level0type = N'SCHEMA', @level0name = N{schema_literal}, \
+                          @level1type = N'TABLE', @level1name = N{table_literal}, \
+                          @level2type = N'COLUMN', @level2name = N{column_literal};",
+  sql_dialect: SqlDialect::TSql,
+  default_schema: "dbo",
+};
+
+pub const CSV_BACKEND: Backend = Backend {
+  name: "csv",
+  mdb_bool: BackendType::new("bool"),
+  mdb_byte: BackendType::new("byte"),
+  mdb_int: BackendType::new("int"),
+  mdb_longint: BackendType::new("longint"),
+  mdb_money: BackendType::new("money"),
+  mdb_float: BackendType::new("float"),
+  mdb_double: BackendType::new("double"),
+  mdb_datetime: BackendType::new("datetime"),
+  mdb_binary: BackendType::new("binary").needs_byte_length(),
+  mdb_text: BackendType::new("text").needs_char_length(),
+  mdb_ole: BackendType::new("ole"),
+  mdb_memo: BackendType::new("memo"),
+  mdb_replication_id: BackendType::new("replication_id"),
+  mdb_numeric: BackendType::new("numeric").needs_precision().needs_scale(),
+  quote_name: quote_double_quotes,
+  mdb_extended_datetime: BackendType::new("DATETIME2"),
+  mdb_complex: BackendType::new("complex"),
+  array_string: "{column_type}[]",
+  create_table_string: "",
+  column_string: "",
+  create_view_string: "",
+  batch_separator: "",
+  identity_string: "",
+  uuid_default_string: "",
+  generated_string: "",
+  table_comment_string: "",
+  column_comment_string: "",
+  sql_dialect: SqlDialect::Access,
+  default_schema: "",
+  default_quote_str: "\"",
+  default_null_str: "",
+};
+
+/// The generated column clause for the Expression property of a calculated column, or a warning if it could not be
+/// translated, in which case the column only holds the stored results. Backends without generated columns have none.
+pub fn generated_column(column: &Column, properties: Option<&PropertyBlock>, backend: &Backend) -> Result<Option<String>, String> {
+  if backend.generated_string.is_empty() {
+    return Ok(None);
+  }
+  let Some(expression) = properties.and_then(|block| block.expression()) else {
+    return Ok(None);
+  };
+
+  match translate(expression, backend) {
+    Ok(translation) if translation.warnings.is_empty() => Ok(Some(backend.generated_string.replace("{expression}", &translation.sql))),
+    Ok(translation) => Err(format!("the expression of {} ({}) was skipped: {}", column.name, expression, translation.warnings.join("; "))),
+    Err(_) => Err(format!("the expression of {} ({}) could not be parsed", column.name, expression)),
+  }
+}
+
+/// Quote a string literal for SQL, doubling single quotes.
+pub fn quote_string_literal(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Format the values of a multivalued field as a Postgres array literal, such as `{"a","b"}`, which still needs to
+/// be quoted as a string.
+pub fn postgres_array_literal(values: &[Value]) -> String {
+  let elements: Vec<String> = values.iter()
+    .map(|value| match value {
+      Value::Null => "NULL".to_string(),
+      value => format!("\"{}\"", value.to_string().replace('\\', "\\\\").replace('"', "\\\"")),
+    })
+    .collect();
+  format!("{{{}}}", elements.join(","))
+}
+
+/// Double the quote char and surround with quote.
+pub fn quote_generic(name: &str, quote: &str, escape: &Option<String>) -> String {
+  let escape_string = if escape.is_some() {
+    escape.clone().unwrap() + quote
+  } else {
+    quote.to_owned() + quote
+  };
+
+  format!("{quote}{}{quote}", name.replace(quote, &escape_string))
+}
+
+// quoted_table_name
+// table_name
 // quoted_column_name
\ No newline at end of file
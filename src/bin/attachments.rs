@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{ColorChoice, Parser};
+
+use mdbtools::complex::Attachments;
+use mdbtools::database::Database;
+use mdbtools::error::MdbError;
+use mdbtools::utils::sanitize_file_name;
+
+/// Extract the files of attachment columns
+///
+/// Files are written to a directory for each table, then one for the primary key of the row, or the row number when
+/// the table has no primary key, keeping their original names. Tables with several attachment columns have a
+/// directory for each column under the row.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Table name. Default is every table with an attachment column.
+  #[arg(short = 'T', long = "table")]
+  table: Option<String>,
+
+  /// Path to file.
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Directory to write the files to. Default is the current directory.
+  #[arg(short = 'o', long, value_name = "DIR", default_value = ".")]
+  output: PathBuf,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let mut database = match Database::open(args.file.clone()) {
+    Ok(database) => database,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    }
+  };
+
+  if let Some(table) = &args.table && let Err(err) = database.table(table) {
+    match err {
+      MdbError::TableNotFound => println!("Table not found."),
+      _ => eprintln!("Could not read table."),
+    }
+    return ExitCode::FAILURE;
+  }
+
+  let complex_columns = match database.complex_columns() {
+    Ok(complex_columns) => complex_columns.to_vec(),
+    Err(_) => {
+      eprintln!("Could not read complex columns.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  // The attachment columns of each table, in the order of MSysComplexColumns.
+  let mut tables: Vec<(String, Vec<(String, Attachments)>)> = Vec::new();
+  for complex_column in complex_columns {
+    if args.table.as_ref().is_some_and(|table| !table.eq_ignore_ascii_case(&complex_column.table)) {
+      continue;
+    }
+    let attachments = match database.attachments(&complex_column.table, &complex_column.name) {
+      Ok(attachments) => attachments,
+      // Multivalued and version history columns.
+      Err(MdbError::UnhandledType) => continue,
+      Err(_) => {
+        eprintln!("Could not read attachments of {}.{}.", complex_column.table, complex_column.name);
+        return ExitCode::FAILURE;
+      }
+    };
+    match tables.iter_mut().find(|(table, _)| *table == complex_column.table) {
+      Some((_, columns)) => columns.push((complex_column.name, attachments)),
+      None => tables.push((complex_column.table, vec![(complex_column.name, attachments)])),
+    }
+  }
+
+  for (table_name, mut columns) in tables {
+    let mut table = match database.table(&table_name) {
+      Ok(table) => table,
+      Err(_) => {
+        eprintln!("Could not read table.");
+        return ExitCode::FAILURE;
+      }
+    };
+
+    let with_column_directory = columns.len() > 1;
+    let column_indexes: Vec<Option<usize>> = columns.iter().map(|(name, _)| table.find_column_index(name)).collect();
+
+    let mut number = 0;
+    loop {
+      match table.fetch_row() {
+        Ok(()) => number += 1,
+        Err(MdbError::NoFreePages) => break,
+        Err(_) => {
+          eprintln!("Could not read row.");
+          return ExitCode::FAILURE;
+        }
+      }
+      let key = table.row_key(number);
+
+      for ((name, attachments), index) in columns.iter_mut().zip(&column_indexes) {
+        let Some(complex_value) = index.and_then(|index| table.columns[index].complex_value()) else {
+          continue;
+        };
+        let files = match attachments.get(complex_value) {
+          Ok(files) => files,
+          Err(_) => {
+            eprintln!("Could not read attachments of {}.{}.", table_name, name);
+            return ExitCode::FAILURE;
+          }
+        };
+
+        let mut directory = args.output.join(sanitize_file_name(&table_name)).join(sanitize_file_name(&key));
+        if with_column_directory {
+          directory = directory.join(sanitize_file_name(name));
+        }
+        if !files.is_empty() && let Err(_err) = fs::create_dir_all(&directory) {
+          eprintln!("Could not create {}.", directory.display());
+          return ExitCode::FAILURE;
+        }
+
+        for file in files {
+          let path = directory.join(sanitize_file_name(&file.file_name));
+          if let Err(_err) = fs::write(&path, &file.data) {
+            eprintln!("Could not write {}.", path.display());
+            return ExitCode::FAILURE;
+          }
+          println!("{}", path.display());
+        }
+      }
+    }
+  }
+
+  ExitCode::SUCCESS
+}
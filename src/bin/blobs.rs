@@ -0,0 +1,141 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{ColorChoice, Parser};
+
+use mdbtools::column::{Column, ColumnType};
+use mdbtools::database::Database;
+use mdbtools::error::MdbError;
+use mdbtools::ole::{detect_extension, OleObjectReader};
+use mdbtools::utils::sanitize_file_name;
+
+/// Extract the OLE and binary values of a table into files
+///
+/// Files are named by the primary key of the row and the column, or by the row number when the table has no primary
+/// key. OLE objects are unwrapped to the file they embed, keeping its original name when it has one. Values are
+/// streamed from the database, so they needn't fit in memory.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Table name.
+  #[arg(short = 'T', long = "table")]
+  table: String,
+
+  /// Path to file.
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Directory to write the files to. Default is the current directory.
+  #[arg(short = 'o', long, value_name = "DIR", default_value = ".")]
+  output: PathBuf,
+
+  /// Write OLE objects as stored, without removing the Access header.
+  #[arg(long, default_value_t = false)]
+  raw: bool,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let mut database = match Database::open(args.file.clone()) {
+    Ok(database) => database,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let mut table = match database.table(&args.table) {
+    Ok(table) => table,
+    Err(MdbError::TableNotFound) => {
+      println!("Table not found.");
+      return ExitCode::FAILURE;
+    }
+    Err(_) => {
+      eprintln!("Could not read table.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let blob_columns: Vec<usize> = table.columns.iter()
+    .enumerate()
+    .filter(|(_, column)| column.column_type == ColumnType::OLE || column.column_type == ColumnType::Binary)
+    .map(|(index, _)| index)
+    .collect();
+
+  if let Err(_err) = fs::create_dir_all(&args.output) {
+    eprintln!("Could not create output directory.");
+    return ExitCode::FAILURE;
+  }
+
+  let mut number = 0;
+  loop {
+    match table.fetch_row() {
+      Ok(()) => number += 1,
+      Err(MdbError::NoFreePages) => break,
+      Err(_) => {
+        eprintln!("Could not read row.");
+        return ExitCode::FAILURE;
+      }
+    }
+    let key = table.row_key(number);
+
+    for &index in &blob_columns {
+      let column = &table.columns[index];
+      if column.buffer.is_null {
+        continue;
+      }
+      let prefix = format!("{}-{}", key, column.name);
+
+      let written = match column.column_type {
+        ColumnType::OLE => write_ole_value(column, &prefix, &args),
+        // Binary values are stored in the row.
+        _ => {
+          let bytes = &column.buffer.value;
+          let path = args.output.join(sanitize_file_name(&format!("{}.{}", prefix, detect_extension(bytes).unwrap_or("bin"))));
+          fs::write(&path, bytes).map(|_| path).map_err(MdbError::from)
+        }
+      };
+      match written {
+        Ok(path) => println!("{}", path.display()),
+        Err(_err) => {
+          eprintln!("Could not write {}.", prefix);
+          return ExitCode::FAILURE;
+        }
+      }
+    }
+  }
+
+  ExitCode::SUCCESS
+}
+
+/// Stream the OLE value of the current row to a file, unwrapping the file it embeds unless `--raw` is given.
+fn write_ole_value(column: &Column, prefix: &str, args: &Args) -> Result<PathBuf, MdbError> {
+  if !args.raw {
+    match OleObjectReader::new(column.long_value_reader()?) {
+      Ok(mut reader) => {
+        let file_name = match (&reader.object().file_name, reader.extension()) {
+          (Some(file_name), _) => format!("{}-{}", prefix, file_name),
+          (None, Some(extension)) => format!("{}.{}", prefix, extension),
+          (None, None) => format!("{}.bin", prefix),
+        };
+        return copy_to_file(&mut reader, args.output.join(sanitize_file_name(&file_name)));
+      }
+      // Access also stores plain binary data in OLE columns.
+      Err(MdbError::InvalidOleObject) => {}
+      Err(err) => return Err(err),
+    }
+  }
+
+  let mut reader = column.long_value_reader()?;
+  let mut head = Vec::new();
+  (&mut reader).take(16).read_to_end(&mut head)?;
+  let path = args.output.join(sanitize_file_name(&format!("{}.{}", prefix, detect_extension(&head).unwrap_or("bin"))));
+  copy_to_file(&mut head.chain(reader), path)
+}
+
+fn copy_to_file(reader: &mut impl Read, path: PathBuf) -> Result<PathBuf, MdbError> {
+  io::copy(reader, &mut File::create(&path)?)?;
+  Ok(path)
+}
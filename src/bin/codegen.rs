@@ -0,0 +1,325 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{ColorChoice, Parser};
+
+use mdbtools::column::ColumnType;
+use mdbtools::complex::ComplexColumn;
+use mdbtools::database::Database;
+use mdbtools::property::ObjectProperties;
+use mdbtools::table::Table;
+
+/// Generate Rust structs and ORM models from the tables of an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Path to file.
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Table name. Default is every user table.
+  #[arg(short = 'T', long)]
+  table: Option<String>,
+
+  /// What to generate: serde structs, sqlx FromRow structs, a Diesel schema with Queryable structs, or SeaORM
+  /// entities. Default is serde.
+  #[arg(short = 't', long, default_value_t = String::from("serde"))]
+  target: String,
+}
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+enum Target {
+  Serde,
+  Sqlx,
+  Diesel,
+  SeaOrm,
+}
+
+const TARGETS: [(&str, Target); 4] = [("serde", Target::Serde), ("sqlx", Target::Sqlx), ("diesel", Target::Diesel), ("sea-orm", Target::SeaOrm)];
+
+/// A column as it appears in the generated code.
+struct Field {
+  column_name: String,
+  name: String,
+  column_type: ColumnType,
+  /// The type of the values of a multivalued column, which is a list of them.
+  element_type: Option<ColumnType>,
+  optional: bool,
+  auto_increment: bool,
+  primary_key: bool,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let target_name = args.target.to_lowercase();
+  let Some(&(_, target)) = TARGETS.iter().find(|(name, _)| *name == target_name) else {
+    eprintln!("Target not found.");
+    return ExitCode::FAILURE;
+  };
+
+  let mut database = match Database::open(args.file.clone()) {
+    Ok(database) => database,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let names: Vec<String> = match &args.table {
+    Some(table) => vec![table.clone()],
+    None => match database.tables() {
+      Ok(tables) => tables.iter().map(|object| object.name.clone()).collect(),
+      Err(_) => {
+        eprintln!("Error reading system table. Exiting.");
+        return ExitCode::FAILURE;
+      }
+    },
+  };
+
+  let complex_columns = match database.complex_columns() {
+    Ok(complex_columns) => complex_columns.to_vec(),
+    Err(_) => {
+      eprintln!("Could not read complex columns.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let mut output: Vec<String> = Vec::new();
+  for name in names {
+    let (table, properties) = match (database.table(&name), database.table_properties(&name)) {
+      (Ok(table), Ok(properties)) => (table, properties),
+      _ => {
+        eprintln!("Could not read table {}.", name);
+        return ExitCode::FAILURE;
+      }
+    };
+
+    let fields = fields(&table, &properties, &complex_columns);
+    output.push(match target {
+      Target::Serde | Target::Sqlx => generate_struct(&table, &fields, target),
+      Target::Diesel => generate_diesel(&table, &fields),
+      Target::SeaOrm => generate_sea_orm(&table, &fields),
+    });
+  }
+
+  print!("{}", output.join("\n"));
+  ExitCode::SUCCESS
+}
+
+fn fields(table: &Table, properties: &ObjectProperties, complex_columns: &[ComplexColumn]) -> Vec<Field> {
+  let primary_key: Vec<&str> = table.indexes.iter()
+    .filter(|index| index.is_primary_key())
+    .flat_map(|index| index.columns.iter().map(|column| column.name.as_str()))
+    .collect();
+
+  let mut names: Vec<String> = Vec::new();
+  let mut fields = Vec::new();
+  for column in &table.columns {
+    // Names that collide once converted are numbered.
+    let mut name = field_name(&column.name);
+    let mut suffix = 2;
+    while names.contains(&name) {
+      name = format!("{}_{}", field_name(&column.name), suffix);
+      suffix += 1;
+    }
+    names.push(name.clone());
+
+    let element_type = complex_columns.iter()
+      .find(|complex_column| complex_column.table == table.name && complex_column.name == column.name)
+      .and_then(ComplexColumn::value_column)
+      .map(|value_column| value_column.column_type);
+    fields.push(Field {
+      column_name: column.name.clone(),
+      name,
+      column_type: column.column_type,
+      element_type,
+      optional: column.column_type != ColumnType::Bool && !table.is_required(column, properties),
+      auto_increment: column.flags.is_long_auto(),
+      primary_key: primary_key.contains(&column.name.as_str()),
+    });
+  }
+  fields
+}
+
+/// The Rust type of a column. Diesel reads numerics as `BigDecimal`, and only serde can read unsigned bytes. Complex
+/// columns other than multivalued ones hold the id of their values.
+fn rust_type(column_type: ColumnType, target: Target) -> &'static str {
+  match column_type {
+    ColumnType::Bool => "bool",
+    ColumnType::Byte if target == Target::Serde => "u8",
+    ColumnType::Byte | ColumnType::Int => "i16",
+    ColumnType::LongInt | ColumnType::Complex => "i32",
+    ColumnType::Money | ColumnType::Numeric if target == Target::Diesel => "bigdecimal::BigDecimal",
+    ColumnType::Money | ColumnType::Numeric => "rust_decimal::Decimal",
+    ColumnType::Float => "f32",
+    ColumnType::Double => "f64",
+    ColumnType::Datetime | ColumnType::ExtendedDatetime => "chrono::NaiveDateTime",
+    ColumnType::Binary | ColumnType::OLE => "Vec<u8>",
+    ColumnType::Text | ColumnType::Memo => "String",
+    ColumnType::ReplicationId => "uuid::Uuid",
+  }
+}
+
+fn diesel_type(column_type: ColumnType) -> &'static str {
+  match column_type {
+    ColumnType::Bool => "Bool",
+    ColumnType::Byte | ColumnType::Int => "SmallInt",
+    ColumnType::LongInt | ColumnType::Complex => "Integer",
+    ColumnType::Money | ColumnType::Numeric => "Numeric",
+    ColumnType::Float => "Float",
+    ColumnType::Double => "Double",
+    ColumnType::Datetime | ColumnType::ExtendedDatetime => "Timestamp",
+    ColumnType::Binary | ColumnType::OLE => "Binary",
+    ColumnType::Text | ColumnType::Memo => "Text",
+    ColumnType::ReplicationId => "Uuid",
+  }
+}
+
+fn field_type(field: &Field, target: Target) -> String {
+  let rust_type = match field.element_type {
+    Some(element_type) => format!("Vec<{}>", rust_type(element_type, target)),
+    None => rust_type(field.column_type, target).to_string(),
+  };
+  if field.optional { format!("Option<{}>", rust_type) } else { rust_type }
+}
+
+/// The Diesel type of a field. Multivalued columns are arrays, as Postgres stores them.
+fn diesel_field_type(field: &Field) -> String {
+  let sql_type = match field.element_type {
+    Some(element_type) => format!("Array<{}>", diesel_type(element_type)),
+    None => diesel_type(field.column_type).to_string(),
+  };
+  if field.optional { format!("Nullable<{}>", sql_type) } else { sql_type }
+}
+
+/// The primary key fields, or the first field when the table has no primary key.
+fn key_fields(fields: &[Field]) -> Vec<&Field> {
+  let keys: Vec<&Field> = fields.iter().filter(|field| field.primary_key).collect();
+  if keys.is_empty() { fields.iter().take(1).collect() } else { keys }
+}
+
+fn generate_struct(table: &Table, fields: &[Field], target: Target) -> String {
+  let (derives, attribute) = match target {
+    Target::Sqlx => ("Debug, Clone, PartialEq, sqlx::FromRow", "sqlx"),
+    _ => ("Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize", "serde"),
+  };
+
+  let mut code = format!("/// The {:?} table.\n#[derive({})]\npub struct {} {{\n", table.name, derives, type_name(&table.name));
+  for field in fields {
+    if field.name.trim_start_matches("r#") != field.column_name {
+      code.push_str(&format!("    #[{}(rename = {:?})]\n", attribute, field.column_name));
+    }
+    code.push_str(&format!("    pub {}: {},\n", field.name, field_type(field, target)));
+  }
+  code.push_str("}\n");
+  code
+}
+
+fn generate_diesel(table: &Table, fields: &[Field]) -> String {
+  let module = field_name(&table.name);
+  let keys: Vec<&str> = key_fields(fields).iter().map(|field| field.name.as_str()).collect();
+
+  let mut code = String::from("diesel::table! {\n");
+  if module != table.name {
+    code.push_str(&format!("    #[sql_name = {:?}]\n", table.name));
+  }
+  code.push_str(&format!("    {} ({}) {{\n", module, keys.join(", ")));
+  for field in fields {
+    if field.name.trim_start_matches("r#") != field.column_name {
+      code.push_str(&format!("        #[sql_name = {:?}]\n", field.column_name));
+    }
+    code.push_str(&format!("        {} -> {},\n", field.name, diesel_field_type(field)));
+  }
+  code.push_str("    }\n}\n\n");
+
+  code.push_str(&format!("/// The {:?} table.\n", table.name));
+  code.push_str("#[derive(Debug, Clone, PartialEq, diesel::Queryable, diesel::Selectable)]\n");
+  code.push_str(&format!("#[diesel(table_name = {})]\n", module));
+  code.push_str(&format!("pub struct {} {{\n", type_name(&table.name)));
+  for field in fields {
+    code.push_str(&format!("    pub {}: {},\n", field.name, field_type(field, Target::Diesel)));
+  }
+  code.push_str("}\n");
+  code
+}
+
+fn generate_sea_orm(table: &Table, fields: &[Field]) -> String {
+  let keys = key_fields(fields);
+
+  let mut code = format!("/// The {:?} table.\npub mod {} {{\n", table.name, field_name(&table.name));
+  code.push_str("    use sea_orm::entity::prelude::*;\n\n");
+  code.push_str("    #[derive(Debug, Clone, PartialEq, DeriveEntityModel)]\n");
+  code.push_str(&format!("    #[sea_orm(table_name = {:?})]\n", table.name));
+  code.push_str("    pub struct Model {\n");
+  for field in fields {
+    let mut attributes: Vec<String> = Vec::new();
+    if keys.iter().any(|key| key.name == field.name) {
+      attributes.push("primary_key".to_string());
+      if !field.auto_increment {
+        attributes.push("auto_increment = false".to_string());
+      }
+    }
+    if field.name.trim_start_matches("r#") != field.column_name {
+      attributes.push(format!("column_name = {:?}", field.column_name));
+    }
+    if !attributes.is_empty() {
+      code.push_str(&format!("        #[sea_orm({})]\n", attributes.join(", ")));
+    }
+    code.push_str(&format!("        pub {}: {},\n", field.name, field_type(field, Target::SeaOrm)));
+  }
+  code.push_str("    }\n\n");
+  code.push_str("    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]\n    pub enum Relation {}\n\n");
+  code.push_str("    impl ActiveModelBehavior for ActiveModel {}\n}\n");
+  code
+}
+
+/// Convert a column or table name to a snake_case identifier, so "Asset No" and "AssetNo" both become `asset_no`.
+fn field_name(name: &str) -> String {
+  let mut snake = String::new();
+  let mut previous: Option<char> = None;
+  for c in name.chars() {
+    if c.is_alphanumeric() {
+      if c.is_uppercase() && previous.is_some_and(|previous| previous.is_lowercase() || previous.is_ascii_digit()) {
+        snake.push('_');
+      }
+      snake.extend(c.to_lowercase());
+      previous = Some(c);
+    } else {
+      if !snake.is_empty() && !snake.ends_with('_') {
+        snake.push('_');
+      }
+      previous = None;
+    }
+  }
+  let snake = snake.trim_end_matches('_');
+
+  if snake.is_empty() {
+    "field".to_string()
+  } else if snake.starts_with(|c: char| c.is_ascii_digit()) {
+    format!("_{}", snake)
+  } else if ["self", "super", "crate"].contains(&snake) {
+    format!("{}_", snake)
+  } else if KEYWORDS.contains(&snake) {
+    format!("r#{}", snake)
+  } else {
+    snake.to_string()
+  }
+}
+
+/// Convert a table name to a PascalCase type name.
+fn type_name(name: &str) -> String {
+  let name = field_name(name);
+  let name = name.trim_start_matches("r#").trim_end_matches('_');
+  let pascal: String = name.split('_').map(|word| {
+    let mut chars = word.chars();
+    chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+  }).collect();
+  if pascal.starts_with(|c: char| c.is_ascii_digit()) { format!("Table{}", pascal) } else { pascal }
+}
+
+const KEYWORDS: [&str; 48] = [
+  "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+  "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move",
+  "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof",
+  "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
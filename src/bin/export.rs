@@ -1,228 +1,471 @@
-use std::path::PathBuf;
-use std::process::ExitCode;
-
-use clap::{ColorChoice, Parser};
-
-use mdbtools;
-use mdbtools::backend;
-use mdbtools::backend::Backend;
-use mdbtools::catalog::{read_catalog, CatalogEntry, TableCatalogEntry};
-use mdbtools::column::ColumnType;
-use mdbtools::mdbfile::Mdb;
-use mdbtools::table::Table;
-
-/// Get listing of tables in an MDB database
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
-struct Args {
-  /// Suppress header row.
-  #[arg(short = 'H', long, default_value_t = false)]
-  no_header: bool,
-
-  /// Add types to header.
-  #[arg(long, default_value_t = false)]
-  types: bool,
-
-  /// Specify an alternative column delimiter. Default is comma.
-  #[arg(short = 'd', long, default_value_t = String::from(","))]
-  delimiter: String,
-
-  /// Table name.
-  #[arg(short = 'T', long = "table")]
-  table: String,
-
-  /// Path to file.
-  #[arg(short, long, value_name = "FILE")]
-  file: PathBuf,
-
-  /// Don't wrap text-like fields in quotes.
-  #[arg(short = 'Q', long, default_value_t = false)]
-  no_quote: bool,
-
-  /// Specify an alternative quote. The backend is consulted for the default.
-  #[arg(short = 'q', long)]
-  quote: Option<String>,
-
-  /// Backend. Default is CSV.
-  #[arg(short = 'b', long, default_value_t = String::from("csv"))]
-  backend: String,
-
-  /// Null specifier. Default is backend specific.
-  #[arg(short = 'n', long)]
-  null: Option<String>,
-
-  /// Schema/namespace. Default is "".
-  #[arg(short = 's', long, default_value_t = String::from(""))]
-  schema: String,
-
-  /// Escape quoted characters within a field. The default approach is to double thq quote string.
-  #[arg(short = 'X', long)]
-  escape: Option<String>,
-}
-
-pub fn main() -> ExitCode {
-  let args = Args::parse();
-
-  let mut mdb = match Mdb::open(args.file.clone()) {
-    Ok(mdb) => mdb,
-    Err(_err) => {
-      return ExitCode::FAILURE;
-    }
-  };
-
-  let catalog = match read_catalog(&mut mdb) {
-    Ok(row) => row,
-    Err(_) => {
-      println!("Error reading system table. Exiting.");
-      return ExitCode::FAILURE;
-    }
-  };
-
-  let mut table_catalog_entry: Option<TableCatalogEntry> = None;
-  for catalog_entry in catalog {
-    match catalog_entry {
-      CatalogEntry::Table(table) => {
-        if table.name.eq(&args.table) {
-          table_catalog_entry = Some(table);
-          break;
-        }
-      }
-    }
-  };
-
-  if table_catalog_entry.is_none() {
-    println!("Table not found.");
-    return ExitCode::FAILURE;
-  }
-
-  let table_catalog_entry = table_catalog_entry.unwrap();
-  let mut table = Table::from_catalog_entry(CatalogEntry::Table(table_catalog_entry), &mut mdb).expect("Could not read table.");
-  match table.read_columns() {
-    Ok(_) => {},
-    Err(_) => {
-      eprintln!("Could not read columns.");
-      return ExitCode::FAILURE;
-    }
-  }
-
-  let backend_name = args.backend.to_lowercase();
-  let backends: Vec<Backend> = vec![backend::CSV_BACKEND, backend::MSSQL_BACKEND, backend::POSTGRES_BACKEND];
-
-  let mut backend: Backend = backend::CSV_BACKEND;
-  for (index, cur_backend) in backends.iter().enumerate() {
-    if backend_name.eq(&cur_backend.name.to_lowercase()) {
-      backend = cur_backend.clone();
-      break;
-    }
-    if index + 1 == backends.len() {
-      eprintln!("Backend not found.");
-      return ExitCode::FAILURE;
-    }
-  };
-
-  if backend == backend::CSV_BACKEND {
-    print_header(&args, &mut table);
-  } else {
-    if table.row_count == 0 {
-      return ExitCode::SUCCESS;
-    }
-
-    print!("INSERT INTO {} (", (backend.quote_name)(&table.name));
-
-    for (index, col) in (&table.columns).into_iter().enumerate() {
-      if index != 0 {
-        print!(", ");
-      }
-
-      print!("{}", (backend.quote_name)(&col.name));
-    }
-    println!(")\nVALUES");
-  }
-
-  let null = args.null.unwrap_or(backend.default_null_str.to_string());
-  let mut first = true;
-  loop {
-    let _result = match table.fetch_row() {
-      Ok(row) => row,
-      Err(_) => {
-        // If there is a problem, return the known good values.
-        break;
-      }
-    };
-
-    if backend != backend::CSV_BACKEND {
-      if !first {
-        print!("),\n  (");
-      } else {
-        print!("  (");
-      }
-    }
-
-    first = false;
-
-    for (index, col) in (&table.columns).into_iter().enumerate() {
-      if index != 0 {
-        print!(",");
-      }
-
-      let mut is_null = false;
-      let mut col_string = match col.buffer.is_null {
-        true => {
-          match col.column_type {
-            ColumnType::Bool => col.to_string(),
-            _ => {
-              is_null = true;
-              null.clone()
-            }
-          }
-        }
-        false => col.to_string()
-      };
-
-      let should_quote = !is_null && should_quote(col.column_type);
-      if should_quote && !args.no_quote {
-        col_string = backend::quote_generic(&col_string, backend.default_quote_str, &args.escape);
-      }
-      // TODO: format binary literal.
-      print!("{}", col_string);
-
-      if index == table.columns.len() - 1 {
-        if backend != backend::CSV_BACKEND {
-        } else {
-          println!();
-        }
-      }
-    }
-  }
-
-  if backend != backend::CSV_BACKEND {
-    println!(");");
-  }
-
-  ExitCode::SUCCESS
-}
-
-fn print_header(args: &Args, table: &mut Table) {
-  if !args.no_header {
-    for (index, col) in (&table.columns).into_iter().enumerate() {
-      print!("{}", col.name);
-      if args.types {
-        print!("({}", col.column_type);
-        if col.is_hyperlink {
-          print!("/Hyperlink");
-        }
-        print!(")");
-      }
-
-      if index == table.columns.len() - 1 {
-        println!();
-      } else {
-        print!(",");
-      }
-    }
-  }
-}
-
-fn should_quote(column_type: ColumnType) -> bool {
-  matches!(column_type, ColumnType::Text | ColumnType::Bool | ColumnType::Memo | ColumnType::Datetime | ColumnType::ExtendedDatetime)
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{ColorChoice, Parser};
+
+use mdbtools::backend;
+use mdbtools::backend::{Backend, SqlDialect};
+use mdbtools::column::{Column, ColumnType};
+use mdbtools::complex::{ComplexColumn, ComplexValues, MemoHistory, MemoVersion};
+use mdbtools::database::Database;
+use mdbtools::error::MdbError;
+use mdbtools::predicate::Predicate;
+use mdbtools::property::ObjectProperties;
+use mdbtools::table::Table;
+use mdbtools::value::Value;
+
+/// Get listing of tables in an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Suppress header row.
+  #[arg(short = 'H', long, default_value_t = false)]
+  no_header: bool,
+
+  /// Add types to header.
+  ///
+  /// Each type is followed by the flags of the column, such as nullable or hyperlink, separated by slashes.
+  #[arg(long, default_value_t = false)]
+  types: bool,
+
+  /// Specify an alternative column delimiter. Default is comma.
+  #[arg(short = 'd', long, default_value_t = String::from(","))]
+  delimiter: String,
+
+  /// Table name.
+  #[arg(short = 'T', long = "table")]
+  table: String,
+
+  /// Path to file.
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Don't wrap text-like fields in quotes.
+  #[arg(short = 'Q', long, default_value_t = false)]
+  no_quote: bool,
+
+  /// Specify an alternative quote. The backend is consulted for the default.
+  #[arg(short = 'q', long)]
+  quote: Option<String>,
+
+  /// Backend. Default is CSV.
+  #[arg(short = 'b', long, default_value_t = String::from("csv"))]
+  backend: String,
+
+  /// Null specifier. Default is backend specific.
+  #[arg(short = 'n', long)]
+  null: Option<String>,
+
+  /// Schema/namespace. Default is "".
+  #[arg(short = 's', long, default_value_t = String::from(""))]
+  schema: String,
+
+  /// Escape quoted characters within a field. The default approach is to double thq quote string.
+  #[arg(short = 'X', long)]
+  escape: Option<String>,
+
+  /// Use the Caption property of columns in the header row. Columns without a caption keep their name.
+  #[arg(long, default_value_t = false)]
+  captions: bool,
+
+  /// Only export these columns, in this order. Separate names with commas.
+  #[arg(short = 'c', long, value_delimiter = ',')]
+  columns: Vec<String>,
+
+  /// Only export rows matching an Access SQL condition, such as "[Owner] = 'Sales' AND Acquired > #1/1/1997#".
+  #[arg(short = 'w', long = "where", value_name = "CONDITION")]
+  condition: Option<String>,
+
+  /// Export complex columns as child tables rather than arrays.
+  ///
+  /// The values of multivalued, attachment and version history fields are inserted into a table named after the
+  /// table and the column, keyed by the primary key of the table, after the rows of the table. See mdb-schema
+  /// --normalize-complex. Needs a SQL backend.
+  #[arg(long, default_value_t = false)]
+  normalize_complex: bool,
+
+  /// Export every version of an append only memo column.
+  ///
+  /// Rows are repeated for each version of the memo, oldest first, followed by a COLUMN_Modified column with the
+  /// date of the version. Rows without a history are exported once with their current value. Only the CSV backend
+  /// exports memo history; with a SQL backend, use --normalize-complex.
+  #[arg(long, value_name = "COLUMN", conflicts_with = "normalize_complex")]
+  memo_history: Option<String>,
+
+  /// Keep calculated columns in INSERTs, for tables created with mdb-schema --no-generated.
+  ///
+  /// Otherwise calculated columns are left out wherever mdb-schema turns them into generated columns, which can't be
+  /// inserted into.
+  #[arg(long, default_value_t = false)]
+  no_generated: bool,
+}
+
+/// An append only memo exported once for each of its versions.
+struct MemoVersions {
+  history: MemoHistory,
+  /// The position of the memo in the table.
+  memo: usize,
+  /// The position of the version history column in the table, which is left out of the export.
+  column: usize,
+}
+
+/// A complex column exported as a child table.
+struct ChildTable {
+  complex_column: ComplexColumn,
+  /// The position of the column in the table.
+  column: usize,
+  values: Arc<ComplexValues>,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let mut database = match Database::open(args.file.clone()) {
+    Ok(database) => database,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let mut table = match database.table(&args.table) {
+    Ok(table) => table,
+    Err(MdbError::TableNotFound) => {
+      println!("Table not found.");
+      return ExitCode::FAILURE;
+    }
+    Err(_) => {
+      eprintln!("Could not read table.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  if !args.columns.is_empty() && let Err(err) = table.select(&args.columns) {
+    match err {
+      MdbError::ColumnNotFound => eprintln!("Column not found."),
+      _ => eprintln!("Could not read columns."),
+    }
+    return ExitCode::FAILURE;
+  }
+
+  if let Some(condition) = &args.condition {
+    let predicate = match Predicate::parse(condition) {
+      Ok(predicate) => predicate,
+      Err(_) => {
+        eprintln!("Invalid condition.");
+        return ExitCode::FAILURE;
+      }
+    };
+    if let Err(err) = table.filter(predicate) {
+      match err {
+        MdbError::ColumnNotFound => eprintln!("Column not found."),
+        _ => eprintln!("Could not read columns."),
+      }
+      return ExitCode::FAILURE;
+    }
+  }
+
+  let memo_versions = match &args.memo_history {
+    Some(column) => {
+      let history = match database.memo_history(&args.table, column) {
+        Ok(history) => history,
+        Err(err) => {
+          match err {
+            MdbError::ColumnNotFound => eprintln!("Column not found."),
+            MdbError::UnhandledType => eprintln!("{} is not an append only memo.", column),
+            _ => eprintln!("Could not read memo history."),
+          }
+          return ExitCode::FAILURE;
+        }
+      };
+      let memo = table.find_column_index(column).unwrap();
+      let history_column = table.find_column_index(history.column()).unwrap();
+      if !table.selected_columns().any(|col| col.name == table.columns[memo].name) {
+        eprintln!("The memo must be exported to export its history.");
+        return ExitCode::FAILURE;
+      }
+      // The version history column is read, but not exported.
+      if !args.columns.is_empty() && !table.selected_columns().any(|col| col.name == history.column()) {
+        let mut columns = args.columns.clone();
+        columns.push(history.column().to_string());
+        if table.select(&columns).is_err() {
+          eprintln!("Could not read columns.");
+          return ExitCode::FAILURE;
+        }
+      }
+      Some(MemoVersions { history, memo, column: history_column })
+    }
+    None => None,
+  };
+  // Columns which are read, but not exported.
+  let mut hidden_columns: Vec<String> = memo_versions.iter()
+    .map(|memo_versions| table.columns[memo_versions.column].name.clone())
+    .collect();
+  let memo_name = memo_versions.as_ref().map(|memo_versions| table.columns[memo_versions.memo].name.clone());
+
+  let backend_name = args.backend.to_lowercase();
+  let backends: Vec<Backend> = vec![backend::CSV_BACKEND, backend::MSSQL_BACKEND, backend::POSTGRES_BACKEND];
+
+  let mut backend: Backend = backend::CSV_BACKEND;
+  for (index, cur_backend) in backends.iter().enumerate() {
+    if backend_name.eq(&cur_backend.name.to_lowercase()) {
+      backend = *cur_backend;
+      break;
+    }
+    if index + 1 == backends.len() {
+      eprintln!("Backend not found.");
+      return ExitCode::FAILURE;
+    }
+  };
+  // No table created by mdb-schema has the extra column, so SQL backends export the history as a child table.
+  if memo_versions.is_some() && backend != backend::CSV_BACKEND {
+    eprintln!("Memo history can only be exported to CSV. Use --normalize-complex with a SQL backend.");
+    return ExitCode::FAILURE;
+  }
+
+  if backend == backend::CSV_BACKEND {
+    let properties = if args.captions {
+      match database.table_properties(&args.table) {
+        Ok(properties) => properties,
+        Err(_) => {
+          eprintln!("Could not read column captions.");
+          return ExitCode::FAILURE;
+        }
+      }
+    } else {
+      ObjectProperties::default()
+    };
+    print_header(&args, &mut table, &properties, &hidden_columns, memo_name.as_deref());
+  } else if table.row_count == 0 {
+    return ExitCode::SUCCESS;
+  } else if !args.no_generated {
+    let properties = match database.table_properties(&args.table) {
+      Ok(properties) => properties,
+      Err(_) => {
+        eprintln!("Could not read column properties.");
+        return ExitCode::FAILURE;
+      }
+    };
+    // The same calculated columns as mdb-schema makes generated columns.
+    hidden_columns.extend(table.selected_columns()
+      .filter(|col| col.flags.is_calculated())
+      .filter(|col| matches!(backend::generated_column(col, properties.column(&col.name), &backend), Ok(Some(_))))
+      .map(|col| col.name.clone()));
+  }
+
+  let key_columns: Vec<usize> = table.indexes.iter()
+    .find(|index| index.is_primary_key())
+    .map(|index| index.columns.iter().filter_map(|column| table.find_column_index(&column.name)).collect())
+    .unwrap_or_default();
+  let mut children: Vec<ChildTable> = Vec::new();
+  if args.normalize_complex {
+    if backend == backend::CSV_BACKEND {
+      eprintln!("Complex columns can only be normalized with a SQL backend.");
+      return ExitCode::FAILURE;
+    }
+    let selected: Vec<String> = table.selected_columns().map(|col| col.name.clone()).collect();
+    if key_columns.is_empty() || key_columns.iter().any(|column| !selected.contains(&table.columns[*column].name)) {
+      eprintln!("The primary key must be exported to normalize complex columns.");
+      return ExitCode::FAILURE;
+    }
+    for col in table.selected_columns().filter(|col| col.column_type == ColumnType::Complex) {
+      let complex_column = database.complex_columns().ok()
+        .and_then(|complex_columns| complex_columns.iter().find(|complex_column| Some(complex_column.complex_id) == col.complex_id).cloned());
+      let (Some(complex_column), Ok(values)) = (complex_column, database.complex_values(&args.table, &col.name)) else {
+        eprintln!("Could not read complex column {}.", col.name);
+        return ExitCode::FAILURE;
+      };
+      children.push(ChildTable { complex_column, column: table.find_column_index(&col.name).unwrap(), values });
+    }
+  }
+
+  let null = args.null.clone().unwrap_or(backend.default_null_str.to_string());
+  let mut first = true;
+  // If there is a problem, return the known good values.
+  while table.fetch_row().is_ok() {
+
+    // The INSERT is only started once a row is found, as a filter may match none.
+    if backend != backend::CSV_BACKEND {
+      if !first {
+        print!("),\n  (");
+      } else {
+        print_insert(&table, &backend, args.normalize_complex, &hidden_columns);
+        print!("  (");
+      }
+    }
+
+    first = false;
+
+    // Each version of the memo history is a row, or the row itself when the memo has no history.
+    let versions: Vec<Option<MemoVersion>> = match &memo_versions {
+      Some(memo_versions) => {
+        let versions = match table.columns[memo_versions.column].complex_value() {
+          Some(complex_value) => match memo_versions.history.get(complex_value) {
+            Ok(versions) => versions,
+            Err(_) => {
+              eprintln!("Could not read memo history.");
+              return ExitCode::FAILURE;
+            }
+          },
+          None => Vec::new(),
+        };
+        if versions.is_empty() { vec![None] } else { versions.into_iter().map(Some).collect() }
+      }
+      None => vec![None],
+    };
+
+    let columns: Vec<&Column> = table.selected_columns()
+      .filter(|col| is_exported(col, args.normalize_complex, &hidden_columns))
+      .collect();
+    // Memo history is only exported to CSV, so only CSV rows can have more than one version.
+    for (line, version) in versions.iter().enumerate() {
+      if line != 0 {
+        println!();
+      }
+
+      let mut values: Vec<String> = Vec::new();
+      for col in columns.iter() {
+        let is_memo = memo_name.as_deref() == Some(col.name.as_str());
+        match version {
+          Some(version) if is_memo => {
+            values.push(format_value(&Value::Text(version.value.clone()), col.column_type, &args, &backend, &null));
+            let modified = version.modified.map_or(Value::Null, Value::DateTime);
+            values.push(format_value(&modified, ColumnType::Datetime, &args, &backend, &null));
+          }
+          _ => {
+            values.push(format_value(&col.value(), col.column_type, &args, &backend, &null));
+            if is_memo {
+              values.push(null.clone());
+            }
+          }
+        }
+      }
+      print!("{}", values.join(","));
+    }
+    if backend == backend::CSV_BACKEND && !columns.is_empty() {
+      println!();
+    }
+  }
+
+  if backend != backend::CSV_BACKEND && !first {
+    println!(");");
+  }
+
+  // The table is read again for each child table, rather than holding on to the values of every row.
+  for child in &children {
+    table.rewind();
+    if print_child_table(&mut table, child, &key_columns, &args, &backend, &null).is_err() {
+      eprintln!("Could not read complex column {}.", child.complex_column.name);
+      return ExitCode::FAILURE;
+    }
+  }
+
+  ExitCode::SUCCESS
+}
+
+/// Insert the values of a complex column into its child table, keyed by the primary key of the row they belong to.
+fn print_child_table(table: &mut Table, child: &ChildTable, key_columns: &[usize], args: &Args, backend: &Backend, null: &str) -> Result<(), MdbError> {
+  let columns: Vec<&Column> = key_columns.iter().map(|column| &table.columns[*column])
+    .chain(child.complex_column.value_columns.iter())
+    .collect();
+  let names: Vec<String> = columns.iter().map(|col| (backend.quote_name)(&col.name)).collect();
+  let column_types: Vec<ColumnType> = columns.iter().map(|col| col.column_type).collect();
+
+  let mut first = true;
+  while table.fetch_row().is_ok() {
+    let Some(complex_value) = table.columns[child.column].complex_value() else {
+      continue;
+    };
+    let key: Vec<Value> = key_columns.iter().map(|column| table.columns[*column].value()).collect();
+    for row in child.values.rows(complex_value)? {
+      if first {
+        print!("INSERT INTO {} ({})\nVALUES\n  (", (backend.quote_name)(&child.complex_column.child_table_name()), names.join(", "));
+        first = false;
+      } else {
+        print!("),\n  (");
+      }
+      let values: Vec<String> = key.iter().chain(row.values())
+        .zip(&column_types)
+        .map(|(value, column_type)| format_value(value, *column_type, args, backend, null))
+        .collect();
+      print!("{}", values.join(","));
+    }
+  }
+
+  if !first {
+    println!(");");
+  }
+  Ok(())
+}
+
+/// Complex columns are left out of the table when they are exported as child tables, and so are hidden columns: the
+/// version history column of a memo exported with its history, and generated columns.
+fn is_exported(col: &Column, normalize_complex: bool, hidden_columns: &[String]) -> bool {
+  let normalized = normalize_complex && col.column_type == ColumnType::Complex;
+  !normalized && !hidden_columns.contains(&col.name)
+}
+
+/// The name of the column holding the date of each version of a memo.
+fn modified_column_name(memo: &str) -> String {
+  format!("{}_Modified", memo)
+}
+
+fn format_value(value: &Value, column_type: ColumnType, args: &Args, backend: &Backend, null: &str) -> String {
+  // Multivalued fields are JSON arrays, except in Postgres, which has arrays.
+  let col_string = match value {
+    Value::Null => return null.to_string(),
+    Value::List(values) if backend.sql_dialect == SqlDialect::Postgres => backend::postgres_array_literal(values),
+    value => value.to_string(),
+  };
+
+  if (should_quote(column_type) || matches!(value, Value::List(_))) && !args.no_quote {
+    return backend::quote_generic(&col_string, backend.default_quote_str, &args.escape);
+  }
+  // TODO: format binary literal.
+  col_string
+}
+
+fn print_insert(table: &Table, backend: &Backend, normalize_complex: bool, hidden_columns: &[String]) {
+  print!("INSERT INTO {} (", (backend.quote_name)(&table.name));
+
+  for (index, col) in table.selected_columns().filter(|col| is_exported(col, normalize_complex, hidden_columns)).enumerate() {
+    if index != 0 {
+      print!(", ");
+    }
+
+    print!("{}", (backend.quote_name)(&col.name));
+  }
+  println!(")\nVALUES");
+}
+
+fn print_header(args: &Args, table: &mut Table, properties: &ObjectProperties, hidden_columns: &[String], memo: Option<&str>) {
+  if !args.no_header {
+    let mut names: Vec<String> = Vec::new();
+    for col in table.selected_columns().filter(|col| !hidden_columns.contains(&col.name)) {
+      let mut name = properties.caption(&col.name).unwrap_or(&col.name).to_string();
+      if args.types {
+        name.push_str(&format!("({}", col.column_type));
+        for flag in col.flags.names() {
+          name.push_str(&format!("/{}", flag));
+        }
+        name.push(')');
+      }
+      names.push(name);
+
+      if memo == Some(col.name.as_str()) {
+        let mut name = modified_column_name(&col.name);
+        if args.types {
+          name.push_str(&format!("({}/nullable)", ColumnType::Datetime));
+        }
+        names.push(name);
+      }
+    }
+    if !names.is_empty() {
+      println!("{}", names.join(","));
+    }
+  }
+}
+
+fn should_quote(column_type: ColumnType) -> bool {
+  matches!(column_type, ColumnType::Text | ColumnType::Bool | ColumnType::Memo | ColumnType::Datetime | ColumnType::ExtendedDatetime)
 }
\ No newline at end of file
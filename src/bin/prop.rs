@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ColorChoice};
+
+use mdbtools::mdbfile::Mdb;
+use mdbtools::catalog::read_system_objects;
+use mdbtools::property::{read_properties, PropertyBlock, DATABASE_PROPERTIES_OBJECT};
+
+/// Display the properties of an object in an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Table name
+  ///
+  /// The table, query, form or other object whose properties are shown. If no name is given, the properties of the
+  /// database are shown.
+  #[arg(short = 'T', long = "table")]
+  table: Option<String>,
+
+  /// Only show the properties of this column
+  #[arg(short = 'c', long)]
+  column: Option<String>,
+
+  /// Show property types
+  ///
+  /// Prints the type of each value and whether it was set through DDL.
+  #[arg(long = "showtype", default_value_t = false)]
+  show_type: bool,
+
+  /// Path to file
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let mut mdb = match Mdb::open(args.file.clone()) {
+    Ok(mdb) => mdb,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    },
+  };
+
+  let system_objects = match read_system_objects(&mut mdb) {
+    Ok(system_objects) => system_objects,
+    Err(_) => {
+      eprintln!("Error reading system table. Exiting.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let name = args.table.as_deref().unwrap_or(DATABASE_PROPERTIES_OBJECT);
+  let object = match system_objects.iter().find(|object| object.name == name) {
+    Some(object) => object,
+    None => {
+      eprintln!("Object {} not found.", name);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let properties = match read_properties(&mdb, object) {
+    Ok(properties) => properties,
+    Err(_) => {
+      eprintln!("Could not read the properties of {}.", name);
+      return ExitCode::FAILURE;
+    }
+  };
+
+  match &args.column {
+    Some(column) => match properties.column(column) {
+      Some(block) => print_block(column, block, &args),
+      None => {
+        eprintln!("Column {} of {} has no properties.", column, name);
+        return ExitCode::FAILURE;
+      }
+    },
+    None => {
+      print_block(name, &properties.object, &args);
+      for block in &properties.columns {
+        print_block(&block.name, block, &args);
+      }
+    }
+  }
+
+  ExitCode::SUCCESS
+}
+
+fn print_block(name: &str, block: &PropertyBlock, args: &Args) {
+  println!("name: {}", name);
+  for property in &block.properties {
+    if args.show_type {
+      println!("\t{} ({:#04x}{}): {}", property.name, property.data_type, if property.ddl { ", ddl" } else { "" }, property.value);
+    } else {
+      println!("\t{}: {}", property.name, property.value);
+    }
+  }
+}
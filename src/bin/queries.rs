@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ColorChoice};
+
+use mdbtools::mdbfile::Mdb;
+use mdbtools::query::{read_queries, Query};
+
+/// List saved queries or print the SQL of a query in an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// List queries
+  ///
+  /// Lists the names of the queries in the database. This is the default when no query is given.
+  #[arg(short = 'L', long, default_value_t = false)]
+  list: bool,
+
+  /// One query name per line
+  ///
+  /// Specifies that the queries should be listed 1 per line.
+  #[arg(short = '1', long, default_value_t = false)]
+  single_column: bool,
+
+  /// Query name delimiter
+  ///
+  /// Specifies an alternative delimiter. If no delimiter is specified, query names will be delimited by a space.
+  #[arg(short = 'd', long, default_value_t = String::from(" "))]
+  delimiter: String,
+
+  /// Include hidden queries
+  ///
+  /// Hidden queries (~sq_...) are the record sources of forms, reports and controls.
+  #[arg(short = 'H', long, default_value_t = false)]
+  hidden: bool,
+
+  /// Path to file
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Query name
+  ///
+  /// Prints the SQL of the named query.
+  #[arg(short = 'q', long = "query")]
+  query: Option<String>,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let mut mdb = match Mdb::open(args.file) {
+    Ok(mdb) => mdb,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    },
+  };
+
+  let queries = match read_queries(&mut mdb) {
+    Ok(queries) => queries,
+    Err(_) => {
+      eprintln!("Error reading queries. Exiting.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let query_name = match args.query {
+    Some(query_name) if !args.list => query_name,
+    _ => {
+      let queries: Vec<&Query> = queries.iter().filter(|query| args.hidden || !query.is_hidden()).collect();
+      for (index, query) in queries.iter().enumerate() {
+        print!("{}", query.name);
+        if args.single_column {
+          println!();
+        } else if index != queries.len() - 1 {
+          print!("{}", args.delimiter);
+        }
+      }
+      if !args.single_column {
+        println!();
+      }
+      return ExitCode::SUCCESS;
+    }
+  };
+
+  match queries.iter().find(|query| query.name == query_name) {
+    Some(query) => {
+      println!("{}", query.to_sql());
+      ExitCode::SUCCESS
+    }
+    None => {
+      eprintln!("Query {} not found.", query_name);
+      ExitCode::FAILURE
+    }
+  }
+}
@@ -1,150 +1,517 @@
-use std::path::PathBuf;
-use std::process::ExitCode;
-
-use clap::{Parser, ColorChoice};
-
-use mdbtools;
-use mdbtools::backend;
-use mdbtools::backend::Backend;
-use mdbtools::mdbfile::Mdb;
-use mdbtools::catalog::{CatalogEntry, read_catalog, TableCatalogEntry};
-use mdbtools::table::Table;
-
-/// Get listing of tables in an MDB database
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
-struct Args {
-  /// Suppress header row.
-  #[arg(short = 'H', long, default_value_t = false)]
-  no_header: bool,
-
-  /// Add types to header.
-  #[arg(long, default_value_t = false)]
-  types: bool,
-
-  /// Path to file.
-  #[arg(short, long, value_name = "FILE")]
-  file: PathBuf,
-
-  /// Don't wrap text-like fields in quotes.
-  #[arg(short = 'Q', long, default_value_t = false)]
-  no_quote: bool,
-
-  /// Specify an alternative quote. Default is ".
-  #[arg(short = 'q', long, default_value_t = String::from("\""))]
-  quote: String,
-
-  /// Backend. Default is CSV.
-  #[arg(short = 'b', long, default_value_t = String::from("csv"))]
-  backend: String,
-
-  /// Null specifier. Default is "".
-  #[arg(short = 'n', long, default_value_t = String::from(""))]
-  null: String,
-
-  /// Schema/namespace. Default is "".
-  #[arg(short = 's', long)]
-  schema: Option<String>,
-
-  /// Table name.
-  #[arg(short = 'T', long)]
-  table: Option<String>,
-}
-
-pub fn main() -> ExitCode {
-  let args = Args::parse();
-
-  let backend_name = args.backend.to_lowercase();
-  let backends: Vec<Backend> = vec![backend::MSSQL_BACKEND, backend::POSTGRES_BACKEND];
-
-  let mut backend: Backend = backend::MSSQL_BACKEND;
-  for (index, cur_backend) in backends.iter().enumerate() {
-    if backend_name.eq(&cur_backend.name.to_lowercase()) {
-      backend = cur_backend.clone();
-      break;
-    }
-    if index + 1 == backends.len() {
-      eprintln!("Backend not found.");
-      return ExitCode::FAILURE;
-    }
-  };
-
-  let mut mdb = match Mdb::open(args.file.clone()) {
-    Ok(mdb) => mdb,
-    Err(_err) => {
-      return ExitCode::FAILURE;
-    }
-  };
-
-  let catalog = match read_catalog(&mut mdb) {
-    Ok(row) => row,
-    Err(_) => {
-      println!("Error reading system table. Exiting.");
-      return ExitCode::FAILURE;
-    }
-  };
-
-  for catalog_entry in catalog {
-    match catalog_entry {
-      CatalogEntry::Table(table) => {
-        if args.table.is_some() && !args.table.as_ref().unwrap().eq(&table.name) {
-          continue;
-        }
-        print_table_schema(table, &args, &mut mdb, &backend);
-      }
-    }
-  };
-
-  ExitCode::SUCCESS
-}
-
-fn print_table_schema(table: TableCatalogEntry, args: &Args, mdb: &mut Mdb, backend: &Backend) {
-  if table.is_system_table() && args.table.is_none() {
-    return;
-  }
-
-  let mut table = Table::from_catalog_entry(CatalogEntry::Table(table), mdb).expect("Could not read table.");
-  table.read_columns().expect("Could not read table.");
-
-  let schema_name = args.schema.as_deref().unwrap_or(&String::new()).to_string();
-  let quoted_schema_name = if schema_name.is_empty() {schema_name.clone()} else {(backend.quote_name)(&schema_name) + "."};
-  let quoted_table_name = quoted_schema_name.clone() + (backend.quote_name)(&table.name).as_str();
-  let table_name = quoted_schema_name.clone() + table.name.as_str();
-
-  let mut create_statement = backend.create_table_string.to_string();
-  create_statement = create_statement.replace("{quoted_table_name}", &quoted_table_name);
-  create_statement = create_statement.replace("{table_name}", &table_name);
-  println!("{}", create_statement);
-
-  let mut first: bool = true;
-  for col in  table.columns {
-    let quoted_column_name = (backend.quote_name)(&col.name);
-    let mut col_string = backend.column_string.to_string();
-    let column_type = col.get_backend_type(backend);
-    col_string = col_string.replace("{quoted_column_name}", &quoted_column_name);
-
-    let precision = col.precision;
-    let scale = col.scale;
-    if column_type.needs_precision && column_type.needs_scale {
-      col_string = col_string.replace("{column_type}",  &format!("{{column_type}}({precision},{scale})"));
-    } else if column_type.needs_precision {
-      col_string = col_string.replace("{column_type}",  &format!("{{column_type}}({precision})"));
-    }
-
-    col_string = col_string.replace("{column_type}", &column_type.name);
-
-    if !first {
-      print!(",\n  {}", col_string);
-    } else {
-      print!("  {}", col_string);
-    }
-    if column_type.needs_char_length {
-      print!("({})", col.size/2);
-    }
-    first = false;
-  }
-  println!();
-
-  println!(");");
-  println!();
-}
\ No newline at end of file
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ColorChoice};
+
+use mdbtools::backend;
+use mdbtools::backend::{quote_string_literal, Backend, SqlDialect};
+use mdbtools::mdbfile::Mdb;
+use mdbtools::catalog::{CatalogEntry, ObjectType, read_system_objects, SystemObject, TableCatalogEntry};
+use mdbtools::column::{Column, ColumnType};
+use mdbtools::complex::{read_complex_columns, ComplexColumn};
+use mdbtools::property::{read_properties, ObjectProperties, PropertyBlock};
+use mdbtools::relationship::{read_relationships, Relationship};
+use mdbtools::query::{dependency_order, read_queries, Query, QueryType};
+use mdbtools::sql::{translate, translate_in_schema};
+use mdbtools::index::Index;
+use mdbtools::table::Table;
+
+/// Get listing of tables in an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Suppress header row.
+  #[arg(short = 'H', long, default_value_t = false)]
+  no_header: bool,
+
+  /// Describe the Access type and flags of each column in a comment before the table.
+  #[arg(long, default_value_t = false)]
+  types: bool,
+
+  /// Path to file.
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+
+  /// Don't wrap text-like fields in quotes.
+  #[arg(short = 'Q', long, default_value_t = false)]
+  no_quote: bool,
+
+  /// Specify an alternative quote. Default is ".
+  #[arg(short = 'q', long, default_value_t = String::from("\""))]
+  quote: String,
+
+  /// Backend. Default is CSV.
+  #[arg(short = 'b', long, default_value_t = String::from("csv"))]
+  backend: String,
+
+  /// Null specifier. Default is "".
+  #[arg(short = 'n', long, default_value_t = String::from(""))]
+  null: String,
+
+  /// Schema/namespace. Default is "".
+  #[arg(short = 's', long)]
+  schema: Option<String>,
+
+  /// Table name.
+  #[arg(short = 'T', long)]
+  table: Option<String>,
+
+  /// Don't emit primary keys.
+  #[arg(long, default_value_t = false)]
+  no_primary_keys: bool,
+
+  /// Don't emit UNIQUE constraints or CREATE INDEX statements for other indexes.
+  #[arg(long, default_value_t = false)]
+  no_indexes: bool,
+
+  /// Don't emit NOT NULL for required columns.
+  #[arg(long, default_value_t = false)]
+  no_not_null: bool,
+
+  /// Don't emit DEFAULT clauses.
+  #[arg(long, default_value_t = false)]
+  no_default_values: bool,
+
+  /// Don't turn AutoNumber columns into identity columns.
+  #[arg(long, default_value_t = false)]
+  no_identity: bool,
+
+  /// Don't turn calculated columns into generated columns, so their stored results can be exported into them with
+  /// mdb-export --no-generated.
+  #[arg(long, default_value_t = false)]
+  no_generated: bool,
+
+  /// Don't emit the Description property of tables and columns as comments.
+  #[arg(long, default_value_t = false)]
+  no_comments: bool,
+
+  /// Don't emit foreign keys for relationships.
+  #[arg(long, default_value_t = false)]
+  no_relations: bool,
+
+  /// Emit saved queries as views.
+  ///
+  /// Select and union queries are translated into the backend dialect and emitted after the tables, each after the
+  /// views it reads from. Anything that could not be translated is flagged in a comment.
+  #[arg(long, default_value_t = false)]
+  views: bool,
+
+  /// Emit complex columns as child tables rather than arrays.
+  ///
+  /// The values of multivalued, attachment and version history fields are moved to a table named after the table and
+  /// the column, keyed by the primary key of the table.
+  #[arg(long, default_value_t = false)]
+  normalize_complex: bool,
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let backend_name = args.backend.to_lowercase();
+  let backends: Vec<Backend> = vec![backend::MSSQL_BACKEND, backend::POSTGRES_BACKEND];
+
+  let mut backend: Backend = backend::MSSQL_BACKEND;
+  for (index, cur_backend) in backends.iter().enumerate() {
+    if backend_name.eq(&cur_backend.name.to_lowercase()) {
+      backend = *cur_backend;
+      break;
+    }
+    if index + 1 == backends.len() {
+      eprintln!("Backend not found.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let mut mdb = match Mdb::open(args.file.clone()) {
+    Ok(mdb) => mdb,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let system_objects = match read_system_objects(&mut mdb) {
+    Ok(system_objects) => system_objects,
+    Err(_) => {
+      println!("Error reading system table. Exiting.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let complex_columns = match read_complex_columns(&mut mdb) {
+    Ok(complex_columns) => complex_columns,
+    Err(_) => {
+      eprintln!("Error reading complex columns. Exiting.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let system_tables: Vec<String> = system_objects.iter()
+    .filter(|object| object.is_system_table())
+    .map(|object| object.name.clone())
+    .collect();
+
+  for object in system_objects.iter().filter(|object| object.object_type == ObjectType::Table) {
+    if args.table.is_some() && !args.table.as_ref().unwrap().eq(&object.name) {
+      continue;
+    }
+    let Some(CatalogEntry::Table(table)) = object.to_catalog_entry() else {
+      continue;
+    };
+    print_table_schema(table, object, &complex_columns, &args, &mut mdb, &backend);
+  };
+
+  if !args.no_relations {
+    let relationships = match read_relationships(&mut mdb) {
+      Ok(relationships) => relationships,
+      Err(_) => {
+        eprintln!("Error reading relationships. Exiting.");
+        return ExitCode::FAILURE;
+      }
+    };
+
+    for relationship in relationships {
+      if args.table.as_ref().is_some_and(|name| name != &relationship.from_table) {
+        continue;
+      }
+      let involves_system_table = system_tables.contains(&relationship.from_table) || system_tables.contains(&relationship.to_table);
+      if involves_system_table && args.table.is_none() {
+        continue;
+      }
+      print_relationship(&relationship, &args, &backend);
+    }
+  }
+
+  if args.views {
+    let queries = match read_queries(&mut mdb) {
+      Ok(queries) => queries,
+      Err(_) => {
+        eprintln!("Error reading queries. Exiting.");
+        return ExitCode::FAILURE;
+      }
+    };
+
+    for query in dependency_order(&queries) {
+      if query.is_hidden() || args.table.as_ref().is_some_and(|name| name != &query.name) {
+        continue;
+      }
+      print_view(query, &args, &backend);
+    }
+  }
+
+  ExitCode::SUCCESS
+}
+
+fn print_table_schema(table: TableCatalogEntry, object: &SystemObject, complex_columns: &[ComplexColumn], args: &Args, mdb: &mut Mdb, backend: &Backend) {
+  if table.is_system_table() && args.table.is_none() {
+    return;
+  }
+
+  let mut table = Table::from_catalog_entry(CatalogEntry::Table(table), mdb).expect("Could not read table.");
+  table.read_columns().expect("Could not read table.");
+
+  let properties = read_properties(mdb, object).unwrap_or_else(|_| {
+    eprintln!("Could not read the properties of {}.", table.name);
+    ObjectProperties::default()
+  });
+  let column_properties = |name: &str| properties.column(name);
+
+  let mut warnings: Vec<String> = Vec::new();
+  let mut definitions: Vec<String> = Vec::new();
+
+  let schema_name = args.schema.as_deref().unwrap_or("").to_string();
+  let quoted_schema_name = if schema_name.is_empty() {schema_name.clone()} else {(backend.quote_name)(&schema_name) + "."};
+  let quoted_table_name = quoted_schema_name.clone() + (backend.quote_name)(&table.name).as_str();
+  let table_name = quoted_schema_name.clone() + table.name.as_str();
+
+  let mut create_statement = backend.create_table_string.to_string();
+  create_statement = create_statement.replace("{quoted_table_name}", &quoted_table_name);
+  create_statement = create_statement.replace("{table_name}", &table_name);
+
+  for col in table.columns.iter() {
+    if args.normalize_complex && col.column_type == ColumnType::Complex {
+      continue;
+    }
+    let quoted_column_name = (backend.quote_name)(&col.name);
+    let mut col_string = backend.column_string.to_string();
+    col_string = col_string.replace("{quoted_column_name}", &quoted_column_name);
+
+    // Multivalued fields are arrays of the type of their values.
+    let value_column = col.complex_id
+      .and_then(|complex_id| complex_columns.iter().find(|complex_column| complex_column.complex_id == complex_id))
+      .and_then(|complex_column| complex_column.value_column());
+    let column_type = match value_column {
+      Some(value_column) => backend.array_string.replace("{column_type}", &column_type(value_column, backend)),
+      None => column_type(col, backend),
+    };
+    col_string = col_string.replace("{column_type}", &column_type);
+
+    if col.flags.is_calculated() {
+      if !args.no_generated {
+        match backend::generated_column(col, column_properties(&col.name), backend) {
+          Ok(Some(generated)) => col_string.push_str(&format!(" {}", generated)),
+          Ok(None) => {}
+          Err(warning) => warnings.push(warning),
+        }
+      }
+    } else if !args.no_identity && col.flags.is_long_auto() && !backend.identity_string.is_empty() {
+      col_string.push_str(&format!(" {}", backend.identity_string));
+    } else if !args.no_identity && col.flags.is_uuid_auto() && !backend.uuid_default_string.is_empty() {
+      col_string.push_str(&format!(" DEFAULT {}", backend.uuid_default_string));
+    } else if !args.no_default_values {
+      match default_value(col, column_properties(&col.name), backend) {
+        Ok(Some(default)) => col_string.push_str(&format!(" DEFAULT {}", default)),
+        Ok(None) => {}
+        Err(warning) => warnings.push(warning),
+      }
+    }
+
+    if !args.no_not_null && table.is_required(col, &properties) {
+      col_string.push_str(" NOT NULL");
+    }
+
+    definitions.push(format!("  {}", col_string));
+  }
+
+  let constraint_name = |index: &Index| (backend.quote_name)(&format!("{}_{}", table.name, index.name));
+  let quote_columns = |index: &Index| index.columns.iter()
+    .map(|column| (backend.quote_name)(&column.name))
+    .collect::<Vec<String>>()
+    .join(", ");
+
+  let mut create_indexes: Vec<String> = Vec::new();
+  for index in table.indexes.iter().filter(|index| !index.is_foreign_key()) {
+    if index.is_primary_key() {
+      if !args.no_primary_keys {
+        definitions.push(format!("  CONSTRAINT {} PRIMARY KEY ({})", constraint_name(index), quote_columns(index)));
+      }
+    } else if args.no_indexes {
+      continue;
+    } else if index.is_unique() {
+      definitions.push(format!("  CONSTRAINT {} UNIQUE ({})", constraint_name(index), quote_columns(index)));
+    } else {
+      let columns = index.columns.iter()
+        .map(|column| (backend.quote_name)(&column.name) + if column.ascending { "" } else { " DESC" })
+        .collect::<Vec<String>>()
+        .join(", ");
+      create_indexes.push(format!("CREATE INDEX {} ON {} ({});", constraint_name(index), quoted_table_name, columns));
+    }
+  }
+
+  for warning in warnings {
+    println!("-- WARNING: {}", warning);
+  }
+  if args.types {
+    for col in table.columns.iter() {
+      println!("-- {}: {} ({})", col.name, col.column_type, col.flags);
+    }
+  }
+  println!("{}", create_statement);
+  println!("{}", definitions.join(",\n"));
+  println!(");");
+  for create_index in create_indexes {
+    println!("{}", create_index);
+  }
+  if !args.no_comments {
+    print_comments(&table, &properties, &quoted_table_name, args, backend);
+  }
+  println!();
+
+  if args.normalize_complex {
+    let table_complex_columns = table.columns.iter()
+      .filter_map(|col| col.complex_id)
+      .filter_map(|complex_id| complex_columns.iter().find(|complex_column| complex_column.complex_id == complex_id));
+    for complex_column in table_complex_columns {
+      print_child_table(&table, complex_column, &quoted_schema_name, args, backend);
+    }
+  }
+}
+
+/// Emit the values of a complex column as a child table, keyed by the primary key of the table.
+fn print_child_table(table: &Table, complex_column: &ComplexColumn, quoted_schema_name: &str, args: &Args, backend: &Backend) {
+  let Some(primary_key) = table.indexes.iter().find(|index| index.is_primary_key()) else {
+    println!("-- WARNING: {} has no primary key, so {} could not be emitted as a child table.", table.name, complex_column.name);
+    println!();
+    return;
+  };
+  let key_columns: Vec<&Column> = primary_key.columns.iter()
+    .filter_map(|key_column| table.columns.iter().find(|col| col.name == key_column.name))
+    .collect();
+
+  let child_table_name = complex_column.child_table_name();
+  let quoted_child_table_name = quoted_schema_name.to_string() + (backend.quote_name)(&child_table_name).as_str();
+  let quoted_table_name = quoted_schema_name.to_string() + (backend.quote_name)(&table.name).as_str();
+  let quoted_key_columns = key_columns.iter().map(|col| (backend.quote_name)(&col.name)).collect::<Vec<String>>().join(", ");
+
+  let column_definition = |col: &Column| backend.column_string
+    .replace("{quoted_column_name}", &(backend.quote_name)(&col.name))
+    .replace("{column_type}", &column_type(col, backend));
+  let mut definitions: Vec<String> = Vec::new();
+  for col in key_columns.iter() {
+    definitions.push(format!("  {} NOT NULL", column_definition(col)));
+  }
+  for col in complex_column.value_columns.iter() {
+    definitions.push(format!("  {}", column_definition(col)));
+  }
+  if !args.no_relations {
+    // The foreign key needs the primary key of the table to reference.
+    if args.no_primary_keys {
+      println!("-- WARNING: the primary key of {} is not emitted, so the foreign key of {} was left out.", table.name, child_table_name);
+    } else {
+      definitions.push(format!("  CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE CASCADE",
+                               (backend.quote_name)(&format!("{}_{}", child_table_name, table.name)),
+                               quoted_key_columns, quoted_table_name, quoted_key_columns));
+    }
+  }
+
+  println!("{}", backend.create_table_string
+    .replace("{quoted_table_name}", &quoted_child_table_name)
+    .replace("{table_name}", &(quoted_schema_name.to_string() + child_table_name.as_str())));
+  println!("{}", definitions.join(",\n"));
+  println!(");");
+  if !args.no_indexes {
+    println!("CREATE INDEX {} ON {} ({});", (backend.quote_name)(&format!("{}_{}", child_table_name, primary_key.name)),
+             quoted_child_table_name, quoted_key_columns);
+  }
+  println!();
+}
+
+/// The type of a column in the backend, with its precision, scale and length.
+fn column_type(col: &Column, backend: &Backend) -> String {
+  let column_type = col.get_backend_type(backend);
+  let mut type_string = column_type.name.to_string();
+  if column_type.needs_precision && column_type.needs_scale {
+    type_string.push_str(&format!("({},{})", col.precision, col.scale));
+  } else if column_type.needs_precision {
+    type_string.push_str(&format!("({})", col.precision));
+  }
+  if column_type.needs_char_length {
+    type_string.push_str(&format!("({})", col.size/2));
+  }
+  type_string
+}
+
+/// Attach the Description property of the table and its columns with the comment statements of the backend.
+fn print_comments(table: &Table, properties: &ObjectProperties, quoted_table_name: &str, args: &Args, backend: &Backend) {
+  let schema_name = args.schema.as_deref().unwrap_or(backend.default_schema);
+  let description = |block: Option<&PropertyBlock>| block
+    .and_then(|block| block.get("Description"))
+    .and_then(|property| property.as_str())
+    .filter(|description| !description.is_empty())
+    .map(quote_string_literal);
+
+  let fill = |template: &str, comment: &str, column_name: &str| template
+    .replace("{quoted_table_name}", quoted_table_name)
+    .replace("{quoted_column_name}", &(backend.quote_name)(column_name))
+    .replace("{schema_literal}", &quote_string_literal(schema_name))
+    .replace("{table_literal}", &quote_string_literal(&table.name))
+    .replace("{column_literal}", &quote_string_literal(column_name))
+    .replace("{comment}", comment);
+
+  if !backend.table_comment_string.is_empty() && let Some(comment) = description(Some(&properties.object)) {
+    println!("{}", fill(backend.table_comment_string, &comment, ""));
+  }
+  if backend.column_comment_string.is_empty() {
+    return;
+  }
+  for column in table.columns.iter() {
+    if args.normalize_complex && column.column_type == ColumnType::Complex {
+      continue;
+    }
+    if let Some(comment) = description(properties.column(&column.name)) {
+      println!("{}", fill(backend.column_comment_string, &comment, &column.name));
+    }
+  }
+}
+
+/// The DEFAULT clause for the DefaultValue property of a column, or a warning if it could not be translated.
+fn default_value(column: &Column, properties: Option<&PropertyBlock>, backend: &Backend) -> Result<Option<String>, String> {
+  let expression = match properties.and_then(|block| block.get("DefaultValue")) {
+    Some(property) => property.value.to_string(),
+    None => return Ok(None),
+  };
+  let expression = expression.trim().trim_start_matches('=').trim();
+  if expression.is_empty() {
+    return Ok(None);
+  }
+
+  // Yes/No defaults are often stored as numbers.
+  if column.column_type == ColumnType::Bool && let Ok(number) = expression.parse::<i64>() {
+    return Ok(Some(match (number != 0, backend.sql_dialect) {
+      (true, SqlDialect::Postgres) => "TRUE".to_string(),
+      (false, SqlDialect::Postgres) => "FALSE".to_string(),
+      (true, _) => "1".to_string(),
+      (false, _) => "0".to_string(),
+    }));
+  }
+
+  match translate(expression, backend) {
+    Ok(translation) if translation.warnings.is_empty() => Ok(Some(translation.sql)),
+    Ok(translation) => Err(format!("the default value of {} ({}) was skipped: {}", column.name, expression, translation.warnings.join("; "))),
+    Err(_) => Err(format!("the default value of {} ({}) could not be parsed", column.name, expression)),
+  }
+}
+
+fn print_relationship(relationship: &Relationship, args: &Args, backend: &Backend) {
+  let schema_name = args.schema.as_deref().unwrap_or("").to_string();
+  let quoted_schema_name = if schema_name.is_empty() {schema_name.clone()} else {(backend.quote_name)(&schema_name) + "."};
+  let quote_columns = |columns: &[String]| columns.iter().map(|column| (backend.quote_name)(column)).collect::<Vec<String>>().join(", ");
+
+  if !relationship.enforces_integrity() {
+    println!("-- Relationship from {} ({}) to {} ({}) does not enforce integrity.",
+             relationship.from_table, relationship.from_columns.join(", "),
+             relationship.to_table, relationship.to_columns.join(", "));
+    println!();
+    return;
+  }
+
+  let mut statement = format!("ALTER TABLE {}{} ADD CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {}{} ({})",
+                              quoted_schema_name, (backend.quote_name)(&relationship.from_table),
+                              (backend.quote_name)(&relationship.name),
+                              quote_columns(&relationship.from_columns),
+                              quoted_schema_name, (backend.quote_name)(&relationship.to_table),
+                              quote_columns(&relationship.to_columns));
+  if relationship.cascades_updates() {
+    statement.push_str(" ON UPDATE CASCADE");
+  }
+  if relationship.cascades_deletes() {
+    statement.push_str(" ON DELETE CASCADE");
+  } else if relationship.cascades_null() {
+    statement.push_str(" ON DELETE SET NULL");
+  }
+  println!("{};", statement);
+  println!();
+}
+
+fn print_view(query: &Query, args: &Args, backend: &Backend) {
+  if !matches!(query.query_type, QueryType::Select | QueryType::Union) {
+    println!("-- {} is a {} query and cannot be emitted as a view.", query.name, query.query_type);
+    println!();
+    return;
+  }
+
+  let translation = match translate_in_schema(&query.to_sql(), backend, args.schema.as_deref()) {
+    Ok(translation) => translation,
+    Err(_) => {
+      println!("-- The SQL of {} could not be parsed.", query.name);
+      println!();
+      return;
+    }
+  };
+
+  let schema_name = args.schema.as_deref().unwrap_or("").to_string();
+  let quoted_schema_name = if schema_name.is_empty() {schema_name.clone()} else {(backend.quote_name)(&schema_name) + "."};
+  let quoted_view_name = quoted_schema_name + (backend.quote_name)(&query.name).as_str();
+
+  if !backend.batch_separator.is_empty() {
+    println!("{}", backend.batch_separator);
+  }
+  for warning in &translation.warnings {
+    println!("-- WARNING: {}", warning);
+  }
+  println!("{}", backend.create_view_string.replace("{quoted_view_name}", &quoted_view_name));
+  println!("{};", translation.sql);
+  if !backend.batch_separator.is_empty() {
+    println!("{}", backend.batch_separator);
+  }
+  println!();
+}
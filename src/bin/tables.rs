@@ -1,87 +1,168 @@
-use std::path::PathBuf;
-use std::process::ExitCode;
-
-use clap::{Parser, ColorChoice};
-
-use mdbtools;
-use mdbtools::mdbfile::Mdb;
-use mdbtools::catalog::{CatalogEntry, read_catalog};
-
-/// Get listing of tables in an MDB database
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
-struct Args {
-  /// Include system tables
-  ///
-  /// System tables are generally those beginning with 'MSys'.
-  #[arg(short = 'S', long, default_value_t = false)]
-  system: bool,
-
-  /// One table name per line
-  ///
-  /// Specifies that the tables should be listed 1 per line.
-  #[arg(short = '1', long, default_value_t = false)]
-  single_column: bool,
-
-  /// Table name delimiter
-  ///
-  /// Specifies an alternative delimiter. If no delimiter is specified, table names will be delimited by a space.
-  #[arg(short = 'd', long, default_value_t = String::from(" "))]
-  delimiter: String,
-
-  /// Type of entry
-  #[arg(short = 't', long = "type")]
-  entry_type: Option<String>,
-
-  /// Show type
-  #[arg(short = 'T', long = "showtype")]
-  show_type: Option<bool>,
-
-  /// Path to file
-  #[arg(short, long, value_name = "FILE")]
-  file: PathBuf,
-}
-
-pub fn main() -> ExitCode {
-  let args = Args::parse();
-
-  let mut mdb = match Mdb::open(args.file) {
-    Ok(mdb) => mdb,
-    Err(_err) => {
-      return ExitCode::FAILURE;
-    },
-  };
-
-  let catalog = match read_catalog(&mut mdb) {
-    Ok(row) => row,
-    Err(_) => {
-      println!("Error reading system table. Exiting.");
-      return ExitCode::FAILURE;
-    }
-  };
-
-
-  for (index, catalog_entry) in catalog.iter().enumerate() {
-    match catalog_entry {
-      CatalogEntry::Table(table) => {
-        if !args.system && table.is_system_table() {
-          continue;
-        }
-
-        print!("{}", table.name);
-        if index != catalog.len() - 1 {
-          print!("{}", args.delimiter);
-        }
-        if args.single_column {
-          println!();
-        }
-      }
-    }
-  }
-
-  if !args.single_column {
-    println!();
-  }
-
-  ExitCode::SUCCESS
-}
\ No newline at end of file
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ColorChoice};
+
+use mdbtools::mdbfile::Mdb;
+use mdbtools::catalog::{read_system_objects, ObjectType, SystemObject};
+
+/// Get listing of tables in an MDB database
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None, color = ColorChoice::Auto)]
+struct Args {
+  /// Include system tables
+  ///
+  /// System tables are generally those beginning with 'MSys'.
+  #[arg(short = 'S', long, default_value_t = false)]
+  system: bool,
+
+  /// One table name per line
+  ///
+  /// Specifies that the tables should be listed 1 per line.
+  #[arg(short = '1', long, default_value_t = false)]
+  single_column: bool,
+
+  /// Table name delimiter
+  ///
+  /// Specifies an alternative delimiter. If no delimiter is specified, table names will be delimited by a space.
+  #[arg(short = 'd', long, default_value_t = String::from(" "))]
+  delimiter: String,
+
+  /// Type of entry
+  ///
+  /// One of form, table, macro, systable, report, query, linkedtable, module, relationship or all. Default is table.
+  #[arg(short = 't', long = "type", default_value_t = String::from("table"))]
+  entry_type: String,
+
+  /// Show type
+  ///
+  /// Prints the type of each object next to its name.
+  #[arg(short = 'T', long = "showtype", default_value_t = false)]
+  show_type: bool,
+
+  /// Include hidden objects
+  ///
+  /// Hidden, temporary (~TMP) and deleted objects are skipped unless this is set.
+  #[arg(short = 'H', long, default_value_t = false)]
+  hidden: bool,
+
+  /// Long listing
+  ///
+  /// Lists one object per line with its type, creation and modification dates, and hidden (H), system (S),
+  /// temporary (T) and deleted (D) flags.
+  #[arg(short = 'l', long, default_value_t = false)]
+  long: bool,
+
+  /// Path to file
+  #[arg(short, long, value_name = "FILE")]
+  file: PathBuf,
+}
+
+/// Which objects to list, from the --type argument.
+enum TypeFilter {
+  Any,
+  SystemTable,
+  Type(ObjectType),
+}
+
+impl TypeFilter {
+  fn parse(name: &str) -> Option<TypeFilter> {
+    Some(match name.to_lowercase().as_str() {
+      "form" => TypeFilter::Type(ObjectType::Form),
+      "table" => TypeFilter::Type(ObjectType::Table),
+      "macro" => TypeFilter::Type(ObjectType::Macro),
+      "systable" => TypeFilter::SystemTable,
+      "report" => TypeFilter::Type(ObjectType::Report),
+      "query" => TypeFilter::Type(ObjectType::Query),
+      "linkedtable" => TypeFilter::Type(ObjectType::LinkedTable),
+      "module" => TypeFilter::Type(ObjectType::Module),
+      "relationship" => TypeFilter::Type(ObjectType::Relationship),
+      "all" => TypeFilter::Any,
+      _ => return None,
+    })
+  }
+
+  fn matches(&self, object: &SystemObject) -> bool {
+    match self {
+      TypeFilter::Any => true,
+      TypeFilter::SystemTable => object.is_system_table(),
+      TypeFilter::Type(object_type) => object.object_type == *object_type,
+    }
+  }
+}
+
+pub fn main() -> ExitCode {
+  let args = Args::parse();
+
+  let type_filter = match TypeFilter::parse(&args.entry_type) {
+    Some(type_filter) => type_filter,
+    None => {
+      eprintln!("Unknown type: {}", args.entry_type);
+      return ExitCode::FAILURE;
+    }
+  };
+  let show_system = args.system || matches!(type_filter, TypeFilter::SystemTable);
+
+  let mut mdb = match Mdb::open(args.file) {
+    Ok(mdb) => mdb,
+    Err(_err) => {
+      return ExitCode::FAILURE;
+    },
+  };
+
+  let system_objects = match read_system_objects(&mut mdb) {
+    Ok(system_objects) => system_objects,
+    Err(_) => {
+      println!("Error reading system table. Exiting.");
+      return ExitCode::FAILURE;
+    }
+  };
+
+  let objects: Vec<&SystemObject> = system_objects.iter()
+    .filter(|object| type_filter.matches(object))
+    .filter(|object| show_system || !object.is_system())
+    .filter(|object| args.hidden || !(object.is_hidden() || object.is_temporary() || object.is_deleted()))
+    .collect();
+
+  if args.long {
+    for object in objects {
+      print_long_entry(object);
+    }
+    return ExitCode::SUCCESS;
+  }
+
+  for (index, object) in objects.iter().enumerate() {
+    print!("{}", object.name);
+    if args.show_type {
+      print!(" ({})", object.object_type);
+    }
+    if args.single_column {
+      println!();
+    } else if index != objects.len() - 1 {
+      print!("{}", args.delimiter);
+    }
+  }
+
+  if !args.single_column {
+    println!();
+  }
+
+  ExitCode::SUCCESS
+}
+
+fn print_long_entry(object: &SystemObject) {
+  let format_date = |date: Option<chrono::NaiveDateTime>| match date {
+    Some(date) => date.format("%Y-%m-%d %H:%M:%S").to_string(),
+    None => "-".repeat(19),
+  };
+
+  println!("{:<14} {} {} {}{}{}{} {}",
+           object.object_type.to_string(),
+           format_date(object.date_create),
+           format_date(object.date_update),
+           if object.is_hidden() { 'H' } else { '-' },
+           if object.is_system() { 'S' } else { '-' },
+           if object.is_temporary() { 'T' } else { '-' },
+           if object.is_deleted() { 'D' } else { '-' },
+           object.name);
+}
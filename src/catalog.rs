@@ -1,88 +1,305 @@
-use crate::mdbfile::Mdb;
-use crate::table::Table;
-use crate::utils::{get_u16, get_u32};
-
-pub enum CatalogEntry {
-  Table(TableCatalogEntry)
-}
-
-/*enum {
-  MDB_FORM = 0,
-  MDB_TABLE = 1,
-  MDB_MACRO,
-  MDB_SYSTEM_TABLE,
-  MDB_REPORT,
-  MDB_QUERY,
-  MDB_LINKED_TABLE,
-  MDB_MODULE,
-  MDB_RELATIONSHIP,
-  MDB_UNKNOWN_09,
-  MDB_UNKNOWN_0A, /* User access */
-  MDB_DATABASE_PROPERTY,
-  MDB_ANY = -1
-};*/
-
-pub struct TableCatalogEntry {
-  pub name: String,
-  pub page: u32,
-  pub flags: u32,
-}
-
-impl TableCatalogEntry {
-  pub fn is_system_table(&self) -> bool {
-    self.flags & 0x80000002 != 0
-  }
-}
-
-pub fn read_catalog(mdb: &mut Mdb) -> Result<Vec<CatalogEntry>, ()> {
-  let mut catalog_entries: Vec<CatalogEntry> = Vec::new();
-
-  match mdb.read_page(2) {
-    Ok(_) => {}
-    Err(_) => { return Err(()); }
-  }
-
-  let system_objects_table_catalog_entry = CatalogEntry::Table(TableCatalogEntry {
-    name: "MSysObjects".to_string(),
-    page: 2,
-    flags: 0,
-  });
-
-  let mut system_objects_table = match Table::from_catalog_entry(system_objects_table_catalog_entry, mdb) {
-    Ok(system_objects_table) => system_objects_table,
-    Err(_) => {
-      eprintln!("Failed to load system objects table for catalog.");
-      return Err(());
-    }
-  };
-
-  system_objects_table.read_columns().expect("failed to read columns");
-
-  let id_index = system_objects_table.find_column_index("Id").expect("Id column not found in system table.");
-  let name_index = system_objects_table.find_column_index("Name").expect("Name column not found in system table.");
-  let type_index = system_objects_table.find_column_index("Type").expect("Type column not found in system table.");
-  let flags_index = system_objects_table.find_column_index("Flags").expect("Flags column not found in system table.");
-  //let properties_index = system_objects_table.find_column_index("LvProp").expect("LvProp column not found in system table.");
-
-  while let Ok(_row) = system_objects_table.fetch_row() {
-    let id_column = &system_objects_table.columns[id_index];
-    let name_column = &system_objects_table.columns[name_index];
-    let type_column = &system_objects_table.columns[type_index];
-    let flags_column = &system_objects_table.columns[flags_index];
-    //let properties_column = &system_objects_table.columns[properties_index];
-
-    let entry_type = get_u16(&type_column.buffer.value, 0);
-    let name = system_objects_table.mdb.encoding.decode(&name_column.buffer.value).0.to_string();
-    let id = get_u32(&id_column.buffer.value, 0);
-    let flags = get_u32(&flags_column.buffer.value, 0);
-    if entry_type == 1 {
-      catalog_entries.push(CatalogEntry::Table(TableCatalogEntry {
-        name,
-        page: id & 0x00FFFFFF,
-        flags,
-      }))
-    }
-  }
-
-  Ok(catalog_entries)
-}
\ No newline at end of file
+use chrono::NaiveDateTime;
+
+use crate::column::ColumnType;
+use crate::conversion::decode_mdb_string;
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+use crate::table::Table;
+use crate::time::datetime_from_f64;
+use crate::utils::{get_u16, get_u32};
+
+/// System objects are flagged with either of these bits.
+pub const SYSTEM_OBJECT_FLAGS: u32 = 0x80000002;
+pub const HIDDEN_OBJECT_FLAG: u32 = 0x00000008;
+
+pub enum CatalogEntry {
+  Table(TableCatalogEntry)
+}
+
+/// The type of an object in MSysObjects. Forms, reports, macros and modules are stored with the
+/// high bit set, so only the low 7 bits are significant.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum ObjectType {
+  Form,
+  Table,
+  Macro,
+  SystemTable,
+  Report,
+  Query,
+  LinkedTable,
+  Module,
+  Relationship,
+  Unknown09,
+  UserInfo,
+  DatabaseProperty,
+  Unknown,
+}
+
+impl From<i16> for ObjectType {
+  fn from(raw: i16) -> Self {
+    match raw & 0x7F {
+      0 => ObjectType::Form,
+      1 => ObjectType::Table,
+      2 => ObjectType::Macro,
+      3 => ObjectType::SystemTable,
+      4 => ObjectType::Report,
+      5 => ObjectType::Query,
+      6 => ObjectType::LinkedTable,
+      7 => ObjectType::Module,
+      8 => ObjectType::Relationship,
+      9 => ObjectType::Unknown09,
+      10 => ObjectType::UserInfo,
+      11 => ObjectType::DatabaseProperty,
+      _ => ObjectType::Unknown,
+    }
+  }
+}
+
+impl std::fmt::Display for ObjectType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", match self {
+      ObjectType::Form => "Form",
+      ObjectType::Table => "Table",
+      ObjectType::Macro => "Macro",
+      ObjectType::SystemTable => "System Table",
+      ObjectType::Report => "Report",
+      ObjectType::Query => "Query",
+      ObjectType::LinkedTable => "Linked Table",
+      ObjectType::Module => "Module",
+      ObjectType::Relationship => "Relationship",
+      ObjectType::Unknown09 => "Unknown 0x09",
+      ObjectType::UserInfo => "User Info",
+      ObjectType::DatabaseProperty => "Database",
+      ObjectType::Unknown => "Unknown",
+    })
+  }
+}
+
+pub struct TableCatalogEntry {
+  pub name: String,
+  pub page: u32,
+  pub flags: u32,
+}
+
+impl TableCatalogEntry {
+  pub fn is_system_table(&self) -> bool {
+    self.flags & SYSTEM_OBJECT_FLAGS != 0
+  }
+}
+
+/// A fully decoded row of MSysObjects.
+#[derive(Clone, Debug)]
+pub struct SystemObject {
+  pub id: i32,
+  pub parent_id: i32,
+  pub name: String,
+  pub object_type: ObjectType,
+  /// The value of the `Type` column before masking.
+  pub raw_type: i16,
+  pub flags: u32,
+  pub date_create: Option<NaiveDateTime>,
+  pub date_update: Option<NaiveDateTime>,
+  pub owner: Option<Vec<u8>>,
+  pub connect: Option<String>,
+  pub database: Option<String>,
+  pub foreign_name: Option<String>,
+  pub rmt_info_short: Option<Vec<u8>>,
+  pub rmt_info_long: Option<Vec<u8>>,
+  pub lv_prop: Option<Vec<u8>>,
+  pub lv_extra: Option<Vec<u8>>,
+}
+
+impl SystemObject {
+  /// For tables, the page of the table definition.
+  pub fn page(&self) -> u32 {
+    self.id as u32 & 0x00FFFFFF
+  }
+
+  pub fn is_system(&self) -> bool {
+    self.flags & SYSTEM_OBJECT_FLAGS != 0
+  }
+
+  pub fn is_system_table(&self) -> bool {
+    self.object_type == ObjectType::Table && self.is_system()
+  }
+
+  pub fn is_hidden(&self) -> bool {
+    self.flags & HIDDEN_OBJECT_FLAG != 0
+  }
+
+  /// Access renames deleted objects to `~TMPCLP...` so they can be restored with undo.
+  pub fn is_deleted(&self) -> bool {
+    self.name.starts_with("~TMPCLP")
+  }
+
+  /// Temporary objects are created by Access while editing and are named `~TMP...`.
+  pub fn is_temporary(&self) -> bool {
+    self.name.starts_with("~TMP") && !self.is_deleted()
+  }
+
+  /// The catalog entry used to load this object with `Table::from_catalog_entry`.
+  pub fn to_catalog_entry(&self) -> Option<CatalogEntry> {
+    if self.object_type != ObjectType::Table {
+      return None;
+    }
+
+    Some(CatalogEntry::Table(TableCatalogEntry {
+      name: self.name.clone(),
+      page: self.page(),
+      flags: self.flags,
+    }))
+  }
+}
+
+fn load_system_objects_table(mdb: &mut Mdb) -> Result<Table, MdbError> {
+  mdb.read_page(2)?;
+
+  let system_objects_table_catalog_entry = CatalogEntry::Table(TableCatalogEntry {
+    name: "MSysObjects".to_string(),
+    page: 2,
+    flags: 0,
+  });
+
+  let mut system_objects_table = match Table::from_catalog_entry(system_objects_table_catalog_entry, mdb) {
+    Ok(system_objects_table) => system_objects_table,
+    Err(e) => {
+      eprintln!("Failed to load system objects table for catalog.");
+      return Err(e);
+    }
+  };
+
+  system_objects_table.read_columns()?;
+
+  Ok(system_objects_table)
+}
+
+/// Read every row of MSysObjects.
+pub fn read_system_objects(mdb: &mut Mdb) -> Result<Vec<SystemObject>, MdbError> {
+  let mut system_objects_table = load_system_objects_table(mdb)?;
+
+  let id_index = system_objects_table.find_column_index("Id").expect("Id column not found in system table.");
+  let name_index = system_objects_table.find_column_index("Name").expect("Name column not found in system table.");
+  let type_index = system_objects_table.find_column_index("Type").expect("Type column not found in system table.");
+  let flags_index = system_objects_table.find_column_index("Flags").expect("Flags column not found in system table.");
+  let parent_id_index = system_objects_table.find_column_index("ParentId");
+  let date_create_index = system_objects_table.find_column_index("DateCreate");
+  let date_update_index = system_objects_table.find_column_index("DateUpdate");
+  let owner_index = system_objects_table.find_column_index("Owner");
+  let connect_index = system_objects_table.find_column_index("Connect");
+  let database_index = system_objects_table.find_column_index("Database");
+  let foreign_name_index = system_objects_table.find_column_index("ForeignName");
+  let rmt_info_short_index = system_objects_table.find_column_index("RmtInfoShort");
+  let rmt_info_long_index = system_objects_table.find_column_index("RmtInfoLong");
+  let properties_index = system_objects_table.find_column_index("LvProp");
+  let extra_index = system_objects_table.find_column_index("LvExtra");
+
+  let mut system_objects: Vec<SystemObject> = Vec::new();
+
+  while system_objects_table.next_row()? {
+    let table = &system_objects_table;
+    let raw_type = get_u16(&table.columns[type_index].buffer.value, 0) as i16;
+
+    system_objects.push(SystemObject {
+      id: get_u32(&table.columns[id_index].buffer.value, 0) as i32,
+      parent_id: read_i32(table, parent_id_index).unwrap_or(0),
+      name: read_text(table, Some(name_index))?.unwrap_or_default(),
+      object_type: ObjectType::from(raw_type),
+      raw_type,
+      flags: get_u32(&table.columns[flags_index].buffer.value, 0),
+      date_create: read_datetime(table, date_create_index),
+      date_update: read_datetime(table, date_update_index),
+      owner: read_binary(table, owner_index),
+      connect: read_text(table, connect_index)?,
+      database: read_text(table, database_index)?,
+      foreign_name: read_text(table, foreign_name_index)?,
+      rmt_info_short: read_binary(table, rmt_info_short_index),
+      rmt_info_long: read_long_binary(table, rmt_info_long_index)?,
+      lv_prop: read_long_binary(table, properties_index)?,
+      lv_extra: read_long_binary(table, extra_index)?,
+    });
+  }
+
+  Ok(system_objects)
+}
+
+/// Load and read the columns of a table listed in the system objects.
+pub(crate) fn load_table(mdb: &Mdb, system_objects: &[SystemObject], name: &str) -> Result<Option<Table>, MdbError> {
+  let entry = match system_objects.iter().find(|object| object.object_type == ObjectType::Table && object.name == name) {
+    Some(object) => object.to_catalog_entry().unwrap(),
+    None => return Ok(None),
+  };
+
+  let mut table = Table::from_catalog_entry(entry, mdb)?;
+  table.read_columns()?;
+  Ok(Some(table))
+}
+
+pub fn read_catalog(mdb: &mut Mdb) -> Result<Vec<CatalogEntry>, MdbError> {
+  Ok(read_system_objects(mdb)?.iter().filter_map(|object| object.to_catalog_entry()).collect())
+}
+
+pub(crate) fn read_u8(table: &Table, index: Option<usize>) -> Option<u8> {
+  let column = &table.columns[index?];
+  if column.buffer.is_null || column.buffer.value.is_empty() {
+    return None;
+  }
+  Some(column.buffer.value[0])
+}
+
+pub(crate) fn read_i16(table: &Table, index: Option<usize>) -> Option<i16> {
+  let column = &table.columns[index?];
+  if column.buffer.is_null || column.buffer.value.len() < 2 {
+    return None;
+  }
+  Some(get_u16(&column.buffer.value, 0) as i16)
+}
+
+pub(crate) fn read_i32(table: &Table, index: Option<usize>) -> Option<i32> {
+  let column = &table.columns[index?];
+  if column.buffer.is_null || column.buffer.value.len() < 4 {
+    return None;
+  }
+  Some(get_u32(&column.buffer.value, 0) as i32)
+}
+
+pub(crate) fn read_datetime(table: &Table, index: Option<usize>) -> Option<NaiveDateTime> {
+  let column = &table.columns[index?];
+  if column.buffer.is_null || column.buffer.value.len() < 8 {
+    return None;
+  }
+  let raw_data: [u8; 8] = column.buffer.value[0..8].try_into().ok()?;
+  datetime_from_f64(f64::from_le_bytes(raw_data))
+}
+
+pub(crate) fn read_binary(table: &Table, index: Option<usize>) -> Option<Vec<u8>> {
+  let column = &table.columns[index?];
+  if column.buffer.is_null {
+    return None;
+  }
+  Some(column.buffer.value.clone())
+}
+
+pub(crate) fn read_long_binary(table: &Table, index: Option<usize>) -> Result<Option<Vec<u8>>, MdbError> {
+  let column = match index {
+    Some(index) => &table.columns[index],
+    None => return Ok(None),
+  };
+  if column.buffer.is_null {
+    return Ok(None);
+  }
+  Ok(Some(column.get_long_value(&table.mdb)?))
+}
+
+pub(crate) fn read_text(table: &Table, index: Option<usize>) -> Result<Option<String>, MdbError> {
+  let column = match index {
+    Some(index) => &table.columns[index],
+    None => return Ok(None),
+  };
+  if column.buffer.is_null {
+    return Ok(None);
+  }
+  if column.column_type == ColumnType::Memo {
+    return Ok(Some(column.get_memo_string(&table.mdb)?));
+  }
+  Ok(Some(decode_mdb_string(table.mdb.mdb_file.jet_version, table.mdb.encoding, &column.buffer.value)?))
+}
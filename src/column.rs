@@ -1,340 +1,489 @@
-use std::fmt::{Display, Formatter};
-
-use chrono::{DateTime, Duration};
-
-use encoding_rs::{Encoding};
-use crate::backend::{Backend, BackendType};
-
-use crate::conversion::decode_mdb_string;
-use crate::data::{ColBuffer, mdb_find_page_row_packed};
-use crate::error::MdbError;
-use crate::mdbfile::{Mdb, MdbFormatVersion};
-use crate::money::money_column_to_string;
-use crate::numeric::numeric_column_to_string;
-use crate::time::CDateTime;
-use crate::utils::{get_u32};
-
-pub struct Column {
-  pub name: String,
-  pub column_type: ColumnType,
-  pub(crate) number: u8,
-  pub(crate) row_column_number: u16,
-  pub scale: u8,
-  pub precision: u8,
-  pub(crate) is_fixed: bool,
-  pub(crate) is_long_auto: bool,
-  pub(crate) is_uuid_auto: bool,
-  pub is_hyperlink: bool,
-  pub(crate) fixed_offset: u16,
-  pub size: u16,
-  pub(crate) var_col_num: u16,
-  pub buffer: ColBuffer,
-  format: MdbFormatVersion,
-  encoding: &'static Encoding,
-
-  // Used for memo
-  column_text: Option<String>
-}
-
-impl Column {
-
-  pub fn extract_column_text(&mut self, mdb: &Mdb) -> Result<(), ()> {
-    let text = self.get_memo_string(mdb);
-    if text.is_err() {
-      return Err(());
-    }
-
-    self.column_text = Some(text.unwrap());
-
-    Ok(())
-  }
-
-  pub fn new(encoding: &'static Encoding) -> Self {
-    Column {
-      name: "".to_string(),
-      column_type: ColumnType::Bool,
-      number: 0,
-      row_column_number: 0,
-      scale: 0,
-      precision: 0,
-      is_fixed: false,
-      is_long_auto: false,
-      is_uuid_auto: false,
-      is_hyperlink: false,
-      fixed_offset: 0,
-      size: 0,
-      var_col_num: 0,
-      buffer: ColBuffer {
-        value: Vec::with_capacity(200000),
-        size: 0,
-        start: 0,
-        is_null: false,
-        is_fixed: false,
-        column_number: 0,
-        offset: 0,
-      },
-      format: MdbFormatVersion::JET4,
-      encoding,
-      column_text: None,
-    }
-  }
-}
-
-#[repr(u8)]
-#[derive(PartialEq, Copy, Clone, Debug)]
-pub enum ColumnType {
-  Bool = 0x01,
-  Byte = 0x02,
-  Int = 0x03,
-  LongInt = 0x04,
-  Money = 0x05,
-  Float = 0x06,
-  Double = 0x07,
-  Datetime = 0x08,
-  Binary = 0x09,
-  Text = 0x0a,
-  OLE = 0x0b,
-  Memo = 0x0c,
-  ReplicationId = 0x0f,
-  Numeric = 0x10,
-  Complex = 0x12,
-  ExtendedDatetime = 0x14,
-}
-
-impl Display for ColumnType {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    match self {
-      ColumnType::Bool => write!(f, "bool"),
-      ColumnType::Byte => write!(f, "byte"),
-      ColumnType::Int => write!(f, "int"),
-      ColumnType::LongInt => write!(f, "long int"),
-      ColumnType::Money => write!(f, "money"),
-      ColumnType::Float => write!(f, "float"),
-      ColumnType::Double => write!(f, "double"),
-      ColumnType::Datetime => write!(f, "datetime"),
-      ColumnType::Binary => write!(f, "binary"),
-      ColumnType::Text => write!(f, "text"),
-      ColumnType::OLE => write!(f, "ole"),
-      ColumnType::Memo => write!(f, "memo"),
-      ColumnType::ReplicationId => write!(f, "replication id"),
-      ColumnType::Numeric => write!(f, "numeric"),
-      ColumnType::Complex => write!(f, "complex"),
-      ColumnType::ExtendedDatetime => write!(f, "extended datetime"),
-    }
-  }
-}
-
-impl TryFrom<u8> for ColumnType {
-  type Error = ();
-
-  fn try_from(v: u8) -> Result<Self, Self::Error> {
-    match v {
-      v if v == ColumnType::Bool as u8 => Ok(ColumnType::Bool),
-      v if v == ColumnType::Byte as u8 => Ok(ColumnType::Byte),
-      v if v == ColumnType::Int as u8 => Ok(ColumnType::Int),
-      v if v == ColumnType::LongInt as u8 => Ok(ColumnType::LongInt),
-      v if v == ColumnType::Money as u8 => Ok(ColumnType::Money),
-      v if v == ColumnType::Float as u8 => Ok(ColumnType::Float),
-      v if v == ColumnType::Double as u8 => Ok(ColumnType::Double),
-      v if v == ColumnType::Datetime as u8 => Ok(ColumnType::Datetime),
-      v if v == ColumnType::Binary as u8 => Ok(ColumnType::Binary),
-      v if v == ColumnType::Text as u8 => Ok(ColumnType::Text),
-      v if v == ColumnType::OLE as u8 => Ok(ColumnType::OLE),
-      v if v == ColumnType::Memo as u8 => Ok(ColumnType::Memo),
-      v if v == ColumnType::ReplicationId as u8 => Ok(ColumnType::ReplicationId),
-      v if v == ColumnType::Numeric as u8 => Ok(ColumnType::Numeric),
-      v if v == ColumnType::Complex as u8 => Ok(ColumnType::Complex),
-      v if v == ColumnType::ExtendedDatetime as u8 => Ok(ColumnType::ExtendedDatetime),
-      _ => Err(()),
-    }
-  }
-}
-
-impl Column {
-  pub fn get_memo_string(&self, mdb: &Mdb) -> Result<String, MdbError> {
-    let mut mdb = mdb.clone();
-
-    const MEMO_OVERHEAD: usize = 12;
-    if self.column_type != ColumnType::Memo {
-      panic!("Calling print_memo on non-memo column.");
-    }
-
-    if self.buffer.is_null || self.buffer.value.len() == 0 {
-      return Ok(String::new());
-    }
-
-    if !self.buffer.value.len() < 12 {
-      return Ok(String::new());
-    }
-
-    let memo_length = get_u32(&self.buffer.value, 0) as usize;
-
-    if memo_length & 0x80000000 != 0 {
-      /* inline memo field */
-      decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, &self.buffer.value[MEMO_OVERHEAD..])
-    } else if memo_length & 0x40000000 != 0 {
-      /* single-page memo field */
-      let page_row = get_u32(&self.buffer.value, 4);
-
-      let memo_row =  mdb_find_page_row_packed(&mut mdb, page_row)?;
-      decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, &mdb.page_buffer[(memo_row.start as usize)..(memo_row.start + memo_row.length) as usize])
-    } else if (memo_length & 0xff000000) == 0 {
-
-      let mut page_row = get_u32(&self.buffer.value, 4);
-      let mut temp_offset = 0;
-      let mut buffer: Vec<u8> = vec![0; memo_length];
-
-      loop {
-        let memo_string_row = mdb_find_page_row_packed(&mut mdb, page_row)?;
-
-        if temp_offset + memo_string_row.length as usize - 4 > memo_length {
-          break;
-        }
-
-        /* Stop processing on zero length multiple page memo fields */
-        if memo_string_row.length < 4 {
-          break;
-        }
-
-        buffer[(temp_offset as usize)..((temp_offset + memo_string_row.length as usize - 4) as usize)].copy_from_slice(&mdb.page_buffer[(memo_string_row.start as usize + 4)..(memo_string_row.start as usize + 4 + memo_string_row.length as usize - 4)]);
-        temp_offset += memo_string_row.length as usize - 4;
-
-        page_row = get_u32(&mdb.page_buffer, memo_string_row.start as usize);
-        if page_row == 0 {
-          break;
-        }
-      }
-
-      if temp_offset < memo_length {
-        eprintln!("Warning: incorrect memo length");
-      }
-
-      decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, &buffer[..temp_offset])
-    } else {
-      Err(MdbError::UnhandledType)
-    }
-  }
-
-  pub fn get_backend_type<'a>(&self, backend: &Backend<'a>) -> BackendType<'a> {
-    match self.column_type {
-      ColumnType::Bool => backend.mdb_bool,
-      ColumnType::Byte => backend.mdb_byte,
-      ColumnType::Int => backend.mdb_int,
-      ColumnType::LongInt => backend.mdb_longint,
-      ColumnType::Money => backend.mdb_money,
-      ColumnType::Float => backend.mdb_float,
-      ColumnType::Double => backend.mdb_double,
-      ColumnType::Datetime => backend.mdb_datetime,
-      ColumnType::Binary => backend.mdb_binary,
-      ColumnType::Text => backend.mdb_text,
-      ColumnType::OLE => backend.mdb_ole,
-      ColumnType::Memo => backend.mdb_memo,
-      ColumnType::ReplicationId => backend.mdb_replication_id,
-      ColumnType::Numeric => backend.mdb_numeric,
-      ColumnType::Complex => backend.mdb_complex,
-      ColumnType::ExtendedDatetime => backend.mdb_extended_datetime,
-    }
-  }
-
-}
-
-impl Display for Column {
-
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-
-    if self.buffer.is_null {
-      match self.column_type {
-        ColumnType::Bool => (),
-        _ => {
-          return write!(f, "NULL");
-        }
-      }
-    }
-
-    match self.column_type {
-      ColumnType::Int => {
-        let raw_data: [u8; 2] = self.buffer.value[0..2].try_into().unwrap();
-        write!(f, "{}", i16::from_le_bytes(raw_data))
-      },
-      ColumnType::LongInt => {
-        let raw_data: [u8; 4] = self.buffer.value[0..4].try_into().unwrap();
-        write!(f, "{}", i32::from_le_bytes(raw_data))
-      },
-      ColumnType::Float => {
-        let raw_data: [u8; 4] = self.buffer.value[0..4].try_into().unwrap();
-        write!(f, "{}", f32::from_le_bytes(raw_data))
-      },
-      ColumnType::Double => {
-        let raw_data: [u8; 8] = self.buffer.value[0..8].try_into().unwrap();
-        write!(f, "{}", f64::from_le_bytes(raw_data))
-      },
-      ColumnType::Text => {
-        if self.buffer.is_null {
-          return write!(f, "");
-        }
-        write!(f, "{}", decode_mdb_string(self.format, self.encoding, &self.buffer.value).expect("Failed to convert"))
-      },
-      ColumnType::Binary => {
-        if self.buffer.is_null {
-          return write!(f, "");
-        }
-        for byte in &self.buffer.value {
-          write!(f, "{:x}", byte)?
-        }
-        Ok(())
-      },
-      ColumnType::Memo => {
-        write!(f, "{}", self.column_text.as_ref().unwrap())
-      }
-      ColumnType::ExtendedDatetime => {
-        let mut days: i64 = 0;
-        for (i, x) in self.buffer.value[12..19].iter().enumerate() {
-          days += i64::pow(10, (6 - i) as u32) * (x - 48) as i64;
-        }
-
-        let mut nanoseconds: i64 = 0;
-        for (i, x) in self.buffer.value[32..39].iter().enumerate() {
-          nanoseconds += i64::pow(10, (6 - i) as u32) * (x - 48) as i64;
-        }
-        nanoseconds *= 100;
-
-        let mut seconds: i64 = 0;
-        for (i, x) in self.buffer.value[27..32].iter().enumerate() {
-          seconds += i64::pow(10, (4 - i) as u32) * (x - 48) as i64;
-        }
-
-        //print!("{:#02}:{:#02}:{:#02}.{} ==== ", (seconds / 3600) % 24, (seconds / 60) % 60, seconds % 60, nanoseconds);
-        let start_datetime = DateTime::parse_from_rfc3339("0001-01-01T00:00:00+00:00").expect("failed");
-        let datetime = start_datetime + Duration::days(days) + Duration::seconds(seconds) + Duration::nanoseconds(nanoseconds);
-        write!(f, "{}", datetime.to_rfc3339())
-      },
-      ColumnType::Datetime => {
-        let raw_data: [u8; 8] = self.buffer.value[0..8].try_into().expect("");
-        let raw_time = f64::from_le_bytes(raw_data);
-        let dt = CDateTime::from_f64(raw_time);
-        write!(f, "{:0>2}/{:0>2}/{} {:0>2}:{:0>2}:{:0>2}", dt.month + 1, dt.month_day, 1900 + dt.year, dt.hour, dt.minute, dt.second)
-      },
-      ColumnType::ReplicationId => {
-        write!(f, "{{{:X}{:X}{:X}{:X}-{:X}{:X}-{:X}{:X}-{:X}{:X}-{:X}{:X}{:X}{:X}{:X}{:X}}}",
-               self.buffer.value[3], self.buffer.value[2], self.buffer.value[1], self.buffer.value[0],
-               self.buffer.value[5], self.buffer.value[4],
-               self.buffer.value[7], self.buffer.value[6],
-               self.buffer.value[8], self.buffer.value[9],
-               self.buffer.value[10], self.buffer.value[11], self.buffer.value[12], self.buffer.value[13], self.buffer.value[14], self.buffer.value[15]
-        )
-      },
-      ColumnType::Numeric => {
-        write!(f, "{}", numeric_column_to_string(&self.buffer.value, self.precision))
-      },
-      ColumnType::Money => {
-        write!(f, "{}", money_column_to_string(&self.buffer.value))
-      },
-      ColumnType::Bool => {
-        write!(f, "{}", self.buffer.is_null)
-      },
-      _ => {
-        write!(f, "todo")
-      }
-    }
-  }
-}
\ No newline at end of file
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::sync::{Arc, OnceLock};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+
+use encoding_rs::{Encoding};
+use crate::backend::{Backend, BackendType};
+
+use crate::complex::{ComplexColumnKind, ComplexValues};
+use crate::conversion::decode_mdb_string;
+use crate::data::ColBuffer;
+use crate::error::MdbError;
+use crate::long_value::{LongValueHeader, LongValueReader};
+use crate::mdbfile::{Mdb, MdbFormatVersion};
+use crate::money::money_column_value;
+use crate::numeric::{calculated_numeric_value, numeric_column_value};
+use crate::time::datetime_from_f64;
+use crate::value::Value;
+
+#[derive(Clone)]
+pub struct Column {
+  pub name: String,
+  pub column_type: ColumnType,
+  pub(crate) number: u8,
+  pub(crate) row_column_number: u16,
+  pub scale: u8,
+  pub precision: u8,
+  /// The ComplexID of a complex column in MSysComplexColumns.
+  pub complex_id: Option<i32>,
+  pub flags: ColumnFlags,
+  pub(crate) fixed_offset: u16,
+  pub size: u16,
+  pub(crate) var_col_num: u16,
+  pub buffer: ColBuffer,
+  format: MdbFormatVersion,
+  encoding: &'static Encoding,
+
+  // The bytes of a memo or OLE value, read the first time the value of the current row is needed.
+  long_value: OnceLock<Vec<u8>>,
+  // The flat table of a multivalued column, loaded the first time a value is needed.
+  multi_values: OnceLock<Option<Arc<ComplexValues>>>,
+  /// The database long values and the values of complex columns are read from, set for memo, OLE and complex
+  /// columns by `Table::read_columns`.
+  pub(crate) mdb: Option<Mdb>,
+  /// Whether the column is decoded when rows are read: it is selected with `Table::select`, or read by the filter.
+  pub(crate) decoded: bool,
+}
+
+impl Column {
+
+  /// Read the long value of the current row now rather than when it is first used.
+  pub fn extract_long_value(&mut self, mdb: &Mdb) -> Result<(), MdbError> {
+    self.long_value = OnceLock::from(self.get_long_value(mdb)?);
+
+    Ok(())
+  }
+
+  /// Forget the long value of the previous row.
+  pub(crate) fn clear_long_value(&mut self) {
+    self.long_value = OnceLock::new();
+  }
+
+  /// The header of the memo or OLE value in the current row, or None for other columns and null values.
+  pub fn long_value_header(&self) -> Option<LongValueHeader> {
+    if (self.column_type != ColumnType::Memo && self.column_type != ColumnType::OLE) || self.buffer.is_null {
+      return None;
+    }
+    LongValueHeader::from_bytes(&self.buffer.value)
+  }
+
+  /// The bytes of the memo or OLE value in the current row. LVAL pages are only read the first time it is called
+  /// for a row.
+  pub fn long_value(&self) -> Result<&[u8], MdbError> {
+    if let Some(bytes) = self.long_value.get() {
+      return Ok(bytes);
+    }
+    let bytes = self.get_long_value(self.mdb.as_ref().ok_or(MdbError::UnhandledType)?)?;
+    Ok(self.long_value.get_or_init(|| bytes))
+  }
+
+  /// The flat table holding the values of a multivalued column, or None for other columns.
+  fn multi_values(&self) -> Result<Option<&ComplexValues>, MdbError> {
+    if let Some(multi_values) = self.multi_values.get() {
+      return Ok(multi_values.as_deref());
+    }
+    let (Some(mdb), Some(complex_id)) = (&self.mdb, self.complex_id) else {
+      return Ok(None);
+    };
+    let values = mdb.complex.values(mdb, complex_id)?;
+    let multi_values = (values.kind() == ComplexColumnKind::MultiValue).then_some(values);
+    Ok(self.multi_values.get_or_init(|| multi_values).as_deref())
+  }
+
+  pub fn new(encoding: &'static Encoding) -> Self {
+    Column {
+      name: "".to_string(),
+      column_type: ColumnType::Bool,
+      number: 0,
+      row_column_number: 0,
+      scale: 0,
+      precision: 0,
+      complex_id: None,
+      flags: ColumnFlags::default(),
+      fixed_offset: 0,
+      size: 0,
+      var_col_num: 0,
+      buffer: ColBuffer {
+        value: Vec::with_capacity(200000),
+        size: 0,
+        start: 0,
+        is_null: false,
+        is_fixed: false,
+        column_number: 0,
+        offset: 0,
+      },
+      format: MdbFormatVersion::JET4,
+      encoding,
+      long_value: OnceLock::new(),
+      multi_values: OnceLock::new(),
+      mdb: None,
+      decoded: true,
+    }
+  }
+}
+
+// Bits of the flags byte of a column definition.
+pub const FIXED_LENGTH_COLUMN_FLAG: u8 = 0x01;
+pub const NULLABLE_COLUMN_FLAG: u8 = 0x02;
+pub const AUTO_LONG_COLUMN_FLAG: u8 = 0x04;
+pub const REPLICATION_COLUMN_FLAG: u8 = 0x10;
+pub const AUTO_GUID_COLUMN_FLAG: u8 = 0x40;
+pub const HYPERLINK_COLUMN_FLAG: u8 = 0x80;
+
+// Bits of the extended flags byte, which follows the flags in Jet 4 and later.
+pub const COMPRESSED_UNICODE_COLUMN_FLAG: u8 = 0x01;
+pub const CALCULATED_COLUMN_FLAG: u8 = 0xC0;
+
+// The stored result of a calculated column is wrapped in a header ending with the length of the result, and followed
+// by a few more bytes.
+const CALCULATED_VALUE_LENGTH_OFFSET: usize = 16;
+const CALCULATED_VALUE_OFFSET: usize = 20;
+
+/// The flags of a column definition.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub struct ColumnFlags {
+  pub flags: u8,
+  pub extended_flags: u8,
+  /// Complex columns (attachments, multivalued fields and version history) have the same flags as AutoNumber long
+  /// integers, so this comes from the type of the column.
+  pub complex: bool,
+}
+
+impl ColumnFlags {
+  pub fn new(flags: u8, extended_flags: u8) -> Self {
+    ColumnFlags { flags, extended_flags, complex: false }
+  }
+
+  /// The flags of a column of type `column_type`.
+  pub fn for_type(column_type: ColumnType, flags: u8, extended_flags: u8) -> Self {
+    ColumnFlags { flags, extended_flags, complex: column_type == ColumnType::Complex }
+  }
+
+  pub fn is_fixed(&self) -> bool {
+    self.flags & FIXED_LENGTH_COLUMN_FLAG != 0
+  }
+
+  /// Set on nearly every column. Whether a value is required comes from the Required property instead.
+  pub fn is_nullable(&self) -> bool {
+    self.flags & NULLABLE_COLUMN_FLAG != 0
+  }
+
+  /// An AutoNumber long integer column.
+  pub fn is_long_auto(&self) -> bool {
+    self.flags & AUTO_LONG_COLUMN_FLAG != 0 && !self.is_complex()
+  }
+
+  /// An AutoNumber replication ID column.
+  pub fn is_uuid_auto(&self) -> bool {
+    self.flags & AUTO_GUID_COLUMN_FLAG != 0
+  }
+
+  pub fn is_hyperlink(&self) -> bool {
+    self.flags & HYPERLINK_COLUMN_FLAG != 0
+  }
+
+  /// A column added by replication, such as `s_GUID` or `Gen_*`.
+  pub fn is_replication(&self) -> bool {
+    self.flags & REPLICATION_COLUMN_FLAG != 0
+  }
+
+  /// Text stored with Unicode compression.
+  pub fn is_compressed_unicode(&self) -> bool {
+    self.extended_flags & COMPRESSED_UNICODE_COLUMN_FLAG != 0
+  }
+
+  /// A column whose value is computed from an expression.
+  pub fn is_calculated(&self) -> bool {
+    self.extended_flags & CALCULATED_COLUMN_FLAG == CALCULATED_COLUMN_FLAG
+  }
+
+  pub fn is_complex(&self) -> bool {
+    self.complex
+  }
+
+  /// The names of the flags that are set.
+  pub fn names(&self) -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = Vec::new();
+    if self.is_complex() {
+      names.push("complex");
+    } else {
+      if self.is_fixed() {
+        names.push("fixed");
+      }
+      if self.is_nullable() {
+        names.push("nullable");
+      }
+      if self.is_long_auto() {
+        names.push("auto long");
+      }
+    }
+    if self.is_replication() {
+      names.push("replication");
+    }
+    if self.is_uuid_auto() {
+      names.push("auto guid");
+    }
+    if self.is_hyperlink() {
+      names.push("hyperlink");
+    }
+    if self.is_compressed_unicode() {
+      names.push("compressed unicode");
+    }
+    if self.is_calculated() {
+      names.push("calculated");
+    }
+    names
+  }
+}
+
+impl Display for ColumnFlags {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.names().join(", "))
+  }
+}
+
+#[repr(u8)]
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ColumnType {
+  Bool = 0x01,
+  Byte = 0x02,
+  Int = 0x03,
+  LongInt = 0x04,
+  Money = 0x05,
+  Float = 0x06,
+  Double = 0x07,
+  Datetime = 0x08,
+  Binary = 0x09,
+  Text = 0x0a,
+  OLE = 0x0b,
+  Memo = 0x0c,
+  ReplicationId = 0x0f,
+  Numeric = 0x10,
+  Complex = 0x12,
+  ExtendedDatetime = 0x14,
+}
+
+impl Display for ColumnType {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ColumnType::Bool => write!(f, "bool"),
+      ColumnType::Byte => write!(f, "byte"),
+      ColumnType::Int => write!(f, "int"),
+      ColumnType::LongInt => write!(f, "long int"),
+      ColumnType::Money => write!(f, "money"),
+      ColumnType::Float => write!(f, "float"),
+      ColumnType::Double => write!(f, "double"),
+      ColumnType::Datetime => write!(f, "datetime"),
+      ColumnType::Binary => write!(f, "binary"),
+      ColumnType::Text => write!(f, "text"),
+      ColumnType::OLE => write!(f, "ole"),
+      ColumnType::Memo => write!(f, "memo"),
+      ColumnType::ReplicationId => write!(f, "replication id"),
+      ColumnType::Numeric => write!(f, "numeric"),
+      ColumnType::Complex => write!(f, "complex"),
+      ColumnType::ExtendedDatetime => write!(f, "extended datetime"),
+    }
+  }
+}
+
+impl TryFrom<u8> for ColumnType {
+  type Error = ();
+
+  fn try_from(v: u8) -> Result<Self, Self::Error> {
+    match v {
+      v if v == ColumnType::Bool as u8 => Ok(ColumnType::Bool),
+      v if v == ColumnType::Byte as u8 => Ok(ColumnType::Byte),
+      v if v == ColumnType::Int as u8 => Ok(ColumnType::Int),
+      v if v == ColumnType::LongInt as u8 => Ok(ColumnType::LongInt),
+      v if v == ColumnType::Money as u8 => Ok(ColumnType::Money),
+      v if v == ColumnType::Float as u8 => Ok(ColumnType::Float),
+      v if v == ColumnType::Double as u8 => Ok(ColumnType::Double),
+      v if v == ColumnType::Datetime as u8 => Ok(ColumnType::Datetime),
+      v if v == ColumnType::Binary as u8 => Ok(ColumnType::Binary),
+      v if v == ColumnType::Text as u8 => Ok(ColumnType::Text),
+      v if v == ColumnType::OLE as u8 => Ok(ColumnType::OLE),
+      v if v == ColumnType::Memo as u8 => Ok(ColumnType::Memo),
+      v if v == ColumnType::ReplicationId as u8 => Ok(ColumnType::ReplicationId),
+      v if v == ColumnType::Numeric as u8 => Ok(ColumnType::Numeric),
+      v if v == ColumnType::Complex as u8 => Ok(ColumnType::Complex),
+      v if v == ColumnType::ExtendedDatetime as u8 => Ok(ColumnType::ExtendedDatetime),
+      _ => Err(()),
+    }
+  }
+}
+
+impl Column {
+  pub fn get_memo_string(&self, mdb: &Mdb) -> Result<String, MdbError> {
+    if self.column_type != ColumnType::Memo {
+      return Err(MdbError::UnhandledType);
+    }
+
+    let buffer = self.get_long_value(mdb)?;
+    decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, &buffer)
+  }
+
+  /// Read the raw bytes of a memo or OLE column, following LVAL pages as needed.
+  pub fn get_long_value(&self, mdb: &Mdb) -> Result<Vec<u8>, MdbError> {
+    if self.column_type != ColumnType::Memo && self.column_type != ColumnType::OLE {
+      return Err(MdbError::UnhandledType);
+    }
+    if self.buffer.is_null {
+      return Ok(Vec::new());
+    }
+
+    let reader = LongValueReader::new(mdb, &self.buffer.value)?;
+    let limit = mdb.options.max_long_value_size.unwrap_or(usize::MAX);
+    if reader.len() > limit && mdb.options.strict {
+      return Err(MdbError::LongValueTooLarge);
+    }
+
+    let mut buffer = Vec::with_capacity(reader.len().min(limit));
+    reader.take(limit as u64).read_to_end(&mut buffer)?;
+    Ok(buffer)
+  }
+
+  /// Replace the stored value of a calculated column in the current row with its result, which is decoded as the
+  /// type of the column.
+  pub(crate) fn unwrap_calculated_value(&mut self) {
+    if !self.flags.is_calculated() || self.buffer.is_null {
+      return;
+    }
+    let result = unwrap_calculated_value(&self.buffer.value).map(|result| result.to_vec());
+    match result {
+      Some(result) => self.buffer.value = result,
+      None => self.buffer.is_null = true,
+    }
+  }
+
+  /// The id a complex column has for the current row in its flat table, or None for other columns and null values.
+  pub fn complex_value(&self) -> Option<i32> {
+    if self.column_type != ColumnType::Complex || self.buffer.is_null {
+      return None;
+    }
+    self.buffer.value.get(0..4).map(|raw| i32::from_le_bytes(raw.try_into().unwrap()))
+  }
+
+  /// Stream the memo or OLE value of the current row. See `LongValueReader`.
+  pub fn long_value_reader(&self) -> Result<LongValueReader, MdbError> {
+    if self.column_type != ColumnType::Memo && self.column_type != ColumnType::OLE {
+      return Err(MdbError::UnhandledType);
+    }
+    let mdb = self.mdb.as_ref().ok_or(MdbError::UnhandledType)?;
+    let bytes = if self.buffer.is_null || !self.decoded { &[][..] } else { &self.buffer.value[..] };
+    LongValueReader::new(mdb, bytes)
+  }
+
+  /// The value of the column in the current row. Memos and OLE values that can't be read are null; use
+  /// `Column::try_value` to get the error instead.
+  pub fn value(&self) -> Value {
+    self.try_value().unwrap_or(Value::Null)
+  }
+
+  /// The value as a scan returns it: unreadable long values are an error in strict mode and null otherwise.
+  pub(crate) fn scan_value(&self) -> Result<Value, MdbError> {
+    match self.try_value() {
+      Err(err) if self.mdb.as_ref().is_some_and(|mdb| mdb.options.strict) => Err(err),
+      res => Ok(res.unwrap_or(Value::Null)),
+    }
+  }
+
+  /// The value of the column in the current row, reading the LVAL pages of a memo or OLE value if they haven't
+  /// been.
+  pub fn try_value(&self) -> Result<Value, MdbError> {
+    // Columns left out by `Table::select` aren't decoded.
+    if !self.decoded {
+      return Ok(Value::Null);
+    }
+    // The value of a boolean column is its bit in the null mask, except for calculated columns, which store it.
+    if self.column_type == ColumnType::Bool && !self.flags.is_calculated() {
+      return Ok(Value::Bool(!self.buffer.is_null));
+    }
+    if self.buffer.is_null {
+      return Ok(Value::Null);
+    }
+
+    let data = &self.buffer.value;
+    let value = match self.column_type {
+      ColumnType::Bool => data.first().map(|byte| Value::Bool(*byte != 0)),
+      ColumnType::Byte => data.first().map(|byte| Value::U8(*byte)),
+      ColumnType::Int => data.get(0..2).map(|raw| Value::I16(i16::from_le_bytes(raw.try_into().unwrap()))),
+      ColumnType::LongInt => data.get(0..4).map(|raw| Value::I32(i32::from_le_bytes(raw.try_into().unwrap()))),
+      ColumnType::Float => data.get(0..4).map(|raw| Value::F32(f32::from_le_bytes(raw.try_into().unwrap()))),
+      ColumnType::Double => data.get(0..8).map(|raw| Value::F64(f64::from_le_bytes(raw.try_into().unwrap()))),
+      ColumnType::Money => money_column_value(data).map(Value::Currency),
+      ColumnType::Numeric if self.flags.is_calculated() => {
+        calculated_numeric_value(data).map(|(value, scale)| Value::Decimal { value, scale })
+      }
+      ColumnType::Numeric => numeric_column_value(data).map(|value| Value::Decimal { value, scale: self.scale }),
+      ColumnType::Datetime => data.get(0..8)
+        .and_then(|raw| datetime_from_f64(f64::from_le_bytes(raw.try_into().unwrap())))
+        .map(Value::DateTime),
+      ColumnType::ExtendedDatetime => extended_datetime(data).map(Value::ExtendedDateTime),
+      ColumnType::ReplicationId => data.get(0..16).map(|raw| Value::Guid(raw.try_into().unwrap())),
+      ColumnType::Text => decode_mdb_string(self.format, self.encoding, data).ok().map(Value::Text),
+      ColumnType::Binary => Some(Value::Binary(data.clone())),
+      ColumnType::Memo => decode_mdb_string(self.format, self.encoding, self.long_value()?).ok().map(Value::Text),
+      ColumnType::OLE => Some(Value::Binary(self.long_value()?.to_vec())),
+      // Multivalued fields are a list of their values, and other complex columns the id of their values.
+      ColumnType::Complex => match (self.complex_value(), self.multi_values()?) {
+        (Some(complex_value), Some(multi_values)) => Some(Value::List(multi_values.list(complex_value)?)),
+        (complex_value, None) => complex_value.map(Value::I32),
+        (None, Some(_)) => None,
+      },
+    };
+
+    // Values that could not be decoded are returned as their raw bytes.
+    Ok(value.unwrap_or_else(|| Value::Binary(data.clone())))
+  }
+
+  pub fn get_backend_type<'a>(&self, backend: &Backend<'a>) -> BackendType<'a> {
+    match self.column_type {
+      ColumnType::Bool => backend.mdb_bool,
+      ColumnType::Byte => backend.mdb_byte,
+      ColumnType::Int => backend.mdb_int,
+      ColumnType::LongInt => backend.mdb_longint,
+      ColumnType::Money => backend.mdb_money,
+      ColumnType::Float => backend.mdb_float,
+      ColumnType::Double => backend.mdb_double,
+      ColumnType::Datetime => backend.mdb_datetime,
+      ColumnType::Binary => backend.mdb_binary,
+      ColumnType::Text => backend.mdb_text,
+      ColumnType::OLE => backend.mdb_ole,
+      ColumnType::Memo => backend.mdb_memo,
+      ColumnType::ReplicationId => backend.mdb_replication_id,
+      ColumnType::Numeric => backend.mdb_numeric,
+      ColumnType::Complex => backend.mdb_complex,
+      ColumnType::ExtendedDatetime => backend.mdb_extended_datetime,
+    }
+  }
+
+}
+
+impl Display for Column {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.value())
+  }
+}
+
+/// The result of a calculated column in the bytes stored in a row, or None if they are too short to hold it.
+pub fn unwrap_calculated_value(data: &[u8]) -> Option<&[u8]> {
+  let length = data.get(CALCULATED_VALUE_LENGTH_OFFSET..CALCULATED_VALUE_OFFSET)?;
+  let length = u32::from_le_bytes(length.try_into().unwrap()) as usize;
+  data.get(CALCULATED_VALUE_OFFSET..CALCULATED_VALUE_OFFSET.checked_add(length)?)
+}
+
+/// Decode a Date/Time Extended value, stored as text: days and seconds since 0001-01-01 and the fraction of a second
+/// in 100 nanosecond units.
+fn extended_datetime(data: &[u8]) -> Option<NaiveDateTime> {
+  let number = |range: std::ops::Range<usize>| -> Option<i64> {
+    std::str::from_utf8(data.get(range)?).ok()?.parse().ok()
+  };
+  let days = number(12..19)?;
+  let seconds = number(27..32)?;
+  let nanoseconds = number(32..39)? * 100;
+
+  let start = NaiveDate::from_ymd_opt(1, 1, 1)?.and_hms_opt(0, 0, 0)?;
+  Some(start + Duration::days(days) + Duration::seconds(seconds) + Duration::nanoseconds(nanoseconds))
+}
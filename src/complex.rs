@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::NaiveDateTime;
+use flate2::read::ZlibDecoder;
+
+use crate::catalog::{load_table, read_i32, read_system_objects, read_text};
+use crate::column::{Column, ColumnType};
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+use crate::row::Row;
+use crate::table::Table;
+use crate::utils::get_u32;
+use crate::value::Value;
+
+// How the FileData of an attachment is stored.
+const ATTACHMENT_RAW: u32 = 0;
+const ATTACHMENT_COMPRESSED: u32 = 1;
+
+/// The page and row of each row of a flat table, by the complex value they belong to.
+type RowLocations = HashMap<i32, Vec<(u32, u16)>>;
+
+/// What a complex column holds, from the columns of its type table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComplexColumnKind {
+  Attachment,
+  /// A multivalued lookup field, with a single value column.
+  MultiValue,
+  /// The edits of an append only memo.
+  VersionHistory,
+  Unknown,
+}
+
+/// A complex column, read from MSysComplexColumns. The values of every row are stored in a hidden flat table, and
+/// the column itself only holds the id its rows share there.
+#[derive(Clone)]
+pub struct ComplexColumn {
+  pub name: String,
+  pub complex_id: i32,
+  /// The table the column belongs to.
+  pub table: String,
+  /// The hidden table holding the values, named `f_<GUID>_<column>`.
+  pub flat_table: String,
+  /// The template of the flat table, such as MSysComplexType_Attachment.
+  pub type_table: String,
+  pub kind: ComplexColumnKind,
+  /// The column of the flat table holding the id of the row the values belong to.
+  pub foreign_key: String,
+  /// The columns of the flat table holding the values, in the order of the type table.
+  pub value_columns: Vec<Column>,
+}
+
+impl ComplexColumn {
+  /// The name of the child table the column is normalized into, the table and column names joined by an underscore.
+  pub fn child_table_name(&self) -> String {
+    format!("{}_{}", self.table, self.name)
+  }
+
+  /// The column holding the values of a multivalued field.
+  pub fn value_column(&self) -> Option<&Column> {
+    match self.kind {
+      ComplexColumnKind::MultiValue => self.value_columns.first(),
+      _ => None,
+    }
+  }
+}
+
+/// Read every complex column in the database. They are only read once for each opened database.
+pub fn read_complex_columns(mdb: &mut Mdb) -> Result<Vec<ComplexColumn>, MdbError> {
+  let cache = mdb.complex.clone();
+  Ok(cache.columns(mdb)?.to_vec())
+}
+
+/// The complex columns of a database and their values, shared by every copy of its `Mdb` so that MSysComplexColumns
+/// and the flat tables are read once.
+#[derive(Default)]
+pub(crate) struct ComplexCache {
+  complex: OnceLock<Complex>,
+}
+
+struct Complex {
+  columns: Vec<ComplexColumn>,
+  values: HashMap<i32, Arc<ComplexValues>>,
+}
+
+impl ComplexCache {
+  fn complex(&self, mdb: &Mdb) -> Result<&Complex, MdbError> {
+    if let Some(complex) = self.complex.get() {
+      return Ok(complex);
+    }
+    // The flat tables get a cache of their own, as the values would otherwise keep alive the cache holding them.
+    let mut mdb = mdb.clone();
+    mdb.complex = Arc::default();
+    let complex = read_complex(&mut mdb)?;
+    Ok(self.complex.get_or_init(|| complex))
+  }
+
+  pub(crate) fn columns(&self, mdb: &Mdb) -> Result<&[ComplexColumn], MdbError> {
+    Ok(&self.complex(mdb)?.columns)
+  }
+
+  /// The values of the complex column with this id.
+  pub(crate) fn values(&self, mdb: &Mdb, complex_id: i32) -> Result<Arc<ComplexValues>, MdbError> {
+    self.complex(mdb)?.values.get(&complex_id).cloned().ok_or(MdbError::InvalidComplexColumn)
+  }
+}
+
+/// Read the complex columns of MSysComplexColumns and the flat tables holding their values.
+fn read_complex(mdb: &mut Mdb) -> Result<Complex, MdbError> {
+  let system_objects = read_system_objects(mdb)?;
+  let mut table = match load_table(mdb, &system_objects, "MSysComplexColumns")? {
+    Some(table) => table,
+    None => return Ok(Complex { columns: Vec::new(), values: HashMap::new() }),
+  };
+
+  let name_index = table.find_column_index("ColumnName");
+  let complex_id_index = table.find_column_index("ComplexID");
+  let type_table_index = table.find_column_index("ComplexTypeObjectID");
+  let table_index = table.find_column_index("ConceptualTableID");
+  let flat_table_index = table.find_column_index("FlatTableID");
+
+  // The tables are stored by their id in MSysObjects.
+  let object_name = |index: Option<usize>, table: &Table| {
+    let id = read_i32(table, index)?;
+    system_objects.iter().find(|object| object.id == id).map(|object| object.name.clone())
+  };
+
+  let mut columns: Vec<ComplexColumn> = Vec::new();
+  let mut values = HashMap::new();
+  while table.next_row()? {
+    let flat_table = object_name(flat_table_index, &table).unwrap_or_default();
+    let type_table = object_name(type_table_index, &table).unwrap_or_default();
+    let flat = load_table(mdb, &system_objects, &flat_table)?.ok_or(MdbError::InvalidComplexColumn)?;
+    let template = load_table(mdb, &system_objects, &type_table)?.ok_or(MdbError::InvalidComplexColumn)?;
+
+    let value_columns = template.columns.iter()
+      .filter_map(|column| flat.columns.iter().find(|flat_column| flat_column.name == column.name).cloned())
+      .collect();
+    let complex_column = ComplexColumn {
+      name: read_text(&table, name_index)?.unwrap_or_default(),
+      complex_id: read_i32(&table, complex_id_index).unwrap_or(0),
+      table: object_name(table_index, &table).unwrap_or_default(),
+      flat_table,
+      type_table,
+      kind: complex_column_kind(&template),
+      foreign_key: foreign_key(&flat, &template)?,
+      value_columns,
+    };
+    values.insert(complex_column.complex_id, Arc::new(ComplexValues::new(flat, &complex_column)?));
+    columns.push(complex_column);
+  }
+
+  Ok(Complex { columns, values })
+}
+
+/// Attachments have the FileData column, version history a memo and the date of each version, and multivalued
+/// fields the values alone.
+fn complex_column_kind(type_table: &Table) -> ComplexColumnKind {
+  let has_type = |column_type: ColumnType| type_table.columns.iter().any(|column| column.column_type == column_type);
+  if type_table.find_column_index("FileData").is_some() {
+    ComplexColumnKind::Attachment
+  } else if type_table.columns.len() == 2 && has_type(ColumnType::Memo) && has_type(ColumnType::Datetime) {
+    ComplexColumnKind::VersionHistory
+  } else if type_table.columns.len() == 1 {
+    ComplexColumnKind::MultiValue
+  } else {
+    ComplexColumnKind::Unknown
+  }
+}
+
+/// Besides the columns of its type table, a flat table has an autonumber key and the foreign key, which is the
+/// other long integer column.
+fn foreign_key(flat_table: &Table, type_table: &Table) -> Result<String, MdbError> {
+  flat_table.columns.iter()
+    .find(|column| {
+      column.column_type == ColumnType::LongInt && !column.flags.is_long_auto()
+        && type_table.find_column_index(&column.name).is_none()
+    })
+    .map(|column| column.name.clone())
+    .ok_or(MdbError::InvalidComplexColumn)
+}
+
+/// The values of a complex column, read from its flat table.
+pub struct ComplexValues {
+  /// The flat table, with only the value columns selected.
+  table: Mutex<Table>,
+  names: Arc<[String]>,
+  /// The column of the flat table holding the complex value of the row the values belong to.
+  foreign_key: String,
+  kind: ComplexColumnKind,
+  /// Where the values of each complex value are, found by scanning the flat table once.
+  locations: OnceLock<RowLocations>,
+}
+
+impl ComplexValues {
+  /// Read the values of a complex column from its flat table, which only has the value columns selected.
+  pub(crate) fn new(mut table: Table, complex_column: &ComplexColumn) -> Result<ComplexValues, MdbError> {
+    table.select(complex_column.value_columns.iter().map(|column| &column.name))?;
+    let names = table.selected_columns().map(|column| column.name.clone()).collect();
+    Ok(ComplexValues {
+      table: Mutex::new(table),
+      names,
+      foreign_key: complex_column.foreign_key.clone(),
+      kind: complex_column.kind,
+      locations: OnceLock::new(),
+    })
+  }
+
+  pub fn kind(&self) -> ComplexColumnKind {
+    self.kind
+  }
+
+  /// The values of a row, one row of the value columns each, from the value of the complex column in that row. The
+  /// FileData of attachments is decompressed.
+  pub fn rows(&self, complex_value: i32) -> Result<Vec<Row>, MdbError> {
+    let Some(locations) = self.locations()?.get(&complex_value) else {
+      return Ok(Vec::new());
+    };
+    let file_data = self.names.iter().position(|name| name == "FileData").filter(|_| self.kind == ComplexColumnKind::Attachment);
+
+    let mut table = self.table.lock().map_err(|_| MdbError::ReadPage)?;
+    let mut rows = Vec::with_capacity(locations.len());
+    for &(page, row) in locations {
+      table.mdb.read_page(page)?;
+      table.current_page_number = page;
+      table.current_row = row;
+      table.read_row(row)?;
+
+      let mut values = table.selected_columns().map(Column::scan_value).collect::<Result<Vec<_>, _>>()?;
+      if let Some(file_data) = file_data && let Value::Binary(bytes) = &values[file_data] {
+        values[file_data] = Value::Binary(decode_attachment_data(bytes)?);
+      }
+      rows.push(Row::new(self.names.clone(), values));
+    }
+    Ok(rows)
+  }
+
+  /// Find where the values of every complex value are, reading only the foreign key of each row.
+  fn locations(&self) -> Result<&RowLocations, MdbError> {
+    if let Some(locations) = self.locations.get() {
+      return Ok(locations);
+    }
+
+    let mut table = self.table.lock().map_err(|_| MdbError::ReadPage)?.clone();
+    table.select([&self.foreign_key])?;
+    let foreign_key = table.find_column_index(&self.foreign_key).ok_or(MdbError::InvalidComplexColumn)?;
+    table.rewind();
+    let mut locations: RowLocations = HashMap::new();
+    while table.next_row()? {
+      if let Some(complex_value) = table.columns[foreign_key].value().as_i64() {
+        // The row has been read, so the cursor is past it.
+        locations.entry(complex_value as i32).or_default().push((table.current_page_number, table.current_row - 1));
+      }
+    }
+    Ok(self.locations.get_or_init(|| locations))
+  }
+
+  /// The values of a multivalued field in a row.
+  pub(crate) fn list(&self, complex_value: i32) -> Result<Vec<Value>, MdbError> {
+    Ok(self.rows(complex_value)?.into_iter().map(|row| row.into_values().remove(0)).collect())
+  }
+}
+
+/// A file attached to a row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attachment {
+  pub file_name: String,
+  /// The extension of the file, such as "pdf".
+  pub file_type: String,
+  pub file_url: Option<String>,
+  pub time_stamp: Option<NaiveDateTime>,
+  pub flags: Option<i32>,
+  /// The contents of the file, decompressed.
+  pub data: Vec<u8>,
+}
+
+/// The attachments of a column, read from its flat table.
+pub struct Attachments {
+  values: Arc<ComplexValues>,
+}
+
+impl Attachments {
+  /// Other kinds of complex column are an `UnhandledType` error.
+  pub(crate) fn new(values: Arc<ComplexValues>) -> Result<Attachments, MdbError> {
+    if values.kind != ComplexColumnKind::Attachment {
+      return Err(MdbError::UnhandledType);
+    }
+    Ok(Attachments { values })
+  }
+
+  /// The files attached to a row, from the value of the attachment column in that row.
+  pub fn get(&mut self, complex_value: i32) -> Result<Vec<Attachment>, MdbError> {
+    let mut attachments: Vec<Attachment> = Vec::new();
+    for row in self.values.rows(complex_value)? {
+      let data = match row.get_by_name("FileData") {
+        Some(Value::Binary(bytes)) => bytes.clone(),
+        _ => Vec::new(),
+      };
+      attachments.push(Attachment {
+        file_name: row["FileName"].as_str().unwrap_or_default().to_string(),
+        file_type: row.get_by_name("FileType").and_then(Value::as_str).unwrap_or_default().to_string(),
+        file_url: row.get_by_name("FileURL").and_then(Value::as_str).map(str::to_string),
+        time_stamp: row.get_by_name("FileTimeStamp").and_then(Value::as_datetime),
+        flags: row.get_by_name("FileFlags").and_then(Value::as_i64).map(|flags| flags as i32),
+        data,
+      });
+    }
+    Ok(attachments)
+  }
+}
+
+/// A version of an append only memo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoVersion {
+  pub value: String,
+  /// When the version was saved.
+  pub modified: Option<NaiveDateTime>,
+}
+
+/// The versions of an append only memo, read from the flat table of its version history column.
+pub struct MemoHistory {
+  values: Arc<ComplexValues>,
+  /// The hidden complex column of the table holding the id of the versions of each row.
+  column: String,
+  value_column: String,
+  modified_column: String,
+}
+
+impl MemoHistory {
+  /// The version history column has a memo column named after the memo it keeps the versions of, and the date of
+  /// each version.
+  pub(crate) fn new(values: Arc<ComplexValues>, complex_column: &ComplexColumn) -> Result<MemoHistory, MdbError> {
+    let find = |column_type: ColumnType| complex_column.value_columns.iter()
+      .find(|column| column.column_type == column_type)
+      .map(|column| column.name.clone())
+      .ok_or(MdbError::InvalidComplexColumn);
+    Ok(MemoHistory {
+      values,
+      column: complex_column.name.clone(),
+      value_column: find(ColumnType::Memo)?,
+      modified_column: find(ColumnType::Datetime)?,
+    })
+  }
+
+  /// The name of the version history column in the table.
+  pub fn column(&self) -> &str {
+    &self.column
+  }
+
+  /// The versions of the memo in a row, oldest first, from the value of the version history column in that row.
+  pub fn get(&self, complex_value: i32) -> Result<Vec<MemoVersion>, MdbError> {
+    let mut versions: Vec<MemoVersion> = self.values.rows(complex_value)?.into_iter()
+      .map(|row| MemoVersion {
+        value: row.get_by_name(&self.value_column).and_then(Value::as_str).unwrap_or_default().to_string(),
+        modified: row.get_by_name(&self.modified_column).and_then(Value::as_datetime),
+      })
+      .collect();
+    versions.sort_by_key(|version| version.modified);
+    Ok(versions)
+  }
+}
+
+/// Decode the FileData of an attachment: a type and the length of the content, then the content, compressed with
+/// zlib unless the file already is. The content starts with a header holding its length and the extension.
+pub fn decode_attachment_data(encoded: &[u8]) -> Result<Vec<u8>, MdbError> {
+  if encoded.len() < 8 {
+    return Err(MdbError::InvalidAttachment);
+  }
+  let length = get_u32(encoded, 4) as usize;
+
+  let mut content = match get_u32(encoded, 0) {
+    ATTACHMENT_RAW => encoded[8..].to_vec(),
+    ATTACHMENT_COMPRESSED => {
+      let mut content = Vec::new();
+      ZlibDecoder::new(&encoded[8..]).take(length as u64).read_to_end(&mut content)
+        .map_err(|_| MdbError::InvalidAttachment)?;
+      content
+    }
+    _ => return Err(MdbError::InvalidAttachment),
+  };
+
+  if content.len() < 4 || content.len() < length {
+    return Err(MdbError::InvalidAttachment);
+  }
+  let header_length = get_u32(&content, 0) as usize;
+  if header_length > length {
+    return Err(MdbError::InvalidAttachment);
+  }
+  content.truncate(length);
+  content.drain(..header_length);
+  Ok(content)
+}
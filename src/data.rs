@@ -1,186 +1,246 @@
-use crate::column::ColumnType;
-use crate::error::MdbError;
-use crate::mdbfile::{Mdb, PageTypes};
-use crate::table::{Table, TableStrategy};
-use crate::write::crack_row;
-
-const OFFSET_MASK: u16 = 0x1fff;
-
-impl Table {
-  /// Get the next row. If an error occurs, no more rows should be read.
-  pub fn fetch_row(&mut self) -> Result<(), MdbError> {
-    if self.current_page_number == 0 {
-      self.current_page_number = 1;
-      self.current_row = 0;
-      if (!self.is_temporary_table) && (self.strategy != TableStrategy::IndexScan) {
-        self.read_next_data_page()?;
-      }
-    }
-
-    loop {
-      if self.is_temporary_table {
-        // TODO: Implement.
-      } else if self.strategy == TableStrategy::IndexScan {} else {
-        let rows = self.mdb.get_u16(self.mdb.format.usage_row_count_offset);
-
-        if self.current_row >= rows {
-          self.current_row = 0;
-
-          self.read_next_data_page()?;
-        }
-      }
-
-      let res = self.read_row(self.current_row);
-      self.current_row += 1;
-
-      if res.is_err() {
-        //eprintln!("Error reading row");
-      } else {
-        for col in &mut self.columns {
-          if col.column_type == ColumnType::Memo {
-            let res = col.extract_column_text(&self.mdb);
-            if res.is_err() {
-              eprintln!("Problem getting memo text.");
-            }
-          }
-        }
-
-        return res;
-      }
-
-      if res.is_ok() {
-        break;
-      }
-    }
-
-    Ok(())
-  }
-
-  /// Attempts to read the next data page of a table.
-  /// An error may indicate an actual error or simply there is no next data page.
-  pub fn read_next_data_page(&mut self) -> Result<(), MdbError> {
-    loop {
-      let next_data_page = self.usage_map.get_next_free_page(self.current_page_number)?;
-
-      if next_data_page == self.current_page_number {
-        return Err(MdbError::NextDataPageCycle);
-      }
-
-      self.mdb.read_page(next_data_page)?;
-
-      self.current_page_number = next_data_page;
-
-      if self.mdb.page_buffer[0] == PageTypes::PageData as u8 && self.mdb.get_u32(4) == self.first_table_definition_page {
-        //println!("FOUND DATA PAGE AT: {}", next_data_page);
-        return Ok(());
-      }
-    }
-
-    // Keep as fallback.
-/*        loop {
-          let res = self.mdb.read_page(self.current_page_number as u32);
-          self.current_page_number += 1;
-          if res.is_err() {
-            return Err(MdbError::ReadPage);
-          }
-
-          if self.mdb.page_buffer[0] == PageTypes::PageData as u8 && self.mdb.get_u32(4) == self.first_table_definition_page {
-            break;
-          }
-        }
-        Ok(())*/
-  }
-
-  pub fn read_row(&mut self, row: u16) -> Result<(), MdbError> {
-    if self.column_count == 0 || self.columns.is_empty() {
-      return Err(MdbError::InvalidRowNumber);
-    }
-
-    let mut row = match mdb_find_row(&mut self.mdb, row) {
-      /* Emitting a warning here isn't especially helpful. The row metadata
-       * could be bogus for a number of reasons, so just skip to the next one
-       * without comment. */
-      Ok(row) => {
-        if row.length != 0 {
-          row
-        } else {
-          return Err(MdbError::InvalidRow);
-        }
-      }
-      Err(_) => {
-        return Err(MdbError::InvalidRow);
-      }
-    };
-
-    let mut deleted_flag = 0;
-    if row.start & 0x4000 != 0 {
-      deleted_flag += 1;
-    }
-
-    /*let mut lookup_flag = 0;
-    if row.start & 0x8000 != 0 {
-      lookup_flag += 1;
-    }*/
-    row.start &= OFFSET_MASK; /* remove flags */
-
-    if deleted_flag != 0 {
-      return Err(MdbError::DeletedRow);
-    }
-
-    crack_row(self, row.start, row.length)
-  }
-
-}
-
-pub fn mdb_find_page_row_packed(mdb: &mut Mdb, page_row: u32) -> Result<Row, MdbError> {
-  // The row is stored in the bottom byte.
-  let row: u8 = (page_row & 0x000000FF) as u8;
-  // The page is stored in the top 3 bytes.
-  let page = (page_row & 0xFFFFFF00) >> 8;
-
-  mdb_find_page_row(mdb, row, page)
-}
-
-pub fn mdb_find_page_row(mdb: &mut Mdb, row: u8, page: u32) -> Result<Row, MdbError> {
-  mdb.read_page(page)?;
-
-  mdb_find_row(mdb, row as u16)
-}
-
-pub struct Row {
-  pub(crate) start: u16,
-  pub(crate) length: u16,
-}
-
-/// Find a row assuming mdb has loaded the given page.
-pub fn mdb_find_row(mdb: &mut Mdb, row: u16) -> Result<Row, MdbError> {
-  if row > 1000 {
-    return Err(MdbError::RowTooLarge);
-  }
-
-  let offset = mdb.format.usage_row_count_offset + 2 + (row as usize) * 2;
-  let start: u16 = mdb.get_u16(offset);
-  let next_start: u16 = if row == 0 { mdb.format.page_size as u16 } else { mdb.get_u16(mdb.format.usage_row_count_offset + (row as usize) * 2) & OFFSET_MASK };
-  let length = next_start - (start & OFFSET_MASK);
-
-  if (start & OFFSET_MASK) >= mdb.format.page_size as u16 || (start & OFFSET_MASK) > next_start || next_start > mdb.format.page_size as u16 {
-    // eprintln!("Invalid bounds for usage map.");
-    return Err(MdbError::InvalidRowBounds);
-  }
-
-  Ok(Row {
-    start,
-    length,
-  })
-}
-
-#[derive(Clone)]
-pub struct ColBuffer {
-  pub value: Vec<u8>,
-  pub size: u16,
-  pub start: usize,
-  pub is_null: bool,
-  pub is_fixed: bool,
-  pub column_number: u16,
-  pub offset: usize,
+use crate::error::MdbError;
+use crate::mdbfile::{Mdb, PageTypes};
+use crate::table::{Table, TableStrategy};
+use crate::write::crack_row;
+
+const OFFSET_MASK: u16 = 0x1fff;
+/// How many times a row is followed to another page before it is taken to be corrupt.
+const MAX_ROW_MOVES: usize = 8;
+
+impl Table {
+  /// Get the next row. If an error occurs, no more rows should be read.
+  pub fn fetch_row(&mut self) -> Result<(), MdbError> {
+    match self.next_row()? {
+      true => Ok(()),
+      false => Err(MdbError::NoFreePages),
+    }
+  }
+
+  /// Read the next row into the column buffers. Returns false once every data page has been read.
+  pub(crate) fn next_row(&mut self) -> Result<bool, MdbError> {
+    if self.current_page_number == 0 {
+      self.current_page_number = 1;
+      self.current_row = 0;
+      if (!self.is_temporary_table) && (self.strategy != TableStrategy::IndexScan) && !self.advance_data_page()? {
+        return Ok(false);
+      }
+    }
+
+    loop {
+      if self.is_temporary_table {
+        // TODO: Implement.
+      } else if self.strategy == TableStrategy::IndexScan {
+        let Some(cursor) = self.index_cursor.as_mut() else {
+          return Ok(false);
+        };
+        let Some((page, row)) = cursor.next_entry()? else {
+          return Ok(false);
+        };
+        // Table scans skip the pointer and find the moved row on its new page, but the index still points at the old.
+        let (page, row) = follow_moved_row(&mut self.mdb, page, row)?;
+        self.current_page_number = page;
+        self.current_row = row;
+        // An entry pointing outside the table is corrupt.
+        if self.mdb.page_buffer[0] != PageTypes::PageData as u8 || self.mdb.get_u32(4) != self.first_table_definition_page {
+          if self.mdb.options.strict {
+            return Err(MdbError::InvalidIndex);
+          }
+          continue;
+        }
+      } else {
+        let rows = self.mdb.get_u16(self.mdb.format.usage_row_count_offset);
+
+        if self.current_row >= rows {
+          self.current_row = 0;
+
+          if !self.advance_data_page()? {
+            return Ok(false);
+          }
+          continue;
+        }
+      }
+
+      let res = self.read_row(self.current_row);
+      self.current_row += 1;
+
+      // Deleted and empty rows are skipped. Unreadable rows are too, unless the database was opened in strict mode.
+      match res {
+        Ok(()) => {}
+        Err(MdbError::DeletedRow | MdbError::InvalidRow) => continue,
+        Err(err) if self.mdb.options.strict => return Err(err),
+        Err(_) => continue,
+      }
+
+      // Memos are only read if the filter uses them, and otherwise when their value is.
+      if let Some(filter) = &self.filter && !filter.matches(&self.columns)? {
+        continue;
+      }
+
+      return Ok(true);
+    }
+  }
+
+  /// Move to the next data page, returning false when there are none left.
+  fn advance_data_page(&mut self) -> Result<bool, MdbError> {
+    match self.read_next_data_page() {
+      Ok(()) => Ok(true),
+      Err(MdbError::NoFreePages) => Ok(false),
+      Err(err) => Err(err),
+    }
+  }
+
+  /// Start the next scan from the first row.
+  pub fn rewind(&mut self) {
+    self.current_page_number = 0;
+    self.current_row = 0;
+    if let Some(cursor) = &mut self.index_cursor {
+      cursor.reset();
+    }
+  }
+
+  /// Attempts to read the next data page of a table.
+  /// An error may indicate an actual error or simply there is no next data page.
+  pub fn read_next_data_page(&mut self) -> Result<(), MdbError> {
+    loop {
+      let next_data_page = self.usage_map.get_next_free_page(self.current_page_number)?;
+
+      if next_data_page == self.current_page_number {
+        return Err(MdbError::NextDataPageCycle);
+      }
+
+      self.mdb.read_page(next_data_page)?;
+
+      self.current_page_number = next_data_page;
+
+      if self.mdb.page_buffer[0] == PageTypes::PageData as u8 && self.mdb.get_u32(4) == self.first_table_definition_page {
+        //println!("FOUND DATA PAGE AT: {}", next_data_page);
+        return Ok(());
+      }
+    }
+
+    // Keep as fallback.
+/*        loop {
+          let res = self.mdb.read_page(self.current_page_number as u32);
+          self.current_page_number += 1;
+          if res.is_err() {
+            return Err(MdbError::ReadPage);
+          }
+
+          if self.mdb.page_buffer[0] == PageTypes::PageData as u8 && self.mdb.get_u32(4) == self.first_table_definition_page {
+            break;
+          }
+        }
+        Ok(())*/
+  }
+
+  pub fn read_row(&mut self, row: u16) -> Result<(), MdbError> {
+    if self.column_count == 0 || self.columns.is_empty() {
+      return Err(MdbError::InvalidRowNumber);
+    }
+
+    let mut row = match mdb_find_row(&mut self.mdb, row) {
+      /* Emitting a warning here isn't especially helpful. The row metadata
+       * could be bogus for a number of reasons, so just skip to the next one
+       * without comment. */
+      Ok(row) => {
+        if row.length != 0 {
+          row
+        } else {
+          return Err(MdbError::InvalidRow);
+        }
+      }
+      Err(err) => {
+        return Err(err);
+      }
+    };
+
+    let mut deleted_flag = 0;
+    if row.start & 0x4000 != 0 {
+      deleted_flag += 1;
+    }
+
+    /*let mut lookup_flag = 0;
+    if row.start & 0x8000 != 0 {
+      lookup_flag += 1;
+    }*/
+    row.start &= OFFSET_MASK; /* remove flags */
+
+    if deleted_flag != 0 {
+      return Err(MdbError::DeletedRow);
+    }
+
+    crack_row(self, row.start, row.length)
+  }
+
+}
+
+pub fn mdb_find_page_row_packed(mdb: &mut Mdb, page_row: u32) -> Result<RowBounds, MdbError> {
+  // The row is stored in the bottom byte.
+  let row: u8 = (page_row & 0x000000FF) as u8;
+  // The page is stored in the top 3 bytes.
+  let page = (page_row & 0xFFFFFF00) >> 8;
+
+  mdb_find_page_row(mdb, row, page)
+}
+
+pub fn mdb_find_page_row(mdb: &mut Mdb, row: u8, page: u32) -> Result<RowBounds, MdbError> {
+  mdb.read_page(page)?;
+
+  mdb_find_row(mdb, row as u16)
+}
+
+/// Where a row is stored on its page.
+pub struct RowBounds {
+  pub(crate) start: u16,
+  pub(crate) length: u16,
+}
+
+/// Follow the pointers left when an update moves a row to another page, returning where the row is now.
+fn follow_moved_row(mdb: &mut Mdb, page: u32, row: u16) -> Result<(u32, u16), MdbError> {
+  let (mut page, mut row) = (page, row);
+  for _ in 0..MAX_ROW_MOVES {
+    mdb.read_page(page)?;
+    let bounds = mdb_find_row(mdb, row)?;
+    // The pointer is the row number then the page number in three bytes.
+    if bounds.start & 0x4000 == 0 || bounds.length != 4 {
+      return Ok((page, row));
+    }
+    let start = (bounds.start & OFFSET_MASK) as usize;
+    row = mdb.get_u8(start) as u16;
+    page = mdb.get_u32(start) >> 8;
+  }
+  Err(MdbError::InvalidRow)
+}
+
+/// Find a row assuming mdb has loaded the given page.
+pub fn mdb_find_row(mdb: &mut Mdb, row: u16) -> Result<RowBounds, MdbError> {
+  if row > 1000 {
+    return Err(MdbError::RowTooLarge);
+  }
+
+  let offset = mdb.format.usage_row_count_offset + 2 + (row as usize) * 2;
+  let start: u16 = mdb.get_u16(offset);
+  let next_start: u16 = if row == 0 { mdb.format.page_size as u16 } else { mdb.get_u16(mdb.format.usage_row_count_offset + (row as usize) * 2) & OFFSET_MASK };
+  let length = next_start - (start & OFFSET_MASK);
+
+  if (start & OFFSET_MASK) >= mdb.format.page_size as u16 || (start & OFFSET_MASK) > next_start || next_start > mdb.format.page_size as u16 {
+    // eprintln!("Invalid bounds for usage map.");
+    return Err(MdbError::InvalidRowBounds);
+  }
+
+  Ok(RowBounds {
+    start,
+    length,
+  })
+}
+
+#[derive(Clone)]
+pub struct ColBuffer {
+  pub value: Vec<u8>,
+  pub size: u16,
+  pub start: usize,
+  pub is_null: bool,
+  pub is_fixed: bool,
+  pub column_number: u16,
+  pub offset: usize,
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::catalog::{read_system_objects, ObjectType, SystemObject};
+use crate::column::ColumnType;
+use crate::complex::{Attachments, ComplexColumn, ComplexColumnKind, ComplexValues, MemoHistory};
+use crate::error::MdbError;
+use crate::mdbfile::{Mdb, MdbFormatVersion};
+use crate::property::{read_database_properties, read_properties, ObjectProperties, PropertyBlock};
+use crate::query::{read_queries, Query};
+use crate::relationship::{read_relationships, Relationship};
+use crate::table::Table;
+
+/// Summary information about a database file.
+#[derive(Clone, Debug)]
+pub struct DatabaseInfo {
+  pub version: MdbFormatVersion,
+  pub page_size: usize,
+  pub page_count: u64,
+  pub code_page: u16,
+  /// The properties of the database, such as AccessVersion.
+  pub properties: PropertyBlock,
+}
+
+/// An open database. The catalog, table definitions, queries and relationships are read the first time they are
+/// needed and kept for later lookups.
+pub struct Database {
+  mdb: Mdb,
+  system_objects: Option<Vec<SystemObject>>,
+  /// Table definitions by lowercased name, with their columns read.
+  tables: HashMap<String, Table>,
+  queries: Option<Vec<Query>>,
+  relationships: Option<Vec<Relationship>>,
+}
+
+impl Database {
+  /// Open a database with the default options. See `OpenOptions::open_database` to configure how it is read.
+  pub fn open(path: impl AsRef<Path>) -> Result<Database, MdbError> {
+    Ok(Database::from_mdb(Mdb::open(path)?))
+  }
+
+  pub fn from_mdb(mdb: Mdb) -> Database {
+    Database {
+      mdb,
+      system_objects: None,
+      tables: HashMap::new(),
+      queries: None,
+      relationships: None,
+    }
+  }
+
+  pub fn mdb(&self) -> &Mdb {
+    &self.mdb
+  }
+
+  /// Every row of MSysObjects.
+  pub fn system_objects(&mut self) -> Result<&[SystemObject], MdbError> {
+    if self.system_objects.is_none() {
+      self.system_objects = Some(read_system_objects(&mut self.mdb)?);
+    }
+    Ok(self.system_objects.as_deref().unwrap())
+  }
+
+  /// The user tables. System tables are only included when the database was opened with
+  /// `OpenOptions::show_system_tables`.
+  pub fn tables(&mut self) -> Result<Vec<&SystemObject>, MdbError> {
+    let show_system_tables = self.mdb.options.show_system_tables;
+    Ok(self.system_objects()?.iter()
+      .filter(|object| object.object_type == ObjectType::Table && (show_system_tables || !object.is_system()))
+      .collect())
+  }
+
+  /// Find a table, system tables included. Names are matched case-insensitively as Access does, preferring an exact
+  /// match.
+  pub fn find_table(&mut self, name: &str) -> Result<Option<&SystemObject>, MdbError> {
+    let index = self.find_table_index(name)?;
+    Ok(index.map(|index| &self.system_objects.as_ref().unwrap()[index]))
+  }
+
+  fn find_table_index(&mut self, name: &str) -> Result<Option<usize>, MdbError> {
+    let lowercase_name = name.to_lowercase();
+    let objects = self.system_objects()?;
+    let is_table = |object: &SystemObject| object.object_type == ObjectType::Table;
+    Ok(objects.iter().position(|object| is_table(object) && object.name == name)
+      .or_else(|| objects.iter().position(|object| is_table(object) && object.name.to_lowercase() == lowercase_name)))
+  }
+
+  /// The properties of a table and its columns.
+  pub fn table_properties(&mut self, name: &str) -> Result<ObjectProperties, MdbError> {
+    let index = self.find_table_index(name)?.ok_or(MdbError::TableNotFound)?;
+    read_properties(&self.mdb, &self.system_objects.as_ref().unwrap()[index])
+  }
+
+  /// Load a table with its columns read, ready to scan. The definition is cached, so later calls only copy it.
+  pub fn table(&mut self, name: &str) -> Result<Table, MdbError> {
+    let key = name.to_lowercase();
+    if !self.tables.contains_key(&key) {
+      let entry = match self.find_table(name)? {
+        Some(object) => object.to_catalog_entry().unwrap(),
+        None => return Err(MdbError::TableNotFound),
+      };
+      let mut table = Table::from_catalog_entry(entry, &self.mdb)?;
+      table.read_columns()?;
+      self.tables.insert(key.clone(), table);
+    }
+
+    let mut table = self.tables[&key].clone();
+    table.rewind();
+    Ok(table)
+  }
+
+  /// The saved queries.
+  pub fn queries(&mut self) -> Result<&[Query], MdbError> {
+    if self.queries.is_none() {
+      self.queries = Some(read_queries(&mut self.mdb)?);
+    }
+    Ok(self.queries.as_deref().unwrap())
+  }
+
+  /// The relationships between tables.
+  pub fn relationships(&mut self) -> Result<&[Relationship], MdbError> {
+    if self.relationships.is_none() {
+      self.relationships = Some(read_relationships(&mut self.mdb)?);
+    }
+    Ok(self.relationships.as_deref().unwrap())
+  }
+
+  /// The attachment, multivalued and version history columns.
+  pub fn complex_columns(&mut self) -> Result<&[ComplexColumn], MdbError> {
+    self.mdb.complex.columns(&self.mdb)
+  }
+
+  /// The values of a complex column, looked up by the value of the column in a row. Other columns are an
+  /// `UnhandledType` error.
+  pub fn complex_values(&mut self, table: &str, column: &str) -> Result<Arc<ComplexValues>, MdbError> {
+    let table = self.table(table)?;
+    let column = table.find_column_index(column).ok_or(MdbError::ColumnNotFound)?;
+    let complex_id = table.columns[column].complex_id.ok_or(MdbError::UnhandledType)?;
+    self.mdb.complex.values(&self.mdb, complex_id)
+  }
+
+  /// The files of an attachment column. Other columns are an `UnhandledType` error.
+  pub fn attachments(&mut self, table: &str, column: &str) -> Result<Attachments, MdbError> {
+    Attachments::new(self.complex_values(table, column)?)
+  }
+
+  /// The version history of an append only memo column. Memos without one are an `UnhandledType` error.
+  pub fn memo_history(&mut self, table: &str, column: &str) -> Result<MemoHistory, MdbError> {
+    let table = self.table(table)?;
+    let column = table.find_column_index(column).ok_or(MdbError::ColumnNotFound)?;
+    let memo = &table.columns[column];
+    if memo.column_type != ColumnType::Memo {
+      return Err(MdbError::UnhandledType);
+    }
+
+    let complex_column = self.complex_columns()?.iter()
+      .find(|complex_column| {
+        complex_column.kind == ComplexColumnKind::VersionHistory && complex_column.table == table.name
+          && complex_column.value_columns.iter().any(|value_column| {
+            value_column.column_type == ColumnType::Memo && value_column.name.eq_ignore_ascii_case(&memo.name)
+          })
+      })
+      .cloned()
+      .ok_or(MdbError::UnhandledType)?;
+    let values = self.complex_values(&table.name, &complex_column.name)?;
+    MemoHistory::new(values, &complex_column)
+  }
+
+  pub fn info(&mut self) -> Result<DatabaseInfo, MdbError> {
+    let page_size = self.mdb.format.page_size;
+    let file_size = self.mdb.mdb_file.len();
+    self.system_objects()?;
+    let properties = read_database_properties(&self.mdb, self.system_objects.as_deref().unwrap())?;
+
+    Ok(DatabaseInfo {
+      version: self.mdb.mdb_file.jet_version,
+      page_size,
+      page_count: file_size / page_size as u64,
+      code_page: self.mdb.codepage,
+      properties,
+    })
+  }
+}
@@ -0,0 +1,295 @@
+use std::fmt::{Display, Formatter};
+
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::MdbError;
+use crate::row::Row;
+use crate::table::Table;
+use crate::value::{format_guid, Value};
+
+/// An error deserializing a row, with the table, row and column it happened in where they are known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeserializeError {
+  pub table: Option<String>,
+  /// The index of the row in the scan, counting from zero.
+  pub row: Option<usize>,
+  pub column: Option<String>,
+  pub message: String,
+}
+
+impl DeserializeError {
+  fn with_column(mut self, column: &str) -> Self {
+    if self.column.is_none() {
+      self.column = Some(column.to_string());
+    }
+    self
+  }
+}
+
+impl Display for DeserializeError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if let Some(table) = &self.table {
+      write!(f, "table \"{}\", ", table)?;
+    }
+    if let Some(row) = self.row {
+      write!(f, "row {}, ", row)?;
+    }
+    if let Some(column) = &self.column {
+      write!(f, "column \"{}\", ", column)?;
+    }
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl de::Error for DeserializeError {
+  fn custom<T: Display>(message: T) -> Self {
+    DeserializeError { table: None, row: None, column: None, message: message.to_string() }
+  }
+}
+
+impl Row {
+  /// Deserialize the row into `T`. Struct fields are matched to columns by name, exactly, then case-insensitively,
+  /// then ignoring spaces and punctuation, so `asset_no` reads the "Asset No" column. Tuples read the columns in
+  /// order. Nullable columns need `Option` fields.
+  pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, DeserializeError> {
+    T::deserialize(RowDeserializer { row: self })
+  }
+}
+
+impl Table {
+  /// Deserialize every row of the table. See `Row::deserialize` for how fields are matched to columns.
+  pub fn deserialize<T: DeserializeOwned>(&mut self) -> Result<Vec<T>, MdbError> {
+    let name = self.name.clone();
+    self.rows().enumerate().map(|(index, row)| {
+      row?.deserialize().map_err(|mut err: DeserializeError| {
+        err.table = Some(name.clone());
+        err.row = Some(index);
+        MdbError::Deserialize(err)
+      })
+    }).collect()
+  }
+}
+
+/// Find the column a struct field reads.
+fn find_column(names: &[String], field: &str) -> Option<usize> {
+  let normalize = |name: &str| name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect::<String>();
+  let normalized_field = normalize(field);
+  names.iter().position(|name| name == field)
+    .or_else(|| names.iter().position(|name| name.eq_ignore_ascii_case(field)))
+    .or_else(|| names.iter().position(|name| normalize(name) == normalized_field))
+}
+
+struct RowDeserializer<'a> {
+  row: &'a Row,
+}
+
+impl<'de> de::Deserializer<'de> for RowDeserializer<'_> {
+  type Error = DeserializeError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_map(visitor)
+  }
+
+  fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    let fields = self.row.names().iter().enumerate().map(|(index, name)| (name.as_str(), index)).collect();
+    visitor.visit_map(RowMap { row: self.row, fields, position: 0 })
+  }
+
+  fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+    // Only fields with a column are visited, so missing columns are reported by the visitor, or left as None.
+    let fields = fields.iter()
+      .filter_map(|field| find_column(self.row.names(), field).map(|index| (*field, index)))
+      .collect();
+    visitor.visit_map(RowMap { row: self.row, fields, position: 0 })
+  }
+
+  fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_seq(RowSeq { row: self.row, position: 0 })
+  }
+
+  fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_seq(visitor)
+  }
+
+  fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_seq(visitor)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct enum
+    identifier ignored_any
+  }
+}
+
+/// The columns of a row as a map, keyed by field name.
+struct RowMap<'a> {
+  row: &'a Row,
+  fields: Vec<(&'a str, usize)>,
+  position: usize,
+}
+
+impl<'de> MapAccess<'de> for RowMap<'_> {
+  type Error = DeserializeError;
+
+  fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+    match self.fields.get(self.position) {
+      Some((field, _)) => {
+        let key: StrDeserializer<DeserializeError> = field.into_deserializer();
+        seed.deserialize(key).map(Some)
+      }
+      None => Ok(None),
+    }
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Self::Error> {
+    let (_, index) = self.fields[self.position];
+    self.position += 1;
+    seed.deserialize(ValueDeserializer { value: &self.row[index] })
+      .map_err(|err| err.with_column(&self.row.names()[index]))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.fields.len() - self.position)
+  }
+}
+
+/// The columns of a row in order.
+struct RowSeq<'a> {
+  row: &'a Row,
+  position: usize,
+}
+
+impl<'de> SeqAccess<'de> for RowSeq<'_> {
+  type Error = DeserializeError;
+
+  fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+    let index = self.position;
+    let Some(value) = self.row.get(index) else {
+      return Ok(None);
+    };
+    self.position += 1;
+    seed.deserialize(ValueDeserializer { value })
+      .map(Some)
+      .map_err(|err| err.with_column(&self.row.names()[index]))
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.row.len() - self.position)
+  }
+}
+
+/// Deserializes a single value. Currency and decimals are visited as strings so they can be read exactly by
+/// `rust_decimal`, dates as ISO 8601 strings for `chrono`, and GUIDs as hyphenated strings for `uuid`.
+struct ValueDeserializer<'a> {
+  value: &'a Value,
+}
+
+impl ValueDeserializer<'_> {
+  fn to_text(&self) -> String {
+    match self.value {
+      Value::DateTime(value) | Value::ExtendedDateTime(value) => value.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+      Value::Guid(value) => format_guid(value).trim_matches(['{', '}']).to_string(),
+      value => value.to_string(),
+    }
+  }
+}
+
+impl<'de, 'a> IntoDeserializer<'de, DeserializeError> for ValueDeserializer<'a> {
+  type Deserializer = ValueDeserializer<'a>;
+
+  fn into_deserializer(self) -> Self::Deserializer {
+    self
+  }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+  type Error = DeserializeError;
+
+  fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      Value::Null => visitor.visit_unit(),
+      Value::Bool(value) => visitor.visit_bool(*value),
+      Value::U8(value) => visitor.visit_u8(*value),
+      Value::I16(value) => visitor.visit_i16(*value),
+      Value::I32(value) => visitor.visit_i32(*value),
+      Value::I64(value) => visitor.visit_i64(*value),
+      Value::F32(value) => visitor.visit_f32(*value),
+      Value::F64(value) => visitor.visit_f64(*value),
+      Value::Text(value) => visitor.visit_str(value),
+      Value::Binary(value) => visitor.visit_seq(SeqDeserializer::new(value.iter().copied())),
+      Value::List(values) => visitor.visit_seq(SeqDeserializer::new(values.iter().map(|value| ValueDeserializer { value }))),
+      Value::Currency(_) | Value::Decimal { .. } | Value::DateTime(_) | Value::ExtendedDateTime(_) | Value::Guid(_) => {
+        visitor.visit_string(self.to_text())
+      }
+    }
+  }
+
+  fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      Value::Null => visitor.visit_none(),
+      _ => visitor.visit_some(self),
+    }
+  }
+
+  fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_f64(visitor)
+  }
+
+  fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value.as_f64() {
+      Some(value) => visitor.visit_f64(value),
+      None => self.deserialize_any(visitor),
+    }
+  }
+
+  fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      Value::Null | Value::Binary(_) => self.deserialize_any(visitor),
+      Value::Text(value) => visitor.visit_str(value),
+      _ => visitor.visit_string(self.to_text()),
+    }
+  }
+
+  fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_str(visitor)
+  }
+
+  fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      Value::Binary(value) => visitor.visit_bytes(value),
+      _ => self.deserialize_any(visitor),
+    }
+  }
+
+  fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+    self.deserialize_bytes(visitor)
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+    match self.value {
+      // Text columns deserialize into unit variants of the same name.
+      Value::Text(value) => {
+        let variant: StrDeserializer<DeserializeError> = value.as_str().into_deserializer();
+        visitor.visit_enum(variant)
+      }
+      _ => self.deserialize_any(visitor),
+    }
+  }
+
+  forward_to_deserialize_any! {
+    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char unit unit_struct seq tuple tuple_struct map struct identifier
+    ignored_any
+  }
+}
@@ -7,6 +7,9 @@ pub enum MdbError {
   // Read table errors
   ReadNonTableCatalogueEntry,
   InvalidTableDefinition,
+  TableNotFound,
+  ColumnNotFound,
+  InvalidIndex,
 
   // Usage Map Errors
   UnknownMapType,
@@ -24,6 +27,7 @@ pub enum MdbError {
 
   ReadPage,
   JetVersion,
+  InvalidPassword,
 
 
   // Row Related Errors
@@ -33,5 +37,48 @@ pub enum MdbError {
 
   // Cracking Row Errors
   InvalidRowBuffer,
-  InvalidDataLocation
-}
\ No newline at end of file
+  InvalidDataLocation,
+
+  // Long value errors
+  LongValueTooLarge,
+  InvalidLongValue,
+
+  // OLE object errors
+  InvalidOleObject,
+
+  // Complex column errors
+  InvalidComplexColumn,
+  InvalidAttachment,
+
+  // SQL errors
+  InvalidSql,
+
+  // Property errors
+  InvalidProperties,
+
+  // Deserializing rows into structs
+  #[cfg(feature = "serde")]
+  Deserialize(crate::de::DeserializeError),
+}
+
+impl std::fmt::Display for MdbError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      #[cfg(feature = "serde")]
+      MdbError::Deserialize(err) => write!(f, "{}", err),
+      err => write!(f, "{:?}", err),
+    }
+  }
+}
+
+impl std::error::Error for MdbError {}
+
+/// Errors from `LongValueReader` are wrapped in an `io::Error`; other IO errors are read errors.
+impl From<std::io::Error> for MdbError {
+  fn from(err: std::io::Error) -> Self {
+    match err.into_inner().map(|inner| inner.downcast::<MdbError>()) {
+      Some(Ok(err)) => *err,
+      _ => MdbError::ReadPage,
+    }
+  }
+}
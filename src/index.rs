@@ -0,0 +1,263 @@
+use crate::column::ColumnType;
+use crate::error::MdbError;
+use crate::mdbfile::{Mdb, MdbFormatVersion, PageTypes};
+
+// Bits of the flags of a real index.
+pub const UNIQUE_INDEX_FLAG: u8 = 0x01;
+pub const IGNORE_NULLS_INDEX_FLAG: u8 = 0x02;
+pub const REQUIRED_INDEX_FLAG: u8 = 0x08;
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum IndexType {
+  Normal,
+  PrimaryKey,
+  /// Created by Access for the many side of an enforced relationship.
+  ForeignKey,
+}
+
+impl From<u8> for IndexType {
+  fn from(raw: u8) -> Self {
+    match raw {
+      1 => IndexType::PrimaryKey,
+      2 => IndexType::ForeignKey,
+      _ => IndexType::Normal,
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct IndexColumn {
+  pub name: String,
+  pub ascending: bool,
+}
+
+/// A logical index of a table, with the columns and flags of the real index it uses.
+#[derive(Clone, Debug)]
+pub struct Index {
+  pub name: String,
+  pub index_type: IndexType,
+  /// The real index holding the keys. Several logical indexes can share one.
+  pub real_index_number: u32,
+  pub columns: Vec<IndexColumn>,
+  pub flags: u8,
+  pub first_page: u32,
+}
+
+impl Index {
+  pub fn is_primary_key(&self) -> bool {
+    self.index_type == IndexType::PrimaryKey
+  }
+
+  pub fn is_foreign_key(&self) -> bool {
+    self.index_type == IndexType::ForeignKey
+  }
+
+  pub fn is_unique(&self) -> bool {
+    self.flags & UNIQUE_INDEX_FLAG != 0
+  }
+
+  pub fn ignores_nulls(&self) -> bool {
+    self.flags & IGNORE_NULLS_INDEX_FLAG != 0
+  }
+
+  pub fn is_required(&self) -> bool {
+    self.flags & REQUIRED_INDEX_FLAG != 0
+  }
+}
+
+/// The first byte of a key sorted ascending that isn't null.
+const ASCENDING_KEY_FLAG: u8 = 0x7F;
+/// How deep the index tree is followed before it is taken to be corrupt.
+const MAX_INDEX_DEPTH: usize = 32;
+
+/// Which end of a range a key bounds, which decides how a fraction is rounded for an integer column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum KeyBound {
+  Lower,
+  Upper,
+}
+
+/// Encode a value as the key of an ascending index column. Integers are stored big endian with the sign bit flipped,
+/// and floating point numbers and dates big endian with the sign bit flipped when positive and every bit when
+/// negative, so the keys sort as bytes. Other types aren't encoded.
+pub(crate) fn encode_key(column_type: ColumnType, value: f64, bound: KeyBound) -> Option<Vec<u8>> {
+  let round = |value: f64| if bound == KeyBound::Lower { value.ceil() } else { value.floor() };
+  let mut bytes = match column_type {
+    ColumnType::Int => (round(value).clamp(i16::MIN as f64, i16::MAX as f64) as i16).to_be_bytes().to_vec(),
+    ColumnType::LongInt => (round(value).clamp(i32::MIN as f64, i32::MAX as f64) as i32).to_be_bytes().to_vec(),
+    ColumnType::Float => (value as f32).to_be_bytes().to_vec(),
+    ColumnType::Double | ColumnType::Datetime => value.to_be_bytes().to_vec(),
+    _ => return None,
+  };
+
+  let is_float = matches!(column_type, ColumnType::Float | ColumnType::Double | ColumnType::Datetime);
+  if is_float && bytes[0] & 0x80 != 0 {
+    for byte in &mut bytes {
+      *byte = !*byte;
+    }
+  } else {
+    bytes[0] ^= 0x80;
+  }
+
+  let mut key = vec![ASCENDING_KEY_FLAG];
+  key.extend(bytes);
+  Some(key)
+}
+
+/// An entry of an index page: the key and the row it points at, and for node pages the child page.
+struct IndexEntry {
+  key: Vec<u8>,
+  data_page: u32,
+  row: u16,
+  child_page: u32,
+}
+
+/// Walks the leaf pages of an index in key order, returning the data page and row of each entry whose key is
+/// between two bounds. Keys longer than a bound, from indexes on several columns, are compared on their start.
+#[derive(Clone)]
+pub(crate) struct IndexCursor {
+  /// The position of the index in `Table::indexes`.
+  pub(crate) index: usize,
+  mdb: Mdb,
+  first_page: u32,
+  lower: Option<Vec<u8>>,
+  upper: Option<Vec<u8>>,
+  /// The leaf page being read, or zero before the scan starts.
+  page: u32,
+  entries: Vec<(Vec<u8>, u32, u16)>,
+  position: usize,
+  done: bool,
+}
+
+impl IndexCursor {
+  pub(crate) fn new(position: usize, index: &Index, mdb: &Mdb, lower: Option<Vec<u8>>, upper: Option<Vec<u8>>) -> Self {
+    IndexCursor {
+      index: position,
+      mdb: mdb.clone(),
+      first_page: index.first_page,
+      lower,
+      upper,
+      page: 0,
+      entries: Vec::new(),
+      position: 0,
+      done: false,
+    }
+  }
+
+  /// Start again from the first key.
+  pub(crate) fn reset(&mut self) {
+    self.page = 0;
+    self.entries.clear();
+    self.position = 0;
+    self.done = false;
+  }
+
+  /// The data page and row of the next entry in range, or None once the upper bound is passed.
+  pub(crate) fn next_entry(&mut self) -> Result<Option<(u32, u16)>, MdbError> {
+    loop {
+      if self.done {
+        return Ok(None);
+      }
+      if self.page == 0 {
+        self.page = self.first_leaf()?;
+        self.load_leaf()?;
+        continue;
+      }
+      if self.position >= self.entries.len() {
+        self.mdb.read_page(self.page)?;
+        let next_page = self.mdb.get_u32(0x0c);
+        if next_page == 0 || next_page == self.page {
+          self.done = true;
+          return Ok(None);
+        }
+        self.page = next_page;
+        self.load_leaf()?;
+        continue;
+      }
+
+      let (key, data_page, row) = &self.entries[self.position];
+      self.position += 1;
+      if let Some(lower) = &self.lower && key_start(key, lower.len()) < lower.as_slice() {
+        continue;
+      }
+      if let Some(upper) = &self.upper && key_start(key, upper.len()) > upper.as_slice() {
+        self.done = true;
+        return Ok(None);
+      }
+      return Ok(Some((*data_page, *row)));
+    }
+  }
+
+  /// Follow the first entry of each node page down to the leftmost leaf.
+  fn first_leaf(&mut self) -> Result<u32, MdbError> {
+    let mut page = self.first_page;
+    for _ in 0..MAX_INDEX_DEPTH {
+      self.mdb.read_page(page)?;
+      match self.mdb.get_u8(0) {
+        page_type if page_type == PageTypes::PageLeaf as u8 => return Ok(page),
+        page_type if page_type == PageTypes::PageIndex as u8 => {
+          let entries = read_index_entries(&self.mdb, false)?;
+          page = entries.first().ok_or(MdbError::InvalidIndex)?.child_page;
+        }
+        _ => return Err(MdbError::InvalidIndex),
+      }
+    }
+    Err(MdbError::InvalidIndex)
+  }
+
+  fn load_leaf(&mut self) -> Result<(), MdbError> {
+    self.mdb.read_page(self.page)?;
+    if self.mdb.get_u8(0) != PageTypes::PageLeaf as u8 {
+      return Err(MdbError::InvalidIndex);
+    }
+    self.entries = read_index_entries(&self.mdb, true)?.into_iter()
+      .map(|entry| (entry.key, entry.data_page, entry.row))
+      .collect();
+    self.position = 0;
+    Ok(())
+  }
+}
+
+fn key_start(key: &[u8], len: usize) -> &[u8] {
+  &key[..key.len().min(len)]
+}
+
+/// Read the entries of the index page in the buffer. A bitmask marks where each entry ends, and entries after the
+/// first leave out the prefix they share with it.
+fn read_index_entries(mdb: &Mdb, leaf: bool) -> Result<Vec<IndexEntry>, MdbError> {
+  let (mask_start, entries_start) = if mdb.mdb_file.jet_version == MdbFormatVersion::JET3 { (0x16, 0xf8) } else { (0x1b, 0x1e0) };
+  let prefix_len = mdb.get_u16(0x14) as usize;
+  let pointer_len = if leaf { 4 } else { 8 };
+
+  let mut entries: Vec<IndexEntry> = Vec::new();
+  let mut prefix: Vec<u8> = Vec::new();
+  let mut start = entries_start;
+  for bit in 1..(entries_start - mask_start) * 8 {
+    if mdb.page_buffer[mask_start + bit / 8] & (1 << (bit % 8)) == 0 {
+      continue;
+    }
+    let end = entries_start + bit;
+    if end > mdb.format.page_size {
+      return Err(MdbError::InvalidIndex);
+    }
+
+    let mut entry = if entries.is_empty() { Vec::new() } else { prefix.clone() };
+    entry.extend_from_slice(&mdb.page_buffer[start..end]);
+    start = end;
+    if entries.is_empty() {
+      prefix = entry[..prefix_len.min(entry.len())].to_vec();
+    }
+    if entry.len() < pointer_len {
+      return Err(MdbError::InvalidIndex);
+    }
+
+    let pointer = &entry[entry.len() - pointer_len..];
+    entries.push(IndexEntry {
+      key: entry[..entry.len() - pointer_len].to_vec(),
+      data_page: u32::from_be_bytes([0, pointer[0], pointer[1], pointer[2]]),
+      row: pointer[3] as u16,
+      child_page: if leaf { 0 } else { u32::from_be_bytes(pointer[4..8].try_into().unwrap()) },
+    });
+  }
+  Ok(entries)
+}
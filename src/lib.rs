@@ -1,23 +1,38 @@
-/*#![warn(
-clippy::all,
-clippy::restriction,
-clippy::pedantic,
-clippy::nursery,
-clippy::cargo,
-)]*/
-
-pub mod mdbfile;
-pub mod table;
-pub mod catalog;
-pub mod data;
-pub mod column;
-pub mod conversion;
-mod write;
-pub mod utils;
-mod map;
-mod rc4;
-pub mod money;
-pub mod time;
-pub mod numeric;
-pub mod backend;
-pub mod error;
\ No newline at end of file
+/*#![warn(
+clippy::all,
+clippy::restriction,
+clippy::pedantic,
+clippy::nursery,
+clippy::cargo,
+)]*/
+
+pub mod mdbfile;
+pub mod table;
+pub mod catalog;
+pub mod data;
+pub mod column;
+pub mod complex;
+pub mod conversion;
+mod write;
+pub mod utils;
+mod map;
+mod rc4;
+pub mod money;
+pub mod time;
+pub mod numeric;
+pub mod ole;
+pub mod backend;
+pub mod error;
+pub mod database;
+#[cfg(feature = "serde")]
+pub mod de;
+pub mod index;
+pub mod long_value;
+pub mod options;
+pub mod predicate;
+pub mod property;
+pub mod query;
+pub mod relationship;
+pub mod row;
+pub mod value;
+pub mod sql;
\ No newline at end of file
@@ -0,0 +1,169 @@
+use std::io::{self, Read};
+
+use crate::data::mdb_find_page_row_packed;
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+use crate::utils::get_u32;
+
+/// The size of the header a memo or OLE column stores in the row.
+pub const LONG_VALUE_HEADER_SIZE: usize = 12;
+
+/// Where the bytes of a memo or OLE value are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LongValueStorage {
+  /// In the row, after the header.
+  Inline,
+  /// In a single row of an LVAL page.
+  SinglePage,
+  /// In a chain of LVAL rows, each starting with the page and row of the next.
+  MultiPage,
+  Unknown,
+}
+
+/// The header of a memo or OLE value as stored in the row: the length and storage flags, then the page and row of
+/// the first LVAL row holding the data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LongValueHeader {
+  pub raw: [u8; LONG_VALUE_HEADER_SIZE],
+}
+
+impl LongValueHeader {
+  /// Read the header from the start of the column bytes, or None if they are too short.
+  pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+    Some(LongValueHeader { raw: bytes.get(..LONG_VALUE_HEADER_SIZE)?.try_into().unwrap() })
+  }
+
+  /// The length of the value in bytes.
+  pub fn length(&self) -> usize {
+    (get_u32(&self.raw, 0) & 0x00ffffff) as usize
+  }
+
+  pub fn storage(&self) -> LongValueStorage {
+    let flags = self.raw[3];
+    if flags & 0x80 != 0 {
+      LongValueStorage::Inline
+    } else if flags & 0x40 != 0 {
+      LongValueStorage::SinglePage
+    } else if flags == 0 {
+      LongValueStorage::MultiPage
+    } else {
+      LongValueStorage::Unknown
+    }
+  }
+
+  /// The first LVAL row, with the row number in the low byte and the page number in the upper three.
+  pub fn page_row(&self) -> u32 {
+    get_u32(&self.raw, 4)
+  }
+}
+
+/// Streams the bytes of a memo or OLE value, reading one LVAL row at a time, so values far larger than memory can be
+/// copied out. `OpenOptions::max_long_value_size` doesn't apply, as the value is never held whole. A chain of pages
+/// shorter than the header says ends the value early, or is an error in strict mode.
+///
+/// ```no_run
+/// use mdbtools::database::Database;
+///
+/// let mut database = Database::open("database.accdb").unwrap();
+/// let mut table = database.table("Documents").unwrap();
+/// let column = table.find_column_index("File").unwrap();
+/// table.fetch_row().unwrap();
+/// let mut reader = table.columns[column].long_value_reader().unwrap();
+/// std::io::copy(&mut reader, &mut std::fs::File::create("file.bin").unwrap()).unwrap();
+/// ```
+pub struct LongValueReader {
+  mdb: Mdb,
+  storage: LongValueStorage,
+  length: usize,
+  /// The next LVAL row to read, or zero when there are no more.
+  next_page_row: u32,
+  /// The bytes of the row being read, and how many of them have been.
+  chunk: Vec<u8>,
+  position: usize,
+  /// The bytes of every row so far.
+  total: usize,
+}
+
+impl LongValueReader {
+  /// A reader of the value whose header starts `bytes`, the bytes of a memo or OLE column in its row. Null and
+  /// empty values have no bytes.
+  pub fn new(mdb: &Mdb, bytes: &[u8]) -> Result<Self, MdbError> {
+    let mut reader = LongValueReader {
+      mdb: mdb.clone(),
+      storage: LongValueStorage::Inline,
+      length: 0,
+      next_page_row: 0,
+      chunk: Vec::new(),
+      position: 0,
+      total: 0,
+    };
+    let Some(header) = LongValueHeader::from_bytes(bytes) else {
+      return Ok(reader);
+    };
+
+    reader.storage = header.storage();
+    reader.length = header.length();
+    match reader.storage {
+      LongValueStorage::Inline => reader.chunk = bytes[LONG_VALUE_HEADER_SIZE..].to_vec(),
+      LongValueStorage::SinglePage | LongValueStorage::MultiPage => reader.next_page_row = header.page_row(),
+      LongValueStorage::Unknown => return Err(MdbError::UnhandledType),
+    }
+    Ok(reader)
+  }
+
+  /// The length of the value from its header.
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  /// Read the next LVAL row into the chunk, returning false at the end of the value.
+  fn next_chunk(&mut self) -> Result<bool, MdbError> {
+    if self.next_page_row == 0 || (self.storage == LongValueStorage::MultiPage && self.total >= self.length) {
+      if self.storage == LongValueStorage::MultiPage && self.total < self.length && self.mdb.options.strict {
+        return Err(MdbError::InvalidLongValue);
+      }
+      return Ok(false);
+    }
+
+    let row = mdb_find_page_row_packed(&mut self.mdb, self.next_page_row)?;
+    let start = row.start as usize;
+    let end = start + row.length as usize;
+    self.chunk.clear();
+    self.position = 0;
+
+    if self.storage == LongValueStorage::SinglePage {
+      self.next_page_row = 0;
+      self.chunk.extend_from_slice(&self.mdb.page_buffer[start..end]);
+    } else {
+      // Each row of a chain starts with the page and row of the next. Rows without data end it.
+      if row.length <= 4 {
+        self.next_page_row = 0;
+        return self.next_chunk();
+      }
+      self.next_page_row = get_u32(&self.mdb.page_buffer, start);
+      let piece_length = (row.length as usize - 4).min(self.length - self.total);
+      self.chunk.extend_from_slice(&self.mdb.page_buffer[start + 4..start + 4 + piece_length]);
+    }
+    self.total += self.chunk.len();
+    Ok(true)
+  }
+}
+
+impl Read for LongValueReader {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    while self.position >= self.chunk.len() {
+      if !self.next_chunk().map_err(io::Error::other)? {
+        return Ok(0);
+      }
+    }
+
+    let length = buf.len().min(self.chunk.len() - self.position);
+    buf[..length].copy_from_slice(&self.chunk[self.position..self.position + length]);
+    self.position += length;
+    Ok(length)
+  }
+}
@@ -1,7 +1,14 @@
-pub fn money_column_to_string(buffer: &[u8]) -> String {
-  let raw_data: [u8; 8] = buffer[0..8].try_into().expect("Invalid money data");
-  let val: i64 = i64::from_le_bytes(raw_data);
-  let whole = val / 10000;
-  let fraction: i64 = i64::abs(val - whole * 10000);
-  format!("{}.{}", whole, fraction)
-}
\ No newline at end of file
+use crate::value::Value;
+
+/// The raw currency value, in ten-thousandths.
+pub fn money_column_value(buffer: &[u8]) -> Option<i64> {
+  let raw_data: [u8; 8] = buffer.get(0..8)?.try_into().ok()?;
+  Some(i64::from_le_bytes(raw_data))
+}
+
+pub fn money_column_to_string(buffer: &[u8]) -> String {
+  match money_column_value(buffer) {
+    Some(value) => Value::Currency(value).to_string(),
+    None => String::new(),
+  }
+}
@@ -1,87 +1,48 @@
-const MAX_NUMERIC_PRECISION: usize = 40;
-
-pub fn numeric_column_to_string(buffer: &[u8], precision: u8) -> String {
-  if buffer.len() != 17 {
-    return String::new();
-  }
-
-  let raw_data: [u8; 16] = buffer[1..17].try_into().expect("Invalid money data");
-  let negative: bool = buffer[0] & 0x80 != 0;
-
-  let mut multiplier: [u8; MAX_NUMERIC_PRECISION] = [0; MAX_NUMERIC_PRECISION];
-  multiplier[0] = 1;
-
-  let mut temp: [u8; MAX_NUMERIC_PRECISION] = [0; MAX_NUMERIC_PRECISION];
-  let mut product: [u8; MAX_NUMERIC_PRECISION] = [0; MAX_NUMERIC_PRECISION];
-
-  for i in 0..(raw_data.len()) {
-    /* product += multiplier * current byte */
-    multiply_byte(&mut product, raw_data[12 - 4 * (i / 4) + i % 4] as i32, &mut multiplier);
-
-    temp.copy_from_slice(&multiplier);
-    multiplier.fill(0);
-
-    /* multiplier = multiplier * 256 */
-    multiply_byte(&mut multiplier, 256, &mut temp);
-  }
-
-  array_to_string(&product, precision as usize, negative)
-}
-
-fn multiply_byte(product: &mut [u8], num: i32, multiplier: &mut [u8]) {
-  let number: [u8; 3] = [(num % 10) as u8, ((num / 10) % 10) as u8, ((num / 100) % 10) as u8];
-  for i in 0..multiplier.len() {
-    if multiplier[i] == 0 {
-      continue;
-    }
-
-    let mut j = 0;
-    while j < 3 && i + j < multiplier.len() {
-      if number[j] == 0 {
-        j += 1;
-        continue;
-      }
-      product[i + j] += multiplier[i] * number[j];
-      j += 1;
-    }
-    do_carry(product, multiplier.len());
-  }
-}
-
-fn do_carry(product: &mut [u8], length: usize) {
-  for j in 0..(length - 1) {
-    if product[j] > 9 {
-      product[j + 1] += product[j] / 10;
-      product[j] %= 10;
-    }
-  }
-  if product[length - 1] > 9 {
-    product[length - 1] %= 10;
-  }
-}
-
-fn array_to_string(array: &[u8], scale: usize, negative: bool) -> String {
-  let mut res: String = String::with_capacity(array.len() + 3);
-  let mut top: usize = array.len();
-
-  while top > 0 && top - 1 > scale && array[top - 1] == 0 {
-    top -= 1;
-  }
-
-  if negative {
-    res.push('-');
-  }
-
-  if top == 0 {
-    res.push('0');
-  } else {
-    for i in (1..=top).rev() {
-      if i == scale {
-        res.push('.');
-      }
-      res.push((array[i - 1] + 48) as char);
-    }
-  }
-
-  res
-}
\ No newline at end of file
+use crate::value::Value;
+
+const NUMERIC_SIZE: usize = 17;
+
+/// The unscaled value of a numeric column. The first byte holds the sign, followed by four little endian 32 bit
+/// words, most significant first.
+pub fn numeric_column_value(buffer: &[u8]) -> Option<i128> {
+  if buffer.len() != NUMERIC_SIZE {
+    return None;
+  }
+
+  let negative: bool = buffer[0] & 0x80 != 0;
+  signed_value(&buffer[1..NUMERIC_SIZE], negative)
+}
+
+/// The unscaled value and scale of a calculated numeric column, which stores its own scale. The length of the rest
+/// of the value comes first, then the scale, the sign and up to 16 bytes of digits in the word order of numeric
+/// columns.
+pub fn calculated_numeric_value(buffer: &[u8]) -> Option<(i128, u8)> {
+  let remaining = buffer.len().checked_sub(2)?;
+  let length = match u16::from_le_bytes(buffer.get(0..2)?.try_into().unwrap()) as usize {
+    0 => remaining,
+    length => length.min(remaining),
+  };
+  let scale = *buffer.get(2)?;
+  let negative = *buffer.get(3)? != 0;
+  let digits_length = (length.checked_sub(2)? / 4 * 4).min(NUMERIC_SIZE - 1);
+  let value = signed_value(buffer.get(4..4 + digits_length)?, negative)?;
+  Some((value, scale))
+}
+
+/// Little endian 32 bit words, most significant first.
+fn signed_value(words: &[u8], negative: bool) -> Option<i128> {
+  let mut magnitude: u128 = 0;
+  for word in words.chunks(4) {
+    magnitude = (magnitude << 32) | u32::from_le_bytes(word.try_into().unwrap()) as u128;
+  }
+
+  let value = i128::try_from(magnitude).ok()?;
+  Some(if negative { -value } else { value })
+}
+
+pub fn numeric_column_to_string(buffer: &[u8], scale: u8) -> String {
+  match numeric_column_value(buffer) {
+    Some(value) => Value::Decimal { value, scale }.to_string(),
+    None => String::new(),
+  }
+}
@@ -0,0 +1,297 @@
+use std::io::{self, Read};
+
+use encoding_rs::WINDOWS_1252;
+
+use crate::column::{Column, ColumnType};
+use crate::error::MdbError;
+use crate::utils::{get_u16, get_u32};
+
+/// The first two bytes of an OLE Object field.
+pub const OLE_HEADER_SIGNATURE: u16 = 0x1C15;
+/// The OLE 1.0 format of an object stored as a link to a file.
+const LINKED_FORMAT: u32 = 1;
+/// A package holding an embedded file, rather than a link to one.
+const PACKAGE_EMBEDDED_FILE: u32 = 0x0003_0000;
+/// How much of the embedded file `OleObjectReader` reads ahead to guess its extension.
+const HEAD_LENGTH: usize = 16;
+
+/// What an OLE object holds, from its class.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OleContentType {
+  /// A file wrapped by the Packager, with its original name.
+  Package,
+  /// A Paintbrush bitmap.
+  Bitmap,
+  Word,
+  Excel,
+  Pdf,
+  /// A link to a file outside the database.
+  Link,
+  Other,
+}
+
+/// An OLE Object field with the Access header and OLE wrapping removed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OleObject {
+  /// The name Access shows for the object, such as "Bitmap Image".
+  pub pretty_name: String,
+  /// The OLE class of the object, such as `Package` or `Word.Document.8`.
+  pub class_name: String,
+  pub content_type: OleContentType,
+  /// The name of the embedded file, for packages.
+  pub file_name: Option<String>,
+  /// The path the file was embedded or linked from.
+  pub file_path: Option<String>,
+  /// The embedded file: the contents of a package, a bitmap, or a document as its application saves it. Other
+  /// objects have their native OLE data, and links none.
+  pub data: Vec<u8>,
+}
+
+impl OleObject {
+  /// Parse the bytes of an OLE Object field. Fields that don't start with the Access header are an
+  /// `InvalidOleObject` error, as Access also stores plain binary data in OLE columns.
+  pub fn parse(bytes: &[u8]) -> Result<OleObject, MdbError> {
+    let mut reader = OleObjectReader::new(bytes)?;
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(OleObject { data, ..reader.object })
+  }
+
+  /// The extension of the embedded file, from its name or else its type and contents.
+  pub fn extension(&self) -> Option<String> {
+    self.extension_of(&self.data)
+  }
+
+  /// The extension, guessed from `data` when the name has none.
+  fn extension_of(&self, data: &[u8]) -> Option<String> {
+    let from_name = self.file_name.as_deref()
+      .and_then(|name| name.rsplit_once('.'))
+      .map(|(_, extension)| extension.to_string());
+    from_name.or_else(|| match self.content_type {
+      OleContentType::Bitmap if data.starts_with(b"BM") => Some("bmp".to_string()),
+      OleContentType::Word => Some("doc".to_string()),
+      OleContentType::Excel => Some("xls".to_string()),
+      OleContentType::Pdf if data.starts_with(b"%PDF") => Some("pdf".to_string()),
+      _ => detect_extension(data).map(str::to_string),
+    })
+  }
+}
+
+/// Streams the embedded file of an OLE Object field after parsing the headers in front of it, so objects can be
+/// copied out of a `LongValueReader` without holding them whole. Acrobat objects are the exception: they are read
+/// whole to carve the PDF out of them. A field shorter than its headers say is an `InvalidOleObject` error.
+///
+/// ```no_run
+/// use mdbtools::database::Database;
+/// use mdbtools::ole::OleObjectReader;
+///
+/// let mut database = Database::open("database.accdb").unwrap();
+/// let mut table = database.table("Documents").unwrap();
+/// let column = table.find_column_index("File").unwrap();
+/// table.fetch_row().unwrap();
+/// let mut reader = OleObjectReader::new(table.columns[column].long_value_reader().unwrap()).unwrap();
+/// let name = format!("file.{}", reader.extension().unwrap_or_else(|| "bin".to_string()));
+/// std::io::copy(&mut reader, &mut std::fs::File::create(name).unwrap()).unwrap();
+/// ```
+pub struct OleObjectReader<R: Read> {
+  /// The object without its data.
+  object: OleObject,
+  /// The start of the embedded file, read ahead, and how much of it has been returned.
+  head: Vec<u8>,
+  position: usize,
+  reader: io::Take<R>,
+}
+
+impl<R: Read> OleObjectReader<R> {
+  /// Parse the headers of the field `reader` returns, leaving it at the embedded file. Fields that don't start with
+  /// the Access header are an `InvalidOleObject` error.
+  pub fn new(mut reader: R) -> Result<Self, MdbError> {
+    let (object, length) = read_headers(&mut reader)?;
+    let mut reader = reader.take(length as u64);
+    let head = if object.content_type == OleContentType::Pdf {
+      let native = read_bytes(&mut reader, length)?;
+      carve_pdf(&native).map(<[u8]>::to_vec).unwrap_or(native)
+    } else {
+      read_bytes(&mut reader, HEAD_LENGTH.min(length))?
+    };
+    Ok(OleObjectReader { object, head, position: 0, reader })
+  }
+
+  /// The object, with an empty `data`.
+  pub fn object(&self) -> &OleObject {
+    &self.object
+  }
+
+  /// The extension of the embedded file, from its name or else its type and first bytes.
+  pub fn extension(&self) -> Option<String> {
+    self.object.extension_of(&self.head)
+  }
+}
+
+impl<R: Read> Read for OleObjectReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    if self.position < self.head.len() {
+      let length = buf.len().min(self.head.len() - self.position);
+      buf[..length].copy_from_slice(&self.head[self.position..self.position + length]);
+      self.position += length;
+      return Ok(length);
+    }
+
+    let length = self.reader.read(buf)?;
+    if length == 0 && !buf.is_empty() && self.reader.limit() > 0 {
+      return Err(io::Error::other(MdbError::InvalidOleObject));
+    }
+    Ok(length)
+  }
+}
+
+/// Read the Access header, then an OLE 1.0 object up to its native data, returning the object and the length of the
+/// embedded file that follows.
+fn read_headers(reader: &mut impl Read) -> Result<(OleObject, usize), MdbError> {
+  // The Access header: its size, then the lengths and offsets of the pretty and class names.
+  let mut header = read_bytes(reader, 4)?;
+  let header_size = get_u16(&header, 2) as usize;
+  if get_u16(&header, 0) != OLE_HEADER_SIGNATURE || header_size < 20 {
+    return Err(MdbError::InvalidOleObject);
+  }
+  header.extend(read_bytes(reader, header_size - 4)?);
+  let pretty_name = read_fixed_str(&header, get_u16(&header, 12) as usize, get_u16(&header, 8) as usize)?;
+  let class_name = read_fixed_str(&header, get_u16(&header, 14) as usize, get_u16(&header, 10) as usize)?;
+
+  // An OLE 1.0 object: the version, format, class, topic and item, then the native data.
+  let _version = read_u32(reader)?;
+  let format = read_u32(reader)?;
+  let type_name = read_sized_str(reader)?;
+  let topic = read_sized_str(reader)?;
+  let _item = read_sized_str(reader)?;
+
+  let mut object = OleObject {
+    pretty_name,
+    class_name: if type_name.is_empty() { class_name } else { type_name },
+    content_type: OleContentType::Other,
+    file_name: None,
+    file_path: None,
+    data: Vec::new(),
+  };
+
+  if format == LINKED_FORMAT {
+    object.content_type = OleContentType::Link;
+    object.file_path = Some(topic).filter(|path| !path.is_empty());
+    return Ok((object, 0));
+  }
+
+  let native_length = read_u32(reader)? as usize;
+  object.content_type = content_type(&object.class_name);
+  if object.content_type != OleContentType::Package {
+    return Ok((object, native_length));
+  }
+
+  // A package: a type, the file name and source path, then for an embedded file a temporary path and the contents.
+  read_bytes(reader, 2)?;
+  object.file_name = Some(read_zero_terminated_str(reader)?).filter(|name| !name.is_empty());
+  object.file_path = Some(read_zero_terminated_str(reader)?).filter(|path| !path.is_empty());
+  if read_u32(reader)? != PACKAGE_EMBEDDED_FILE {
+    return Ok((object, 0));
+  }
+  let _temp_path = read_sized_str(reader)?;
+  let length = read_u32(reader)? as usize;
+  Ok((object, length))
+}
+
+impl Column {
+  /// The OLE object in the current row of an OLE column.
+  pub fn ole_object(&self) -> Result<OleObject, MdbError> {
+    if self.column_type != ColumnType::OLE {
+      return Err(MdbError::UnhandledType);
+    }
+    OleObject::parse(self.long_value()?)
+  }
+}
+
+/// Guess the extension of a file from its first bytes.
+pub fn detect_extension(data: &[u8]) -> Option<&'static str> {
+  const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"%PDF", "pdf"),
+    (b"\x89PNG\r\n\x1a\n", "png"),
+    (b"\xff\xd8\xff", "jpg"),
+    (b"GIF8", "gif"),
+    (b"BM", "bmp"),
+    (b"II*\x00", "tif"),
+    (b"MM\x00*", "tif"),
+    (b"PK\x03\x04", "zip"),
+    (b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1", "ole"),
+    (b"{\\rtf", "rtf"),
+  ];
+  SIGNATURES.iter().find(|(signature, _)| data.starts_with(signature)).map(|(_, extension)| *extension)
+}
+
+fn content_type(class_name: &str) -> OleContentType {
+  let class = class_name.to_ascii_lowercase();
+  if class == "package" {
+    OleContentType::Package
+  } else if class == "pbrush" || class.starts_with("paint.picture") || class == "staticdib" {
+    OleContentType::Bitmap
+  } else if class.starts_with("word.document") {
+    OleContentType::Word
+  } else if class.starts_with("excel.sheet") || class.starts_with("excel.chart") {
+    OleContentType::Excel
+  } else if class.starts_with("acroexch.") || class.starts_with("acrobat.") {
+    OleContentType::Pdf
+  } else {
+    OleContentType::Other
+  }
+}
+
+/// Acrobat stores the PDF in a stream of a compound file. Streams are usually written in one piece, so the file is
+/// taken from its header to its last end marker.
+fn carve_pdf(native: &[u8]) -> Option<&[u8]> {
+  let start = find(native, b"%PDF-")?;
+  let end = native.windows(5).rposition(|window| window == b"%%EOF")? + 5;
+  let end = end + native[end..].iter().take(2).take_while(|byte| **byte == b'\r' || **byte == b'\n').count();
+  (end > start).then(|| &native[start..end])
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn read_fixed_str(bytes: &[u8], offset: usize, length: usize) -> Result<String, MdbError> {
+  let raw = bytes.get(offset..offset + length).ok_or(MdbError::InvalidOleObject)?;
+  Ok(decode_ansi(raw))
+}
+
+/// Decode an ANSI string, dropping the terminating zero.
+fn decode_ansi(raw: &[u8]) -> String {
+  let raw = raw.split(|byte| *byte == 0).next().unwrap_or_default();
+  WINDOWS_1252.decode(raw).0.into_owned()
+}
+
+/// Read exactly `length` bytes, or fail with `InvalidOleObject` if the field ends first.
+fn read_bytes(reader: &mut impl Read, length: usize) -> Result<Vec<u8>, MdbError> {
+  let mut bytes = Vec::new();
+  reader.take(length as u64).read_to_end(&mut bytes)?;
+  if bytes.len() < length {
+    return Err(MdbError::InvalidOleObject);
+  }
+  Ok(bytes)
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, MdbError> {
+  Ok(get_u32(&read_bytes(reader, 4)?, 0))
+}
+
+/// A string with its length in front.
+fn read_sized_str(reader: &mut impl Read) -> Result<String, MdbError> {
+  let length = read_u32(reader)? as usize;
+  Ok(decode_ansi(&read_bytes(reader, length)?))
+}
+
+fn read_zero_terminated_str(reader: &mut impl Read) -> Result<String, MdbError> {
+  let mut bytes = Vec::new();
+  loop {
+    match read_bytes(reader, 1)?[0] {
+      0 => return Ok(decode_ansi(&bytes)),
+      byte => bytes.push(byte),
+    }
+  }
+}
@@ -0,0 +1,103 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+
+use encoding_rs::Encoding;
+
+use crate::database::Database;
+use crate::error::MdbError;
+use crate::mdbfile::{Mdb, MdbSource};
+
+/// The number of pages cached by default, 1 MiB with 4 KiB pages.
+pub const DEFAULT_PAGE_CACHE_SIZE: usize = 256;
+
+/// Options for opening a database. Every table and memo read from the opened database uses them.
+///
+/// ```no_run
+/// use mdbtools::options::OpenOptions;
+///
+/// let mdb = OpenOptions::new()
+///   .password("secret")
+///   .strict(true)
+///   .max_long_value_size(Some(1 << 20))
+///   .open("database.accdb");
+/// ```
+#[derive(Clone, Debug)]
+pub struct OpenOptions {
+  pub(crate) password: Option<String>,
+  pub(crate) encoding: Option<&'static Encoding>,
+  pub(crate) strict: bool,
+  pub(crate) page_cache_size: usize,
+  pub(crate) max_long_value_size: Option<usize>,
+  pub(crate) show_system_tables: bool,
+}
+
+impl Default for OpenOptions {
+  fn default() -> Self {
+    OpenOptions {
+      password: None,
+      encoding: None,
+      strict: false,
+      page_cache_size: DEFAULT_PAGE_CACHE_SIZE,
+      max_long_value_size: None,
+      show_system_tables: false,
+    }
+  }
+}
+
+impl OpenOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// The database password. When set, it is checked against the password stored in the header and ignored when the
+  /// database has none. Without it, databases with a password open as well, since the password encrypts nothing.
+  pub fn password(&mut self, password: impl Into<String>) -> &mut Self {
+    self.password = Some(password.into());
+    self
+  }
+
+  /// Decode text with this encoding instead of the one implied by the format, such as a code page for JET3 files.
+  pub fn encoding(&mut self, encoding: &'static Encoding) -> &mut Self {
+    self.encoding = Some(encoding);
+    self
+  }
+
+  /// Return an error for corrupt rows and memos instead of skipping them.
+  pub fn strict(&mut self, strict: bool) -> &mut Self {
+    self.strict = strict;
+    self
+  }
+
+  /// The number of pages kept in memory. Zero disables the cache.
+  pub fn page_cache_size(&mut self, pages: usize) -> &mut Self {
+    self.page_cache_size = pages;
+    self
+  }
+
+  /// The most bytes read for a memo or OLE value. Longer values are cut short, or are an error in strict mode.
+  pub fn max_long_value_size(&mut self, bytes: Option<usize>) -> &mut Self {
+    self.max_long_value_size = bytes;
+    self
+  }
+
+  /// Whether `Database::tables` includes the system tables.
+  pub fn show_system_tables(&mut self, show: bool) -> &mut Self {
+    self.show_system_tables = show;
+    self
+  }
+
+  pub fn open(&self, path: impl AsRef<Path>) -> Result<Mdb, MdbError> {
+    let file = File::open(path).map_err(|_| MdbError::ReadPage)?;
+    self.open_reader(file)
+  }
+
+  /// Open a database from any seekable source, such as an in-memory `Cursor`.
+  pub fn open_reader(&self, source: impl MdbSource + 'static) -> Result<Mdb, MdbError> {
+    Mdb::from_source(Box::new(source), Arc::new(self.clone()))
+  }
+
+  pub fn open_database(&self, path: impl AsRef<Path>) -> Result<Database, MdbError> {
+    Ok(Database::from_mdb(self.open(path)?))
+  }
+}
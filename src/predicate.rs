@@ -0,0 +1,677 @@
+use std::cmp::Ordering;
+
+use crate::column::{Column, ColumnType};
+use crate::error::MdbError;
+use crate::index::{encode_key, IndexCursor, KeyBound};
+use crate::sql::{parse_date_literal, tokenize, Token};
+use crate::table::{Table, TableStrategy};
+use crate::time::datetime_to_f64;
+use crate::value::{format_guid, Value};
+
+/// A comparison operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+}
+
+impl CompareOp {
+  fn matches(self, ordering: Ordering) -> bool {
+    match self {
+      CompareOp::Eq => ordering == Ordering::Equal,
+      CompareOp::Ne => ordering != Ordering::Equal,
+      CompareOp::Lt => ordering == Ordering::Less,
+      CompareOp::Le => ordering != Ordering::Greater,
+      CompareOp::Gt => ordering == Ordering::Greater,
+      CompareOp::Ge => ordering != Ordering::Less,
+    }
+  }
+}
+
+/// A condition on the columns of a row, tested while a table is scanned. Comparisons with null are unknown, as in
+/// SQL, so only rows where the whole predicate is true are returned.
+///
+/// ```
+/// use mdbtools::predicate::{CompareOp, Predicate};
+///
+/// let parsed = Predicate::parse("[Owner] = 'Sales' AND Quantity >= 2").unwrap();
+/// let built = Predicate::compare("Owner", CompareOp::Eq, "Sales").and(Predicate::compare("Quantity", CompareOp::Ge, 2i64));
+/// assert_eq!(parsed, built);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predicate {
+  Compare { column: String, op: CompareOp, value: Value },
+  In { column: String, values: Vec<Value> },
+  IsNull(String),
+  /// An Access `LIKE` pattern: `*` matches any text, `?` any character, `#` any digit and `[a-z]` or `[!a-z]` a
+  /// character in or not in a set. Matching ignores case.
+  Like { column: String, pattern: String },
+  And(Vec<Predicate>),
+  Or(Vec<Predicate>),
+  Not(Box<Predicate>),
+}
+
+impl Predicate {
+  pub fn compare(column: impl Into<String>, op: CompareOp, value: impl Into<Value>) -> Self {
+    Predicate::Compare { column: column.into(), op, value: value.into() }
+  }
+
+  pub fn is_in<V: Into<Value>>(column: impl Into<String>, values: impl IntoIterator<Item = V>) -> Self {
+    Predicate::In { column: column.into(), values: values.into_iter().map(Into::into).collect() }
+  }
+
+  pub fn is_null(column: impl Into<String>) -> Self {
+    Predicate::IsNull(column.into())
+  }
+
+  pub fn is_not_null(column: impl Into<String>) -> Self {
+    Predicate::IsNull(column.into()).negate()
+  }
+
+  pub fn like(column: impl Into<String>, pattern: impl Into<String>) -> Self {
+    Predicate::Like { column: column.into(), pattern: pattern.into() }
+  }
+
+  pub fn and(self, other: Predicate) -> Self {
+    match self {
+      Predicate::And(mut predicates) => {
+        predicates.push(other);
+        Predicate::And(predicates)
+      }
+      predicate => Predicate::And(vec![predicate, other]),
+    }
+  }
+
+  pub fn or(self, other: Predicate) -> Self {
+    match self {
+      Predicate::Or(mut predicates) => {
+        predicates.push(other);
+        Predicate::Or(predicates)
+      }
+      predicate => Predicate::Or(vec![predicate, other]),
+    }
+  }
+
+  pub fn negate(self) -> Self {
+    Predicate::Not(Box::new(self))
+  }
+
+  /// Parse the condition of an Access SQL `WHERE` clause, such as `[Owner] = 'Sales' AND Acquired > #1/1/1997#`.
+  /// Columns are compared with literals using `=`, `<>`, `<`, `<=`, `>`, `>=`, `IN`, `BETWEEN`, `LIKE` and
+  /// `IS NULL`, and conditions are combined with `AND`, `OR`, `NOT` and parentheses.
+  pub fn parse(condition: &str) -> Result<Predicate, MdbError> {
+    let tokens = tokenize(condition)?.into_iter().filter(|token| !matches!(token, Token::Whitespace(_))).collect();
+    let mut parser = Parser { tokens, position: 0 };
+    let predicate = parser.parse_or()?;
+    if parser.position != parser.tokens.len() {
+      return Err(MdbError::InvalidSql);
+    }
+    Ok(predicate)
+  }
+
+  /// The names of the columns the predicate reads.
+  pub fn columns(&self) -> Vec<&str> {
+    let mut columns = Vec::new();
+    self.collect_columns(&mut columns);
+    columns
+  }
+
+  fn collect_columns<'a>(&'a self, columns: &mut Vec<&'a str>) {
+    match self {
+      Predicate::Compare { column, .. } | Predicate::In { column, .. } | Predicate::Like { column, .. } | Predicate::IsNull(column) => {
+        if !columns.contains(&column.as_str()) {
+          columns.push(column);
+        }
+      }
+      Predicate::And(predicates) | Predicate::Or(predicates) => {
+        for predicate in predicates {
+          predicate.collect_columns(columns);
+        }
+      }
+      Predicate::Not(predicate) => predicate.collect_columns(columns),
+    }
+  }
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position)
+  }
+
+  fn next(&mut self) -> Result<Token, MdbError> {
+    let token = self.tokens.get(self.position).cloned().ok_or(MdbError::InvalidSql)?;
+    self.position += 1;
+    Ok(token)
+  }
+
+  /// Consume the bare word `word` if it is next.
+  fn accept_word(&mut self, word: &str) -> bool {
+    let found = self.peek().is_some_and(|token| token.is_word(word));
+    if found {
+      self.position += 1;
+    }
+    found
+  }
+
+  fn accept_symbol(&mut self, symbol: &str) -> bool {
+    let found = matches!(self.peek(), Some(Token::Symbol(s)) if s == symbol);
+    if found {
+      self.position += 1;
+    }
+    found
+  }
+
+  fn expect_symbol(&mut self, symbol: &str) -> Result<(), MdbError> {
+    if self.accept_symbol(symbol) { Ok(()) } else { Err(MdbError::InvalidSql) }
+  }
+
+  fn parse_or(&mut self) -> Result<Predicate, MdbError> {
+    let mut predicate = self.parse_and()?;
+    while self.accept_word("OR") {
+      predicate = predicate.or(self.parse_and()?);
+    }
+    Ok(predicate)
+  }
+
+  fn parse_and(&mut self) -> Result<Predicate, MdbError> {
+    let mut predicate = self.parse_not()?;
+    while self.accept_word("AND") {
+      predicate = predicate.and(self.parse_not()?);
+    }
+    Ok(predicate)
+  }
+
+  fn parse_not(&mut self) -> Result<Predicate, MdbError> {
+    if self.accept_word("NOT") {
+      return Ok(self.parse_not()?.negate());
+    }
+    if self.accept_symbol("(") {
+      let predicate = self.parse_or()?;
+      self.expect_symbol(")")?;
+      return Ok(predicate);
+    }
+    self.parse_condition()
+  }
+
+  fn parse_condition(&mut self) -> Result<Predicate, MdbError> {
+    let column = match self.next()? {
+      Token::QuotedIdentifier(name) => name,
+      Token::Identifier(name) if !["AND", "OR", "NOT", "NULL"].iter().any(|word| name.eq_ignore_ascii_case(word)) => name,
+      _ => return Err(MdbError::InvalidSql),
+    };
+
+    if self.accept_word("IS") {
+      let negated = self.accept_word("NOT");
+      if !self.accept_word("NULL") {
+        return Err(MdbError::InvalidSql);
+      }
+      let predicate = Predicate::is_null(column);
+      return Ok(if negated { predicate.negate() } else { predicate });
+    }
+
+    let negated = self.accept_word("NOT");
+    let predicate = if self.accept_word("IN") {
+      self.expect_symbol("(")?;
+      let mut values = vec![self.parse_literal()?];
+      while self.accept_symbol(",") {
+        values.push(self.parse_literal()?);
+      }
+      self.expect_symbol(")")?;
+      Predicate::In { column, values }
+    } else if self.accept_word("LIKE") {
+      match self.next()? {
+        Token::String(pattern) => Predicate::like(column, pattern),
+        _ => return Err(MdbError::InvalidSql),
+      }
+    } else if self.accept_word("BETWEEN") {
+      let low = self.parse_literal()?;
+      if !self.accept_word("AND") {
+        return Err(MdbError::InvalidSql);
+      }
+      let high = self.parse_literal()?;
+      Predicate::compare(column.clone(), CompareOp::Ge, low).and(Predicate::compare(column, CompareOp::Le, high))
+    } else if negated {
+      return Err(MdbError::InvalidSql);
+    } else {
+      let op = match self.next()? {
+        Token::Symbol(symbol) => match symbol.as_str() {
+          "=" => CompareOp::Eq,
+          "<>" => CompareOp::Ne,
+          "<" => CompareOp::Lt,
+          "<=" => CompareOp::Le,
+          ">" => CompareOp::Gt,
+          ">=" => CompareOp::Ge,
+          _ => return Err(MdbError::InvalidSql),
+        },
+        _ => return Err(MdbError::InvalidSql),
+      };
+      Predicate::compare(column, op, self.parse_literal()?)
+    };
+    Ok(if negated { predicate.negate() } else { predicate })
+  }
+
+  fn parse_literal(&mut self) -> Result<Value, MdbError> {
+    match self.next()? {
+      Token::String(value) => Ok(Value::Text(value)),
+      Token::Number(number) => parse_number(&number),
+      Token::Symbol(sign) if sign == "-" || sign == "+" => match (self.next()?, sign.as_str()) {
+        (Token::Number(number), "-") => parse_number(&format!("-{}", number)),
+        (Token::Number(number), _) => parse_number(&number),
+        _ => Err(MdbError::InvalidSql),
+      },
+      Token::Date(date) => parse_date_literal(&date).map(Value::DateTime).ok_or(MdbError::InvalidSql),
+      token if token.is_word("TRUE") => Ok(Value::Bool(true)),
+      token if token.is_word("FALSE") => Ok(Value::Bool(false)),
+      token if token.is_word("NULL") => Ok(Value::Null),
+      _ => Err(MdbError::InvalidSql),
+    }
+  }
+}
+
+fn parse_number(number: &str) -> Result<Value, MdbError> {
+  if let Ok(value) = number.parse::<i64>() {
+    return Ok(Value::I64(value));
+  }
+  number.parse::<f64>().map(Value::F64).map_err(|_| MdbError::InvalidSql)
+}
+
+/// A predicate with its columns resolved to positions in `Table::columns`.
+#[derive(Clone, Debug)]
+enum Condition {
+  /// The literal is also kept as a number when the column is compared on its raw bytes.
+  Compare(usize, CompareOp, Value, Option<f64>),
+  In(usize, Vec<Value>),
+  IsNull(usize),
+  Like(usize, Vec<LikeToken>),
+  And(Vec<Condition>),
+  Or(Vec<Condition>),
+  Not(Box<Condition>),
+}
+
+impl Condition {
+  fn new(predicate: &Predicate, table: &Table) -> Result<Condition, MdbError> {
+    let column = |name: &str| table.resolve_column(name).ok_or(MdbError::ColumnNotFound);
+    let conditions = |predicates: &[Predicate]| predicates.iter().map(|predicate| Condition::new(predicate, table)).collect::<Result<Vec<_>, _>>();
+    Ok(match predicate {
+      Predicate::Compare { column: name, op, value } => {
+        let index = column(name)?;
+        let number = raw_number_type(table.columns[index].column_type).then(|| literal_number(value)).flatten();
+        Condition::Compare(index, *op, value.clone(), number)
+      }
+      Predicate::In { column: name, values } => Condition::In(column(name)?, values.clone()),
+      Predicate::IsNull(name) => Condition::IsNull(column(name)?),
+      Predicate::Like { column: name, pattern } => Condition::Like(column(name)?, parse_like_pattern(pattern)),
+      Predicate::And(predicates) => Condition::And(conditions(predicates)?),
+      Predicate::Or(predicates) => Condition::Or(conditions(predicates)?),
+      Predicate::Not(predicate) => Condition::Not(Box::new(Condition::new(predicate, table)?)),
+    })
+  }
+
+  /// Whether the current row matches, or None when it is unknown because of a null.
+  fn evaluate(&self, columns: &[Column]) -> Result<Option<bool>, MdbError> {
+    match self {
+      Condition::Compare(index, op, value, number) => {
+        let ordering = match number {
+          Some(number) => raw_number(&columns[*index]).and_then(|raw| raw.partial_cmp(number)),
+          None => compare_values(&columns[*index].scan_value()?, value),
+        };
+        Ok(ordering.map(|ordering| op.matches(ordering)))
+      }
+      Condition::In(index, values) => {
+        let column = columns[*index].scan_value()?;
+        let mut result = Some(false);
+        for value in values {
+          match compare_values(&column, value) {
+            Some(Ordering::Equal) => return Ok(Some(true)),
+            Some(_) => {}
+            None => result = None,
+          }
+        }
+        Ok(result)
+      }
+      Condition::IsNull(index) => Ok(Some(columns[*index].scan_value()?.is_null())),
+      Condition::Like(index, pattern) => Ok(match columns[*index].scan_value()? {
+        Value::Null => None,
+        Value::Text(text) => Some(like_matches(pattern, &text)),
+        value => Some(like_matches(pattern, &value.to_string())),
+      }),
+      Condition::And(conditions) => {
+        let mut result = Some(true);
+        for condition in conditions {
+          match condition.evaluate(columns)? {
+            Some(false) => return Ok(Some(false)),
+            Some(true) => {}
+            None => result = None,
+          }
+        }
+        Ok(result)
+      }
+      Condition::Or(conditions) => {
+        let mut result = Some(false);
+        for condition in conditions {
+          match condition.evaluate(columns)? {
+            Some(true) => return Ok(Some(true)),
+            Some(false) => {}
+            None => result = None,
+          }
+        }
+        Ok(result)
+      }
+      Condition::Not(condition) => Ok(condition.evaluate(columns)?.map(|result| !result)),
+    }
+  }
+
+  fn collect_columns(&self, columns: &mut Vec<usize>) {
+    match self {
+      Condition::Compare(index, ..) | Condition::In(index, _) | Condition::IsNull(index) | Condition::Like(index, _) => {
+        if !columns.contains(index) {
+          columns.push(*index);
+        }
+      }
+      Condition::And(conditions) | Condition::Or(conditions) => {
+        for condition in conditions {
+          condition.collect_columns(columns);
+        }
+      }
+      Condition::Not(condition) => condition.collect_columns(columns),
+    }
+  }
+
+  /// The range of values of a column that every matching row is within, from the comparisons joined by `AND`.
+  fn key_range(&self, column: usize, column_type: ColumnType) -> (Option<f64>, Option<f64>) {
+    let to_key = |value: &Value| key_value(value, column_type);
+    match self {
+      Condition::Compare(index, op, value, _) if *index == column => match (op, to_key(value)) {
+        (CompareOp::Eq, Some(key)) => (Some(key), Some(key)),
+        (CompareOp::Gt | CompareOp::Ge, Some(key)) => (Some(key), None),
+        (CompareOp::Lt | CompareOp::Le, Some(key)) => (None, Some(key)),
+        _ => (None, None),
+      },
+      Condition::In(index, values) if *index == column => {
+        let keys: Option<Vec<f64>> = values.iter().map(to_key).collect();
+        match keys {
+          Some(keys) if !keys.is_empty() => (keys.iter().copied().reduce(f64::min), keys.iter().copied().reduce(f64::max)),
+          _ => (None, None),
+        }
+      }
+      Condition::And(conditions) => conditions.iter().fold((None, None), |(lower, upper), condition| {
+        let (low, high) = condition.key_range(column, column_type);
+        (tighter_bound(lower, low, f64::max), tighter_bound(upper, high, f64::min))
+      }),
+      _ => (None, None),
+    }
+  }
+}
+
+/// Combine two bounds, keeping the tighter one.
+fn tighter_bound(a: Option<f64>, b: Option<f64>, tighter: fn(f64, f64) -> f64) -> Option<f64> {
+  match (a, b) {
+    (Some(a), Some(b)) => Some(tighter(a, b)),
+    (a, b) => a.or(b),
+  }
+}
+
+/// A literal as a number in the key order of an index on a column of the given type.
+fn key_value(value: &Value, column_type: ColumnType) -> Option<f64> {
+  let key = match column_type {
+    ColumnType::Datetime => match value {
+      Value::DateTime(value) | Value::ExtendedDateTime(value) => Some(datetime_to_f64(value)),
+      Value::Text(text) => parse_date_literal(text).map(|value| datetime_to_f64(&value)),
+      _ => None,
+    },
+    _ => literal_number(value),
+  };
+  key.filter(|key| !key.is_nan())
+}
+
+/// Whether columns of the type are compared on their raw bytes. Their bytes are a little-endian number, unlike
+/// dates, whose encoding doesn't sort before 1900, and money, which would lose precision as a float.
+fn raw_number_type(column_type: ColumnType) -> bool {
+  matches!(column_type, ColumnType::Byte | ColumnType::Int | ColumnType::LongInt | ColumnType::Float | ColumnType::Double)
+}
+
+/// A literal as a number, read the way `compare_values` reads it for number columns.
+fn literal_number(value: &Value) -> Option<f64> {
+  match value {
+    Value::Text(text) => text.trim().parse().ok(),
+    Value::Bool(value) => Some(if *value { -1.0 } else { 0.0 }),
+    value => value.as_f64(),
+  }
+}
+
+/// The number in the buffer of a column of a `raw_number_type`, or None when it is null.
+fn raw_number(column: &Column) -> Option<f64> {
+  if column.buffer.is_null {
+    return None;
+  }
+  let data = &column.buffer.value;
+  match column.column_type {
+    ColumnType::Byte => data.first().map(|byte| *byte as f64),
+    ColumnType::Int => data.get(0..2).map(|raw| i16::from_le_bytes(raw.try_into().unwrap()) as f64),
+    ColumnType::LongInt => data.get(0..4).map(|raw| i32::from_le_bytes(raw.try_into().unwrap()) as f64),
+    ColumnType::Float => data.get(0..4).map(|raw| f32::from_le_bytes(raw.try_into().unwrap()) as f64),
+    ColumnType::Double => data.get(0..8).map(|raw| f64::from_le_bytes(raw.try_into().unwrap())),
+    _ => None,
+  }
+}
+
+/// A predicate ready to be tested against the column buffers of a table.
+#[derive(Clone, Debug)]
+pub(crate) struct Filter {
+  predicate: Predicate,
+  condition: Condition,
+  /// The columns the condition reads.
+  pub(crate) columns: Vec<usize>,
+}
+
+impl Filter {
+  pub(crate) fn matches(&self, columns: &[Column]) -> Result<bool, MdbError> {
+    Ok(self.condition.evaluate(columns)? == Some(true))
+  }
+}
+
+impl Table {
+  /// Only return rows matching the predicate. Rows are tested before their values are built: number columns
+  /// compared with a literal are read straight from their bytes, the other columns the predicate reads are decoded
+  /// on their own, and only the memos it reads are fetched for rows that don't match. When an ascending index
+  /// starts with a number or date column compared in the predicate, the scan walks the index instead of every data
+  /// page. Columns are matched by name as in `Table::select`.
+  pub fn filter(&mut self, predicate: Predicate) -> Result<(), MdbError> {
+    if self.columns.is_empty() {
+      self.read_columns()?;
+    }
+
+    let condition = Condition::new(&predicate, self)?;
+    let mut columns = Vec::new();
+    condition.collect_columns(&mut columns);
+
+    self.index_cursor = self.choose_index(&condition);
+    self.strategy = if self.index_cursor.is_some() { TableStrategy::IndexScan } else { TableStrategy::TableScan };
+    self.filter = Some(Filter { predicate, condition, columns });
+    self.update_decoded_columns();
+    self.rewind();
+    Ok(())
+  }
+
+  /// Return every row again.
+  pub fn clear_filter(&mut self) {
+    self.filter = None;
+    self.index_cursor = None;
+    self.strategy = TableStrategy::TableScan;
+    self.update_decoded_columns();
+    self.rewind();
+  }
+
+  pub fn predicate(&self) -> Option<&Predicate> {
+    self.filter.as_ref().map(|filter| &filter.predicate)
+  }
+
+  /// The index the filtered scan walks, if one could be used.
+  pub fn scan_index(&self) -> Option<&crate::index::Index> {
+    self.index_cursor.as_ref().map(|cursor| &self.indexes[cursor.index])
+  }
+
+  /// Pick the index with the tightest key range on its first column. Only ascending indexes on number and date
+  /// columns are used; the sort order of text keys depends on the collation, so text indexes aren't.
+  fn choose_index(&self, condition: &Condition) -> Option<IndexCursor> {
+    self.indexes.iter().enumerate()
+      .filter_map(|(position, index)| {
+        let first = index.columns.first().filter(|first| first.ascending && index.first_page != 0)?;
+        let column = self.find_column_index(&first.name)?;
+        let column_type = self.columns[column].column_type;
+        let (mut lower, mut upper) = condition.key_range(column, column_type);
+        if column_type == ColumnType::Datetime {
+          // Index keys follow the raw doubles, which aren't in date order before 12/31/1899, where the whole part
+          // counts days backwards but the fraction still counts the time forwards. Every negative key is still
+          // earlier than 12/31/1899, so drop the bounds that could skip one.
+          lower = lower.filter(|key| *key >= 1.0);
+          upper = upper.filter(|key| *key >= 0.0);
+        }
+        let lower = lower.and_then(|key| encode_key(column_type, key, KeyBound::Lower));
+        let upper = upper.and_then(|key| encode_key(column_type, key, KeyBound::Upper));
+        let bounds = lower.is_some() as usize + upper.is_some() as usize;
+        (bounds > 0).then_some((bounds, position, lower, upper))
+      })
+      // The first of the tightest wins.
+      .rev()
+      .max_by_key(|(bounds, ..)| *bounds)
+      .map(|(_, position, lower, upper)| IndexCursor::new(position, &self.indexes[position], &self.mdb, lower, upper))
+  }
+}
+
+/// Order two values, or None when either is null or they can't be compared. Text is compared ignoring case, and
+/// text literals are read as numbers or dates when compared with those.
+fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
+  match (a, b) {
+    (Value::Null, _) | (_, Value::Null) => None,
+    (Value::Text(a), Value::Text(b)) => Some(a.to_lowercase().cmp(&b.to_lowercase())),
+    (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+    (Value::Binary(a), Value::Binary(b)) => Some(a.cmp(b)),
+    (Value::Guid(a), Value::Guid(b)) => Some(a.cmp(b)),
+    (Value::Guid(guid), Value::Text(text)) => {
+      Some(format_guid(guid).trim_matches(['{', '}']).to_lowercase().cmp(&text.trim_matches(['{', '}']).to_lowercase()))
+    }
+    (Value::Text(_), Value::Guid(_)) => compare_values(b, a).map(Ordering::reverse),
+    (Value::DateTime(_) | Value::ExtendedDateTime(_), _) | (_, Value::DateTime(_) | Value::ExtendedDateTime(_)) => {
+      let to_datetime = |value: &Value| value.as_datetime().or_else(|| value.as_str().and_then(parse_date_literal));
+      Some(to_datetime(a)?.cmp(&to_datetime(b)?))
+    }
+    (Value::Currency(a), Value::Currency(b)) => Some(a.cmp(b)),
+    _ => {
+      if let (Some(a), Some(b)) = (a.as_i64(), b.as_i64()) {
+        return Some(a.cmp(&b));
+      }
+      let to_number = |value: &Value| match value {
+        Value::Text(text) => text.trim().parse::<f64>().ok(),
+        Value::Bool(value) => Some(if *value { -1.0 } else { 0.0 }),
+        value => value.as_f64(),
+      };
+      to_number(a)?.partial_cmp(&to_number(b)?)
+    }
+  }
+}
+
+#[derive(Clone, Debug)]
+enum LikeToken {
+  /// `*`
+  Any,
+  /// `?`
+  One,
+  /// `#`
+  Digit,
+  Char(char),
+  /// `[a-z]`, or `[!a-z]` when negated.
+  Set { negated: bool, ranges: Vec<(char, char)> },
+}
+
+impl LikeToken {
+  fn matches(&self, c: char) -> bool {
+    match self {
+      LikeToken::Any | LikeToken::One => true,
+      LikeToken::Digit => c.is_ascii_digit(),
+      LikeToken::Char(expected) => lowercase(c) == *expected,
+      LikeToken::Set { negated, ranges } => {
+        let c = lowercase(c);
+        ranges.iter().any(|(low, high)| (*low..=*high).contains(&c)) != *negated
+      }
+    }
+  }
+}
+
+fn lowercase(c: char) -> char {
+  c.to_lowercase().next().unwrap_or(c)
+}
+
+fn parse_like_pattern(pattern: &str) -> Vec<LikeToken> {
+  let chars: Vec<char> = pattern.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+  while i < chars.len() {
+    match chars[i] {
+      '*' => tokens.push(LikeToken::Any),
+      '?' => tokens.push(LikeToken::One),
+      '#' => tokens.push(LikeToken::Digit),
+      '[' if chars[i + 1..].contains(&']') => {
+        let end = chars[i + 1..].iter().position(|&c| c == ']').unwrap() + i + 1;
+        let mut set = &chars[i + 1..end];
+        let negated = set.first() == Some(&'!');
+        if negated {
+          set = &set[1..];
+        }
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        while j < set.len() {
+          if j + 2 < set.len() && set[j + 1] == '-' {
+            ranges.push((lowercase(set[j]), lowercase(set[j + 2])));
+            j += 3;
+          } else {
+            ranges.push((lowercase(set[j]), lowercase(set[j])));
+            j += 1;
+          }
+        }
+        tokens.push(LikeToken::Set { negated, ranges });
+        i = end;
+      }
+      c => tokens.push(LikeToken::Char(lowercase(c))),
+    }
+    i += 1;
+  }
+  tokens
+}
+
+/// Match text against a `LIKE` pattern, backtracking to the last `*` on a mismatch.
+fn like_matches(pattern: &[LikeToken], text: &str) -> bool {
+  let text: Vec<char> = text.chars().collect();
+  let (mut p, mut t) = (0, 0);
+  let mut star: Option<(usize, usize)> = None;
+  while t < text.len() {
+    match pattern.get(p) {
+      Some(LikeToken::Any) => {
+        star = Some((p, t));
+        p += 1;
+      }
+      Some(token) if token.matches(text[t]) => {
+        p += 1;
+        t += 1;
+      }
+      _ => match star {
+        Some((star_p, star_t)) => {
+          p = star_p + 1;
+          t = star_t + 1;
+          star = Some((star_p, star_t + 1));
+        }
+        None => return false,
+      },
+    }
+  }
+  pattern[p..].iter().all(|token| matches!(token, LikeToken::Any))
+}
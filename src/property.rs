@@ -0,0 +1,286 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::NaiveDateTime;
+
+use crate::catalog::{ObjectType, SystemObject};
+use crate::column::ColumnType;
+use crate::conversion::decode_mdb_string;
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+use crate::money::money_column_to_string;
+use crate::time::datetime_from_f64;
+use crate::utils::{get_u16, get_u32, get_u64};
+use crate::value::format_guid;
+
+// Chunk types of a property blob.
+const NAMES_CHUNK: u16 = 0x80;
+const OBJECT_PROPERTIES_CHUNK: u16 = 0x00;
+const COLUMN_PROPERTIES_CHUNK: u16 = 0x01;
+
+/// The name of the MSysObjects row holding the properties of the database itself.
+pub const DATABASE_PROPERTIES_OBJECT: &str = "MSysDb";
+
+/// The decoded value of a property, typed by the column type stored with it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+  Bool(bool),
+  Byte(u8),
+  Int(i16),
+  LongInt(i32),
+  /// Currency, already scaled to four decimal places.
+  Money(String),
+  Float(f32),
+  Double(f64),
+  DateTime(NaiveDateTime),
+  Text(String),
+  /// A 16 byte GUID, such as the GUID property of tables and columns.
+  Guid([u8; 16]),
+  /// Binary and OLE values, and values of types properties don't use.
+  Binary(Vec<u8>),
+}
+
+impl Display for PropertyValue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PropertyValue::Bool(value) => write!(f, "{}", if *value { "yes" } else { "no" }),
+      PropertyValue::Byte(value) => write!(f, "{}", value),
+      PropertyValue::Int(value) => write!(f, "{}", value),
+      PropertyValue::LongInt(value) => write!(f, "{}", value),
+      PropertyValue::Money(value) => write!(f, "{}", value),
+      PropertyValue::Float(value) => write!(f, "{}", value),
+      PropertyValue::Double(value) => write!(f, "{}", value),
+      PropertyValue::DateTime(value) => write!(f, "{}", value.format("%m/%d/%Y %H:%M:%S")),
+      PropertyValue::Text(value) => write!(f, "{}", value),
+      PropertyValue::Guid(value) => write!(f, "{}", format_guid(value)),
+      PropertyValue::Binary(value) => {
+        for byte in value {
+          write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+      }
+    }
+  }
+}
+
+/// A property of an object or column, as stored in the LvProp column of MSysObjects.
+#[derive(Clone, Debug)]
+pub struct Property {
+  pub name: String,
+  /// The column type of the value.
+  pub data_type: u8,
+  /// Whether the property was set through DDL rather than by Access itself.
+  pub ddl: bool,
+  pub value: PropertyValue,
+}
+
+impl Property {
+  /// The value of a text or memo property.
+  pub fn as_str(&self) -> Option<&str> {
+    match &self.value {
+      PropertyValue::Text(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  /// The value of a yes/no property. Numeric values are true when not zero.
+  pub fn to_bool(&self) -> bool {
+    match &self.value {
+      PropertyValue::Bool(value) => *value,
+      PropertyValue::Byte(value) => *value != 0,
+      PropertyValue::Int(value) => *value != 0,
+      PropertyValue::LongInt(value) => *value != 0,
+      _ => false,
+    }
+  }
+
+  /// The value of an integer property, whatever its width.
+  pub fn to_i64(&self) -> Option<i64> {
+    match &self.value {
+      PropertyValue::Bool(value) => Some(*value as i64),
+      PropertyValue::Byte(value) => Some(*value as i64),
+      PropertyValue::Int(value) => Some(*value as i64),
+      PropertyValue::LongInt(value) => Some(*value as i64),
+      _ => None,
+    }
+  }
+}
+
+/// The properties of the object itself, or of one of its columns.
+#[derive(Clone, Debug, Default)]
+pub struct PropertyBlock {
+  /// The column name, or empty for the properties of the object.
+  pub name: String,
+  pub properties: Vec<Property>,
+}
+
+impl PropertyBlock {
+  pub fn get(&self, name: &str) -> Option<&Property> {
+    self.properties.iter().find(|property| property.name.eq_ignore_ascii_case(name))
+  }
+
+  /// The Expression property of a calculated column, computing its value from the other columns of the row.
+  pub fn expression(&self) -> Option<&str> {
+    self.get("Expression").and_then(Property::as_str).filter(|expression| !expression.is_empty())
+  }
+}
+
+/// The properties of an object and of each of its columns.
+#[derive(Clone, Debug, Default)]
+pub struct ObjectProperties {
+  pub object: PropertyBlock,
+  pub columns: Vec<PropertyBlock>,
+}
+
+impl ObjectProperties {
+  /// The properties of a column, matched case-insensitively.
+  pub fn column(&self, name: &str) -> Option<&PropertyBlock> {
+    self.columns.iter().find(|block| block.name.eq_ignore_ascii_case(name))
+  }
+
+  /// The Caption property of a column, which datasheets show instead of its name. Empty captions are ignored.
+  pub fn caption(&self, column: &str) -> Option<&str> {
+    self.column(column)
+      .and_then(|block| block.get("Caption"))
+      .and_then(Property::as_str)
+      .filter(|caption| !caption.is_empty())
+  }
+
+  fn from_blocks(blocks: Vec<PropertyBlock>) -> ObjectProperties {
+    let mut properties = ObjectProperties::default();
+    for block in blocks {
+      if block.name.is_empty() {
+        properties.object.properties.extend(block.properties);
+      } else {
+        properties.columns.push(block);
+      }
+    }
+    properties
+  }
+}
+
+/// The properties of a system object and its columns. Objects without properties return empty blocks.
+pub fn read_properties(mdb: &Mdb, object: &SystemObject) -> Result<ObjectProperties, MdbError> {
+  match &object.lv_prop {
+    Some(data) if !data.is_empty() => Ok(ObjectProperties::from_blocks(parse_properties(mdb, data)?)),
+    _ => Ok(ObjectProperties::default()),
+  }
+}
+
+/// The properties of the database, such as AccessVersion and the startup options.
+pub fn read_database_properties(mdb: &Mdb, system_objects: &[SystemObject]) -> Result<PropertyBlock, MdbError> {
+  match system_objects.iter().find(|object| object.name == DATABASE_PROPERTIES_OBJECT) {
+    Some(object) => Ok(read_properties(mdb, object)?.object),
+    None => Ok(PropertyBlock::default()),
+  }
+}
+
+/// The properties of a table and its columns, or `None` if there is no such table.
+pub fn read_table_properties(mdb: &Mdb, system_objects: &[SystemObject], table_name: &str) -> Result<Option<ObjectProperties>, MdbError> {
+  match system_objects.iter().find(|object| object.object_type == ObjectType::Table && object.name == table_name) {
+    Some(object) => Ok(Some(read_properties(mdb, object)?)),
+    None => Ok(None),
+  }
+}
+
+/// Parse a property blob. The blob starts with `MR2` (or `KKD` for Jet 3) and is followed by chunks: one holding the
+/// property names, then one per object or column holding the values.
+pub fn parse_properties(mdb: &Mdb, data: &[u8]) -> Result<Vec<PropertyBlock>, MdbError> {
+  if data.len() < 4 || !(data.starts_with(b"MR2\0") || data.starts_with(b"KKD\0")) {
+    return Err(MdbError::InvalidProperties);
+  }
+
+  let mut names: Vec<String> = Vec::new();
+  let mut blocks: Vec<PropertyBlock> = Vec::new();
+
+  let mut pos = 4;
+  while pos + 6 <= data.len() {
+    let chunk_length = get_u32(data, pos) as usize;
+    let chunk_type = get_u16(data, pos + 4);
+    if chunk_length < 6 || pos + chunk_length > data.len() {
+      return Err(MdbError::InvalidProperties);
+    }
+    let chunk = &data[pos + 6..pos + chunk_length];
+
+    match chunk_type {
+      NAMES_CHUNK => names = parse_names(mdb, chunk)?,
+      OBJECT_PROPERTIES_CHUNK | COLUMN_PROPERTIES_CHUNK => blocks.push(parse_block(mdb, &names, chunk)?),
+      _ => {}
+    }
+
+    pos += chunk_length;
+  }
+
+  Ok(blocks)
+}
+
+fn parse_names(mdb: &Mdb, chunk: &[u8]) -> Result<Vec<String>, MdbError> {
+  let mut names: Vec<String> = Vec::new();
+  let mut pos = 0;
+  while pos + 2 <= chunk.len() {
+    let length = get_u16(chunk, pos) as usize;
+    pos += 2;
+    let name = chunk.get(pos..pos + length).ok_or(MdbError::InvalidProperties)?;
+    names.push(decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, name)?);
+    pos += length;
+  }
+  Ok(names)
+}
+
+fn parse_block(mdb: &Mdb, names: &[String], chunk: &[u8]) -> Result<PropertyBlock, MdbError> {
+  if chunk.len() < 6 {
+    return Err(MdbError::InvalidProperties);
+  }
+
+  // The block header holds its own length and the name of the column.
+  let header_length = get_u32(chunk, 0) as usize;
+  let name_length = get_u16(chunk, 4) as usize;
+  let name = chunk.get(6..6 + name_length).ok_or(MdbError::InvalidProperties)?;
+  let name = decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, name)?;
+
+  let mut properties: Vec<Property> = Vec::new();
+  let mut pos = header_length;
+  while pos + 8 <= chunk.len() {
+    let record_length = get_u16(chunk, pos) as usize;
+    let ddl = chunk[pos + 2] != 0;
+    let data_type = chunk[pos + 3];
+    let name_index = get_u16(chunk, pos + 4) as usize;
+    let value_length = get_u16(chunk, pos + 6) as usize;
+    if record_length < 8 {
+      return Err(MdbError::InvalidProperties);
+    }
+
+    let value = chunk.get(pos + 8..pos + 8 + value_length).ok_or(MdbError::InvalidProperties)?;
+    properties.push(Property {
+      name: names.get(name_index).cloned().ok_or(MdbError::InvalidProperties)?,
+      data_type,
+      ddl,
+      value: decode_value(mdb, data_type, value)?,
+    });
+
+    pos += record_length;
+  }
+
+  Ok(PropertyBlock { name, properties })
+}
+
+/// Decode a property value by its column type. Integers are sometimes stored wider than their type, so only the
+/// leading bytes are used.
+fn decode_value(mdb: &Mdb, data_type: u8, value: &[u8]) -> Result<PropertyValue, MdbError> {
+  let sized = |size: usize| value.len() >= size;
+  Ok(match ColumnType::try_from(data_type) {
+    Ok(ColumnType::Bool) => PropertyValue::Bool(value.iter().any(|&byte| byte != 0)),
+    Ok(ColumnType::Byte) if sized(1) => PropertyValue::Byte(value[0]),
+    Ok(ColumnType::Int) if sized(2) => PropertyValue::Int(get_u16(value, 0) as i16),
+    Ok(ColumnType::LongInt) if sized(4) => PropertyValue::LongInt(get_u32(value, 0) as i32),
+    Ok(ColumnType::Money) if sized(8) => PropertyValue::Money(money_column_to_string(value)),
+    Ok(ColumnType::Float) if sized(4) => PropertyValue::Float(f32::from_bits(get_u32(value, 0))),
+    Ok(ColumnType::Double) if sized(8) => PropertyValue::Double(f64::from_bits(get_u64(value, 0))),
+    Ok(ColumnType::Datetime) if sized(8) => match datetime_from_f64(f64::from_bits(get_u64(value, 0))) {
+      Some(datetime) => PropertyValue::DateTime(datetime),
+      None => PropertyValue::Binary(value.to_vec()),
+    },
+    Ok(ColumnType::Text | ColumnType::Memo) => PropertyValue::Text(decode_mdb_string(mdb.mdb_file.jet_version, mdb.encoding, value)?),
+    Ok(ColumnType::Binary | ColumnType::ReplicationId) if value.len() == 16 => PropertyValue::Guid(value.try_into().unwrap()),
+    _ => PropertyValue::Binary(value.to_vec()),
+  })
+}
@@ -0,0 +1,556 @@
+use crate::catalog::{load_table, read_binary, read_i16, read_i32, read_system_objects, read_text, read_u8, ObjectType};
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+use crate::sql::{tokenize, Token};
+
+// Values of the Attribute column of MSysQueries.
+const TYPE_ATTRIBUTE: u8 = 0;
+const PARAMETER_ATTRIBUTE: u8 = 2;
+const FLAG_ATTRIBUTE: u8 = 3;
+const REMOTE_DATABASE_ATTRIBUTE: u8 = 4;
+const TABLE_ATTRIBUTE: u8 = 5;
+const COLUMN_ATTRIBUTE: u8 = 6;
+const JOIN_ATTRIBUTE: u8 = 7;
+const WHERE_ATTRIBUTE: u8 = 8;
+const GROUP_BY_ATTRIBUTE: u8 = 9;
+const HAVING_ATTRIBUTE: u8 = 10;
+const ORDER_BY_ATTRIBUTE: u8 = 11;
+
+// Bits of the Flag column of the flag attribute row.
+const SELECT_STAR_FLAG: i16 = 0x01;
+const DISTINCT_FLAG: i16 = 0x02;
+const OWNER_ACCESS_FLAG: i16 = 0x04;
+const DISTINCT_ROW_FLAG: i16 = 0x08;
+const TOP_FLAG: i16 = 0x10;
+const PERCENT_FLAG: i16 = 0x20;
+/// Union queries reuse the distinct bit: set for UNION, clear for UNION ALL.
+const UNION_FLAG: i16 = 0x02;
+
+// Bits of the Flag column of column attribute rows.
+const CROSSTAB_PIVOT_FLAG: i16 = 0x01;
+const CROSSTAB_NORMAL_FLAG: i16 = 0x02;
+const APPEND_VALUE_FLAG: i16 = 0x8000_u16 as i16;
+
+// The two halves of a union query are stored as table rows with these aliases.
+const UNION_PART_1: &str = "X7YZ_____1";
+const UNION_PART_2: &str = "X7YZ_____2";
+
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum QueryType {
+  Select,
+  MakeTable,
+  Append,
+  Update,
+  Delete,
+  CrossTab,
+  DataDefinition,
+  PassThrough,
+  Union,
+  Unknown,
+}
+
+impl QueryType {
+  /// The query type is kept in the MSysObjects flags of the query.
+  pub fn from_object_flags(flags: u32) -> QueryType {
+    match flags & 0xF0 {
+      0x00 => QueryType::Select,
+      0x10 => QueryType::CrossTab,
+      0x20 => QueryType::Delete,
+      0x30 => QueryType::Update,
+      0x40 => QueryType::Append,
+      0x50 => QueryType::MakeTable,
+      0x60 => QueryType::DataDefinition,
+      0x70 => QueryType::PassThrough,
+      0x80 => QueryType::Union,
+      _ => QueryType::Unknown,
+    }
+  }
+}
+
+impl std::fmt::Display for QueryType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", match self {
+      QueryType::Select => "select",
+      QueryType::MakeTable => "make table",
+      QueryType::Append => "append",
+      QueryType::Update => "update",
+      QueryType::Delete => "delete",
+      QueryType::CrossTab => "crosstab",
+      QueryType::DataDefinition => "data definition",
+      QueryType::PassThrough => "pass-through",
+      QueryType::Union => "union",
+      QueryType::Unknown => "unknown",
+    })
+  }
+}
+
+/// A row of MSysQueries.
+#[derive(Clone, Debug)]
+pub struct QueryRow {
+  pub attribute: u8,
+  pub expression: Option<String>,
+  pub flag: Option<i16>,
+  pub extra: Option<i32>,
+  pub name1: Option<String>,
+  pub name2: Option<String>,
+  pub order: Vec<u8>,
+}
+
+/// A saved query and its MSysQueries rows.
+#[derive(Clone, Debug)]
+pub struct Query {
+  pub name: String,
+  pub object_id: i32,
+  /// The MSysObjects flags of the query.
+  pub flags: u32,
+  pub query_type: QueryType,
+  pub rows: Vec<QueryRow>,
+}
+
+/// Read every saved query in the database.
+pub fn read_queries(mdb: &mut Mdb) -> Result<Vec<Query>, MdbError> {
+  let system_objects = read_system_objects(mdb)?;
+
+  let mut queries: Vec<Query> = system_objects.iter()
+    .filter(|object| object.object_type == ObjectType::Query)
+    .map(|object| Query {
+      name: object.name.clone(),
+      object_id: object.id,
+      flags: object.flags,
+      query_type: QueryType::from_object_flags(object.flags),
+      rows: vec![],
+    })
+    .collect();
+
+  let mut table = match load_table(mdb, &system_objects, "MSysQueries")? {
+    Some(table) => table,
+    None => return Ok(queries),
+  };
+
+  let attribute_index = table.find_column_index("Attribute");
+  let expression_index = table.find_column_index("Expression");
+  let flag_index = table.find_column_index("Flag");
+  let extra_index = table.find_column_index("LvExtra");
+  let name1_index = table.find_column_index("Name1");
+  let name2_index = table.find_column_index("Name2");
+  let object_id_index = table.find_column_index("ObjectId");
+  let order_index = table.find_column_index("Order");
+
+  while table.next_row()? {
+    let object_id = read_i32(&table, object_id_index);
+    let query = match queries.iter_mut().find(|query| Some(query.object_id) == object_id) {
+      Some(query) => query,
+      None => continue,
+    };
+
+    query.rows.push(QueryRow {
+      attribute: read_u8(&table, attribute_index).unwrap_or(0),
+      expression: read_text(&table, expression_index)?,
+      flag: read_i16(&table, flag_index),
+      extra: read_i32(&table, extra_index),
+      name1: read_text(&table, name1_index)?,
+      name2: read_text(&table, name2_index)?,
+      order: read_binary(&table, order_index).unwrap_or_default(),
+    });
+  }
+
+  for query in queries.iter_mut() {
+    query.rows.sort_by(|a, b| a.attribute.cmp(&b.attribute).then_with(|| a.order.cmp(&b.order)));
+  }
+
+  Ok(queries)
+}
+
+/// Order queries so that each comes after the queries it reads from. Queries in a dependency cycle keep their
+/// original order at the end.
+pub fn dependency_order(queries: &[Query]) -> Vec<&Query> {
+  // Tokenizing rebuilds the SQL of a query, so look up the dependencies of each query only once.
+  let dependencies: Vec<Vec<usize>> = queries.iter().map(|query| query.dependency_indexes(queries)).collect();
+  let mut remaining: Vec<usize> = (0..queries.len()).collect();
+  let mut done = vec![false; queries.len()];
+  let mut ordered: Vec<&Query> = Vec::with_capacity(queries.len());
+
+  while !remaining.is_empty() {
+    let ready = remaining.iter().position(|&index| dependencies[index].iter().all(|&dependency| done[dependency]));
+    match ready {
+      Some(position) => {
+        let index = remaining.remove(position);
+        done[index] = true;
+        ordered.push(&queries[index]);
+      }
+      None => {
+        ordered.extend(remaining.drain(..).map(|index| &queries[index]));
+      }
+    }
+  }
+
+  ordered
+}
+
+/// Bracket a name if Access would need it to be bracketed.
+pub fn quote_identifier(name: &str) -> String {
+  let is_plain = !name.is_empty()
+    && !name.starts_with(|c: char| c.is_ascii_digit())
+    && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+  if is_plain || name.starts_with('[') {
+    name.to_string()
+  } else {
+    format!("[{}]", name)
+  }
+}
+
+/// A table or join in the FROM clause.
+enum FromItem {
+  Table {
+    key: String,
+    sql: String,
+  },
+  Join {
+    left: Box<FromItem>,
+    right: Box<FromItem>,
+    join_type: &'static str,
+    conditions: Vec<String>,
+  },
+}
+
+impl FromItem {
+  fn contains(&self, key: &str) -> bool {
+    match self {
+      FromItem::Table { key: table_key, .. } => table_key.eq_ignore_ascii_case(key),
+      FromItem::Join { left, right, .. } => left.contains(key) || right.contains(key),
+    }
+  }
+
+  fn to_sql(&self, nested: bool) -> String {
+    match self {
+      FromItem::Table { sql, .. } => sql.clone(),
+      FromItem::Join { left, right, join_type, conditions } => {
+        let sql = format!("{} {} {} ON {}", left.to_sql(true), join_type, right.to_sql(true), conditions.join(" AND "));
+        if nested { format!("({})", sql) } else { sql }
+      }
+    }
+  }
+}
+
+impl Query {
+  fn rows_with(&self, attribute: u8) -> impl Iterator<Item = &QueryRow> {
+    self.rows.iter().filter(move |row| row.attribute == attribute)
+  }
+
+  fn row_with(&self, attribute: u8) -> Option<&QueryRow> {
+    self.rows_with(attribute).next()
+  }
+
+  fn has_flag(&self, flag: i16) -> bool {
+    self.row_with(FLAG_ATTRIBUTE).and_then(|row| row.flag).unwrap_or(0) & flag != 0
+  }
+
+  /// Hidden queries are those Access creates for the record sources of forms and reports.
+  pub fn is_hidden(&self) -> bool {
+    self.name.starts_with('~')
+  }
+
+  /// The names of the tables and queries the query reads from.
+  pub fn source_names(&self) -> Vec<String> {
+    self.rows_with(TABLE_ATTRIBUTE)
+      .filter(|row| !is_union_part(row))
+      .filter_map(|row| row.name1.clone())
+      .collect()
+  }
+
+  /// The other queries this query reads from, found by name anywhere in its SQL.
+  pub fn dependencies<'a>(&self, queries: &'a [Query]) -> Vec<&'a Query> {
+    self.dependency_indexes(queries).into_iter().map(|index| &queries[index]).collect()
+  }
+
+  fn dependency_indexes(&self, queries: &[Query]) -> Vec<usize> {
+    let tokens = tokenize(&self.to_sql()).unwrap_or_default();
+    queries.iter()
+      .enumerate()
+      .filter(|(_, query)| query.name != self.name)
+      .filter(|(_, query)| tokens.iter().any(|token| match token {
+        Token::Identifier(name) | Token::QuotedIdentifier(name) => name.eq_ignore_ascii_case(&query.name),
+        _ => false,
+      }))
+      .map(|(index, _)| index)
+      .collect()
+  }
+
+  /// Rebuild the Access SQL of the query.
+  pub fn to_sql(&self) -> String {
+    let mut sql = String::new();
+
+    let parameters: Vec<String> = self.rows_with(PARAMETER_ATTRIBUTE).map(format_parameter).collect();
+    if !parameters.is_empty() {
+      sql.push_str(&format!("PARAMETERS {};\n", parameters.join(", ")));
+    }
+
+    match self.query_type {
+      QueryType::Select | QueryType::Unknown => self.push_select(&mut sql, self.column_rows().collect(), true),
+      QueryType::MakeTable => self.push_make_table(&mut sql),
+      QueryType::Append => self.push_append(&mut sql),
+      QueryType::Update => self.push_update(&mut sql),
+      QueryType::Delete => self.push_delete(&mut sql),
+      QueryType::CrossTab => self.push_crosstab(&mut sql),
+      QueryType::Union => self.push_union(&mut sql),
+      QueryType::DataDefinition | QueryType::PassThrough => {
+        let text = self.row_with(TYPE_ATTRIBUTE).and_then(|row| row.expression.clone()).unwrap_or_default();
+        sql.push_str(text.trim().trim_end_matches(';'));
+      }
+    }
+
+    if self.has_flag(OWNER_ACCESS_FLAG) {
+      sql.push_str("\nWITH OWNERACCESS OPTION");
+    }
+    sql.push(';');
+    sql
+  }
+
+  fn column_rows(&self) -> impl Iterator<Item = &QueryRow> {
+    self.rows_with(COLUMN_ATTRIBUTE)
+  }
+
+  fn select_type(&self) -> String {
+    let mut select_type = String::new();
+    if self.has_flag(DISTINCT_FLAG) {
+      select_type.push_str("DISTINCT ");
+    } else if self.has_flag(DISTINCT_ROW_FLAG) {
+      select_type.push_str("DISTINCTROW ");
+    }
+    if self.has_flag(TOP_FLAG) {
+      let top = self.row_with(FLAG_ATTRIBUTE).and_then(|row| row.name1.clone()).unwrap_or_default();
+      let percent = if self.has_flag(PERCENT_FLAG) { " PERCENT" } else { "" };
+      select_type.push_str(&format!("TOP {}{} ", top, percent));
+    }
+    select_type
+  }
+
+  fn select_columns(&self, rows: &[&QueryRow], with_aliases: bool) -> String {
+    let mut columns: Vec<String> = rows.iter().map(|row| {
+      let expression = row.expression.clone().unwrap_or_default();
+      match &row.name1 {
+        Some(alias) if with_aliases => format!("{} AS {}", expression, quote_identifier(alias)),
+        _ => expression,
+      }
+    }).collect();
+
+    if self.has_flag(SELECT_STAR_FLAG) {
+      columns.push("*".to_string());
+    }
+    columns.join(", ")
+  }
+
+  fn push_select(&self, sql: &mut String, columns: Vec<&QueryRow>, with_aliases: bool) {
+    sql.push_str(&format!("SELECT {}{}", self.select_type(), self.select_columns(&columns, with_aliases)));
+    self.push_from(sql);
+    self.push_clauses(sql);
+  }
+
+  fn push_make_table(&self, sql: &mut String) {
+    let type_row = self.row_with(TYPE_ATTRIBUTE);
+    let target = type_row.and_then(|row| row.name1.clone()).unwrap_or_default();
+    sql.push_str(&format!("SELECT {}{}", self.select_type(), self.select_columns(&self.column_rows().collect::<Vec<_>>(), true)));
+    sql.push_str(&format!(" INTO {}", quote_identifier(&target)));
+    if let Some(remote) = type_row.and_then(|row| row.name2.clone()) {
+      sql.push_str(&format!(" IN '{}'", remote));
+    }
+    self.push_from(sql);
+    self.push_clauses(sql);
+  }
+
+  fn push_append(&self, sql: &mut String) {
+    let target = self.row_with(TYPE_ATTRIBUTE).and_then(|row| row.name1.clone()).unwrap_or_default();
+    let value_rows: Vec<&QueryRow> = self.column_rows().filter(|row| row.flag.unwrap_or(0) & APPEND_VALUE_FLAG != 0).collect();
+    let column_rows: Vec<&QueryRow> = self.column_rows().filter(|row| row.flag.unwrap_or(0) & APPEND_VALUE_FLAG == 0).collect();
+
+    sql.push_str(&format!("INSERT INTO {}", quote_identifier(&target)));
+    let target_columns: Vec<String> = column_rows.iter().filter_map(|row| row.name1.as_deref().map(quote_identifier)).collect();
+    if !target_columns.is_empty() && target_columns.len() == column_rows.len() {
+      sql.push_str(&format!(" ( {} )", target_columns.join(", ")));
+    }
+    sql.push('\n');
+
+    if !value_rows.is_empty() {
+      let values: Vec<String> = value_rows.iter().map(|row| row.expression.clone().unwrap_or_default()).collect();
+      sql.push_str(&format!("VALUES ({})", values.join(", ")));
+    } else {
+      self.push_select(sql, column_rows, false);
+    }
+  }
+
+  fn push_update(&self, sql: &mut String) {
+    sql.push_str(&format!("UPDATE {}", self.table_sources()));
+    let assignments: Vec<String> = self.column_rows().map(|row| {
+      format!("{} = {}", quote_identifier(row.name2.as_deref().or(row.name1.as_deref()).unwrap_or_default()), row.expression.clone().unwrap_or_default())
+    }).collect();
+    sql.push_str(&format!("\nSET {}", assignments.join(", ")));
+    self.push_where(sql);
+  }
+
+  fn push_delete(&self, sql: &mut String) {
+    sql.push_str(&format!("DELETE {}", self.select_columns(&self.column_rows().collect::<Vec<_>>(), false)));
+    self.push_from(sql);
+    self.push_where(sql);
+  }
+
+  fn push_crosstab(&self, sql: &mut String) {
+    let columns: Vec<&QueryRow> = self.column_rows().collect();
+    let normal_flags = CROSSTAB_NORMAL_FLAG | CROSSTAB_PIVOT_FLAG;
+    let transform = columns.iter().find(|row| row.flag.unwrap_or(0) & normal_flags == 0);
+    let pivot = columns.iter().find(|row| row.flag.unwrap_or(0) & CROSSTAB_PIVOT_FLAG != 0);
+    let normal: Vec<&QueryRow> = columns.iter().filter(|row| row.flag.unwrap_or(0) & CROSSTAB_NORMAL_FLAG != 0).copied().collect();
+
+    if let Some(transform) = transform {
+      sql.push_str(&format!("TRANSFORM {}\n", transform.expression.clone().unwrap_or_default()));
+    }
+    self.push_select(sql, normal, true);
+    if let Some(pivot) = pivot {
+      sql.push_str(&format!("\nPIVOT {}", pivot.expression.clone().unwrap_or_default()));
+    }
+  }
+
+  fn push_union(&self, sql: &mut String) {
+    let part = |alias: &str| {
+      self.rows_with(TABLE_ATTRIBUTE)
+        .find(|row| row.name2.as_deref() == Some(alias))
+        .and_then(|row| row.expression.clone())
+        .map(|expression| expression.trim().trim_end_matches(';').to_string())
+        .unwrap_or_default()
+    };
+
+    sql.push_str(&part(UNION_PART_1));
+    sql.push_str(if self.has_flag(UNION_FLAG) { "\nUNION " } else { "\nUNION ALL " });
+    sql.push_str(&part(UNION_PART_2));
+    self.push_order_by(sql);
+  }
+
+  fn table_sources(&self) -> String {
+    let mut items: Vec<FromItem> = self.rows_with(TABLE_ATTRIBUTE)
+      .filter(|row| !is_union_part(row))
+      .map(|row| {
+        let name = row.name1.clone().unwrap_or_default();
+        match &row.name2 {
+          Some(alias) => FromItem::Table { key: alias.clone(), sql: format!("{} AS {}", quote_identifier(&name), quote_identifier(alias)) },
+          None => FromItem::Table { sql: quote_identifier(&name), key: name },
+        }
+      })
+      .collect();
+
+    for join in self.rows_with(JOIN_ATTRIBUTE) {
+      let left_key = join.name1.clone().unwrap_or_default();
+      let right_key = join.name2.clone().unwrap_or_default();
+      let condition = join.expression.clone().unwrap_or_default();
+      let join_type = match join.flag.unwrap_or(1) {
+        2 => "LEFT JOIN",
+        3 => "RIGHT JOIN",
+        _ => "INNER JOIN",
+      };
+
+      let left = items.iter().position(|item| item.contains(&left_key));
+      let right = items.iter().position(|item| item.contains(&right_key));
+      match (left, right) {
+        (Some(left), Some(right)) if left == right => {
+          if let FromItem::Join { conditions, .. } = &mut items[left] {
+            conditions.push(condition);
+          }
+        }
+        (Some(left), Some(right)) => {
+          let (first, second) = if left < right { (left, right) } else { (right, left) };
+          let second_item = items.remove(second);
+          let first_item = items.remove(first);
+          let (left_item, right_item) = if left < right { (first_item, second_item) } else { (second_item, first_item) };
+          items.insert(first, FromItem::Join {
+            left: Box::new(left_item),
+            right: Box::new(right_item),
+            join_type,
+            conditions: vec![condition],
+          });
+        }
+        _ => {}
+      }
+    }
+
+    let mut from: String = items.iter().map(|item| item.to_sql(false)).collect::<Vec<String>>().join(", ");
+    if let Some(remote) = self.row_with(REMOTE_DATABASE_ATTRIBUTE) {
+      if let Some(path) = &remote.name1 {
+        from.push_str(&format!(" IN '{}'", path));
+      }
+      if let Some(database_type) = &remote.expression {
+        from.push_str(&format!(" [{}]", database_type));
+      }
+    }
+    from
+  }
+
+  fn push_from(&self, sql: &mut String) {
+    let from = self.table_sources();
+    if !from.is_empty() {
+      sql.push_str(&format!("\nFROM {}", from));
+    }
+  }
+
+  fn push_where(&self, sql: &mut String) {
+    if let Some(expression) = self.row_with(WHERE_ATTRIBUTE).and_then(|row| row.expression.as_ref()) {
+      sql.push_str(&format!("\nWHERE {}", expression));
+    }
+  }
+
+  fn push_order_by(&self, sql: &mut String) {
+    let orderings: Vec<String> = self.rows_with(ORDER_BY_ATTRIBUTE).map(|row| {
+      let expression = row.expression.clone().unwrap_or_default();
+      match row.name1.as_deref() {
+        Some(direction) if direction.eq_ignore_ascii_case("D") => format!("{} DESC", expression),
+        _ => expression,
+      }
+    }).collect();
+    if !orderings.is_empty() {
+      sql.push_str(&format!("\nORDER BY {}", orderings.join(", ")));
+    }
+  }
+
+  /// WHERE, GROUP BY, HAVING and ORDER BY.
+  fn push_clauses(&self, sql: &mut String) {
+    self.push_where(sql);
+
+    let group_by: Vec<String> = self.rows_with(GROUP_BY_ATTRIBUTE).filter_map(|row| row.expression.clone()).collect();
+    if !group_by.is_empty() {
+      sql.push_str(&format!("\nGROUP BY {}", group_by.join(", ")));
+    }
+
+    if let Some(expression) = self.row_with(HAVING_ATTRIBUTE).and_then(|row| row.expression.as_ref()) {
+      sql.push_str(&format!("\nHAVING {}", expression));
+    }
+
+    self.push_order_by(sql);
+  }
+}
+
+fn is_union_part(row: &QueryRow) -> bool {
+  matches!(row.name2.as_deref(), Some(UNION_PART_1) | Some(UNION_PART_2))
+}
+
+fn format_parameter(row: &QueryRow) -> String {
+  let name = quote_identifier(row.name1.as_deref().unwrap_or_default());
+  let parameter_type = match row.flag.unwrap_or(0) {
+    1 => "Bit",
+    2 => "Byte",
+    3 => "Short",
+    4 => "Long",
+    5 => "Currency",
+    6 => "IEEESingle",
+    7 => "IEEEDouble",
+    8 => "DateTime",
+    9 => "Binary",
+    10 => "Text",
+    11 => "LongBinary",
+    12 => "Memo",
+    15 => "Guid",
+    16 => "Decimal",
+    _ => "Value",
+  };
+
+  match row.extra {
+    Some(size) if size > 0 && parameter_type == "Text" => format!("{} {} ( {} )", name, parameter_type, size),
+    _ => format!("{} {}", name, parameter_type),
+  }
+}
@@ -0,0 +1,95 @@
+use crate::catalog::{load_table, read_i32, read_system_objects, read_text};
+use crate::error::MdbError;
+use crate::mdbfile::Mdb;
+
+// Bits of the grbit column of MSysRelationships.
+pub const ONE_TO_ONE_FLAG: u32 = 0x00000001;
+pub const NO_INTEGRITY_FLAG: u32 = 0x00000002;
+pub const CASCADE_UPDATES_FLAG: u32 = 0x00000100;
+pub const CASCADE_DELETES_FLAG: u32 = 0x00001000;
+pub const CASCADE_NULL_FLAG: u32 = 0x00002000;
+pub const LEFT_OUTER_JOIN_FLAG: u32 = 0x01000000;
+pub const RIGHT_OUTER_JOIN_FLAG: u32 = 0x02000000;
+
+/// A relationship between two tables, read from MSysRelationships.
+#[derive(Clone, Debug)]
+pub struct Relationship {
+  pub name: String,
+  /// The table holding the foreign key.
+  pub from_table: String,
+  /// The columns of the foreign key, in order.
+  pub from_columns: Vec<String>,
+  /// The table referenced by the foreign key.
+  pub to_table: String,
+  /// The referenced columns, in the same order as `from_columns`.
+  pub to_columns: Vec<String>,
+  pub flags: u32,
+}
+
+impl Relationship {
+  pub fn is_one_to_one(&self) -> bool {
+    self.flags & ONE_TO_ONE_FLAG != 0
+  }
+
+  /// Relationships that don't enforce integrity only record the default join for the query designer.
+  pub fn enforces_integrity(&self) -> bool {
+    self.flags & NO_INTEGRITY_FLAG == 0
+  }
+
+  pub fn cascades_updates(&self) -> bool {
+    self.flags & CASCADE_UPDATES_FLAG != 0
+  }
+
+  pub fn cascades_deletes(&self) -> bool {
+    self.flags & CASCADE_DELETES_FLAG != 0
+  }
+
+  /// Deleting the referenced row sets the foreign key to null.
+  pub fn cascades_null(&self) -> bool {
+    self.flags & CASCADE_NULL_FLAG != 0
+  }
+}
+
+/// Read every relationship in the database.
+pub fn read_relationships(mdb: &mut Mdb) -> Result<Vec<Relationship>, MdbError> {
+  let system_objects = read_system_objects(mdb)?;
+  let mut table = match load_table(mdb, &system_objects, "MSysRelationships")? {
+    Some(table) => table,
+    None => return Ok(vec![]),
+  };
+
+  let column_index = table.find_column_index("icolumn");
+  let flags_index = table.find_column_index("grbit");
+  let from_column_index = table.find_column_index("szColumn");
+  let from_table_index = table.find_column_index("szObject");
+  let to_column_index = table.find_column_index("szReferencedColumn");
+  let to_table_index = table.find_column_index("szReferencedObject");
+  let name_index = table.find_column_index("szRelationship");
+
+  // Each row is one column pair of a relationship.
+  let mut rows: Vec<(i32, Relationship)> = Vec::new();
+  while table.next_row()? {
+    rows.push((read_i32(&table, column_index).unwrap_or(0), Relationship {
+      name: read_text(&table, name_index)?.unwrap_or_default(),
+      from_table: read_text(&table, from_table_index)?.unwrap_or_default(),
+      from_columns: vec![read_text(&table, from_column_index)?.unwrap_or_default()],
+      to_table: read_text(&table, to_table_index)?.unwrap_or_default(),
+      to_columns: vec![read_text(&table, to_column_index)?.unwrap_or_default()],
+      flags: read_i32(&table, flags_index).unwrap_or(0) as u32,
+    }));
+  }
+  rows.sort_by_key(|(column, _)| *column);
+
+  let mut relationships: Vec<Relationship> = Vec::new();
+  for (_, row) in rows {
+    match relationships.iter_mut().find(|relationship| relationship.name == row.name) {
+      Some(relationship) => {
+        relationship.from_columns.extend(row.from_columns);
+        relationship.to_columns.extend(row.to_columns);
+      }
+      None => relationships.push(row),
+    }
+  }
+
+  Ok(relationships)
+}
@@ -0,0 +1,129 @@
+use std::ops::Index;
+use std::sync::Arc;
+
+use crate::error::MdbError;
+use crate::table::Table;
+use crate::value::Value;
+
+/// An owned row of a table, holding the typed value of each column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+  names: Arc<[String]>,
+  values: Vec<Value>,
+}
+
+impl Row {
+  pub fn new(names: Arc<[String]>, values: Vec<Value>) -> Self {
+    Row { names, values }
+  }
+
+  pub fn len(&self) -> usize {
+    self.values.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.values.is_empty()
+  }
+
+  /// The column names, in the same order as the values.
+  pub fn names(&self) -> &[String] {
+    &self.names
+  }
+
+  pub fn values(&self) -> &[Value] {
+    &self.values
+  }
+
+  pub fn into_values(self) -> Vec<Value> {
+    self.values
+  }
+
+  /// The value of the column at `index`.
+  pub fn get(&self, index: usize) -> Option<&Value> {
+    self.values.get(index)
+  }
+
+  /// The value of the column called `name`. Names are matched exactly first, then case-insensitively as Access does.
+  pub fn get_by_name(&self, name: &str) -> Option<&Value> {
+    let index = self.names.iter().position(|column| column == name)
+      .or_else(|| self.names.iter().position(|column| column.eq_ignore_ascii_case(name)))?;
+    self.values.get(index)
+  }
+
+  /// Pairs of column names and values.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+    self.names.iter().map(String::as_str).zip(self.values.iter())
+  }
+}
+
+impl Index<usize> for Row {
+  type Output = Value;
+
+  fn index(&self, index: usize) -> &Value {
+    &self.values[index]
+  }
+}
+
+impl Index<&str> for Row {
+  type Output = Value;
+
+  fn index(&self, name: &str) -> &Value {
+    self.get_by_name(name).unwrap_or_else(|| panic!("No column named {}", name))
+  }
+}
+
+/// An iterator over the rows of a table, created by `Table::rows`.
+pub struct Rows<'a> {
+  table: &'a mut Table,
+  names: Arc<[String]>,
+  /// An error from reading the columns, returned as the first item.
+  error: Option<MdbError>,
+  done: bool,
+}
+
+impl Iterator for Rows<'_> {
+  type Item = Result<Row, MdbError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+    if let Some(err) = self.error.take() {
+      self.done = true;
+      return Some(Err(err));
+    }
+
+    match self.table.next_row() {
+      Ok(true) => {
+        let values = self.table.selected_columns().map(|column| column.scan_value()).collect::<Result<Vec<_>, _>>();
+        match values {
+          Ok(values) => Some(Ok(Row::new(self.names.clone(), values))),
+          Err(err) => {
+            self.done = true;
+            Some(Err(err))
+          }
+        }
+      }
+      Ok(false) => {
+        self.done = true;
+        None
+      }
+      Err(err) => {
+        self.done = true;
+        Some(Err(err))
+      }
+    }
+  }
+}
+
+impl Table {
+  /// Scan the table from the first row. The columns are read first if they haven't been, and each call starts a new
+  /// scan, so the same table can be read several times. Rows only hold the columns chosen with `Table::select`.
+  pub fn rows(&mut self) -> Rows<'_> {
+    let error = if self.columns.is_empty() { self.read_columns().err() } else { None };
+    self.rewind();
+
+    let names: Arc<[String]> = self.selected_columns().map(|column| column.name.clone()).collect();
+    Rows { table: self, names, error, done: false }
+  }
+}
@@ -0,0 +1,804 @@
+use std::ops::Range;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::backend::{Backend, SqlDialect};
+use crate::error::MdbError;
+
+/// A token of Access SQL.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Token {
+  Whitespace(String),
+  /// A bare word: a keyword, function or unbracketed name.
+  Identifier(String),
+  /// A `[bracketed]` name, without the brackets.
+  QuotedIdentifier(String),
+  /// A string literal, without quotes and with doubled quotes unescaped.
+  String(String),
+  Number(String),
+  /// A `#date#` literal, without the hashes.
+  Date(String),
+  Symbol(String),
+}
+
+impl Token {
+  fn is_whitespace(&self) -> bool {
+    matches!(self, Token::Whitespace(_))
+  }
+
+  fn is_symbol(&self, symbol: &str) -> bool {
+    matches!(self, Token::Symbol(s) if s == symbol)
+  }
+
+  /// Whether the token is the bare word `word`, ignoring case.
+  pub fn is_word(&self, word: &str) -> bool {
+    matches!(self, Token::Identifier(s) if s.eq_ignore_ascii_case(word))
+  }
+}
+
+impl std::fmt::Display for Token {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Token::Whitespace(s) | Token::Identifier(s) | Token::Number(s) | Token::Symbol(s) => write!(f, "{}", s),
+      Token::QuotedIdentifier(s) => write!(f, "[{}]", s),
+      Token::String(s) => write!(f, "\"{}\"", s.replace('"', "\"\"")),
+      Token::Date(s) => write!(f, "#{}#", s),
+    }
+  }
+}
+
+/// Split Access SQL into tokens.
+pub fn tokenize(sql: &str) -> Result<Vec<Token>, MdbError> {
+  let chars: Vec<char> = sql.chars().collect();
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut i = 0;
+
+  while i < chars.len() {
+    let c = chars[i];
+    let start = i;
+
+    if c.is_whitespace() {
+      while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+      }
+      tokens.push(Token::Whitespace(chars[start..i].iter().collect()));
+    } else if c == '[' {
+      let end = chars[i..].iter().position(|&c| c == ']').ok_or(MdbError::InvalidSql)? + i;
+      tokens.push(Token::QuotedIdentifier(chars[i + 1..end].iter().collect()));
+      i = end + 1;
+    } else if c == '#' {
+      let end = chars[i + 1..].iter().position(|&c| c == '#').ok_or(MdbError::InvalidSql)? + i + 1;
+      tokens.push(Token::Date(chars[i + 1..end].iter().collect()));
+      i = end + 1;
+    } else if c == '"' || c == '\'' {
+      let mut value = String::new();
+      i += 1;
+      loop {
+        match chars.get(i) {
+          None => return Err(MdbError::InvalidSql),
+          Some(&next) if next == c => {
+            if chars.get(i + 1) == Some(&c) {
+              value.push(c);
+              i += 2;
+            } else {
+              i += 1;
+              break;
+            }
+          }
+          Some(&next) => {
+            value.push(next);
+            i += 1;
+          }
+        }
+      }
+      tokens.push(Token::String(value));
+    } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())) {
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+      if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+        let mut exponent = i + 1;
+        if exponent < chars.len() && (chars[exponent] == '+' || chars[exponent] == '-') {
+          exponent += 1;
+        }
+        if exponent < chars.len() && chars[exponent].is_ascii_digit() {
+          i = exponent;
+          while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+          }
+        }
+      }
+      tokens.push(Token::Number(chars[start..i].iter().collect()));
+    } else if c.is_alphabetic() || c == '_' {
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+      }
+      tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+    } else {
+      let pair: String = chars[i..chars.len().min(i + 2)].iter().collect();
+      if pair == "<=" || pair == ">=" || pair == "<>" {
+        tokens.push(Token::Symbol(pair));
+        i += 2;
+      } else {
+        tokens.push(Token::Symbol(c.to_string()));
+        i += 1;
+      }
+    }
+  }
+
+  Ok(tokens)
+}
+
+/// Turn tokens back into Access SQL.
+pub fn untokenize(tokens: &[Token]) -> String {
+  tokens.iter().map(|token| token.to_string()).collect()
+}
+
+const DATE_TIME_FORMATS: &[&str] = &[
+  "%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M", "%m/%d/%Y %I:%M:%S %p", "%m/%d/%Y %I:%M %p",
+  "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%d %I:%M:%S %p", "%Y-%m-%d %I:%M %p",
+];
+const DATE_FORMATS: &[&str] = &["%m/%d/%Y", "%Y-%m-%d", "%m/%d/%y"];
+const TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// Parse the contents of a `#date#` literal. Dates alone are at midnight, and times alone are on 12/30/1899, the
+/// zero of Access dates.
+pub fn parse_date_literal(date: &str) -> Option<NaiveDateTime> {
+  let date = date.trim();
+  DATE_TIME_FORMATS.iter().find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+    .or_else(|| DATE_FORMATS.iter().find_map(|format| NaiveDate::parse_from_str(date, format).ok()).and_then(|date| date.and_hms_opt(0, 0, 0)))
+    .or_else(|| TIME_FORMATS.iter().find_map(|format| NaiveTime::parse_from_str(date, format).ok())
+      .and_then(|time| NaiveDate::from_ymd_opt(1899, 12, 30).map(|date| date.and_time(time))))
+}
+
+/// Words that are passed through rather than quoted as names.
+const KEYWORDS: &[&str] = &[
+  "ALL", "AND", "AS", "ASC", "BETWEEN", "BY", "DELETE", "DESC", "DISTINCT", "EXISTS", "FROM", "FULL", "GROUP",
+  "HAVING", "IN", "INNER", "INSERT", "INTO", "IS", "JOIN", "LEFT", "LIKE", "NOT", "NULL", "ON", "OR", "ORDER",
+  "OUTER", "PERCENT", "RIGHT", "SELECT", "SET", "TOP", "UNION", "UPDATE", "VALUES", "WHERE", "XOR",
+];
+
+/// SQL translated into a backend dialect.
+pub struct Translation {
+  pub sql: String,
+  /// Descriptions of the parts that could not be translated. These are copied through unchanged.
+  pub warnings: Vec<String>,
+}
+
+/// Translate an Access SQL statement into the dialect of `backend`.
+pub fn translate(sql: &str, backend: &Backend) -> Result<Translation, MdbError> {
+  translate_in_schema(sql, backend, None)
+}
+
+/// Translate an Access SQL statement into the dialect of `backend`, qualifying the tables it reads from with `schema`.
+pub fn translate_in_schema(sql: &str, backend: &Backend, schema: Option<&str>) -> Result<Translation, MdbError> {
+  if backend.sql_dialect == SqlDialect::Access {
+    return Ok(Translation { sql: sql.to_string(), warnings: vec![] });
+  }
+
+  let schema = schema.filter(|schema| !schema.is_empty());
+  let mut translator = Translator { backend, schema, warnings: vec![], in_from: false, expect_table: false };
+  let sql = translator.translate_statement(tokenize(sql)?);
+  Ok(Translation { sql, warnings: translator.warnings })
+}
+
+struct Translator<'a> {
+  backend: &'a Backend<'a>,
+  schema: Option<&'a str>,
+  warnings: Vec<String>,
+  /// Whether the tokens being translated are in a FROM clause.
+  in_from: bool,
+  /// Whether the next name is a table in a FROM clause.
+  expect_table: bool,
+}
+
+/// The index of the next token that is not whitespace.
+fn next_significant(tokens: &[Token], from: usize) -> Option<usize> {
+  (from..tokens.len()).find(|&i| !tokens[i].is_whitespace())
+}
+
+/// The index of the `)` matching the `(` at `open`.
+fn matching_paren(tokens: &[Token], open: usize) -> Option<usize> {
+  let mut depth = 0;
+  for (i, token) in tokens.iter().enumerate().skip(open) {
+    if token.is_symbol("(") {
+      depth += 1;
+    } else if token.is_symbol(")") {
+      depth -= 1;
+      if depth == 0 {
+        return Some(i);
+      }
+    }
+  }
+  None
+}
+
+/// The index of the first `word` outside of parentheses.
+fn find_top_level_word(tokens: &[Token], word: &str) -> Option<usize> {
+  let mut depth = 0;
+  for (i, token) in tokens.iter().enumerate() {
+    if token.is_symbol("(") {
+      depth += 1;
+    } else if token.is_symbol(")") {
+      depth -= 1;
+    } else if depth == 0 && token.is_word(word) {
+      return Some(i);
+    }
+  }
+  None
+}
+
+fn trim_tokens(tokens: &[Token]) -> &[Token] {
+  let start = tokens.iter().position(|token| !token.is_whitespace()).unwrap_or(tokens.len());
+  let end = tokens.iter().rposition(|token| !token.is_whitespace()).map_or(start, |end| end + 1);
+  &tokens[start..end]
+}
+
+fn string_literal(tokens: &[Token]) -> Option<&str> {
+  match trim_tokens(tokens) {
+    [Token::String(s)] => Some(s),
+    _ => None,
+  }
+}
+
+fn quote_string(value: &str) -> String {
+  format!("'{}'", value.replace('\'', "''"))
+}
+
+impl<'a> Translator<'a> {
+  fn is_postgres(&self) -> bool {
+    self.backend.sql_dialect == SqlDialect::Postgres
+  }
+
+  fn warn(&mut self, warning: String) {
+    if !self.warnings.contains(&warning) {
+      self.warnings.push(warning);
+    }
+  }
+
+  /// Translate a whole statement, handling the clauses that need to move or be dropped.
+  fn translate_statement(&mut self, mut tokens: Vec<Token>) -> String {
+    while tokens.last().is_some_and(|token| token.is_whitespace() || token.is_symbol(";")) {
+      tokens.pop();
+    }
+
+    if next_significant(&tokens, 0).is_some_and(|i| tokens[i].is_word("PARAMETERS")) {
+      self.warn("PARAMETERS are not supported and were removed; parameter references are left as names".to_string());
+      let end = tokens.iter().position(|token| token.is_symbol(";")).map_or(tokens.len(), |i| i + 1);
+      tokens.drain(..end);
+    }
+
+    if let Some(with) = find_top_level_word(&tokens, "WITH")
+      && next_significant(&tokens, with + 1).is_some_and(|i| tokens[i].is_word("OWNERACCESS")) {
+      self.warn("WITH OWNERACCESS OPTION was removed".to_string());
+      tokens.truncate(with);
+    }
+
+    let mut limit: Option<String> = None;
+    if let Some(top) = find_top_level_word(&tokens, "TOP") {
+      let count = next_significant(&tokens, top + 1);
+      let percent = count.and_then(|count| next_significant(&tokens, count + 1)).filter(|&i| tokens[i].is_word("PERCENT"));
+      if self.is_postgres() && let Some(count) = count {
+        let end = percent.unwrap_or(count) + 1;
+        if percent.is_some() {
+          self.warn(format!("TOP {} PERCENT has no equivalent and was removed", tokens[count]));
+        } else {
+          limit = Some(tokens[count].to_string());
+        }
+        tokens.drain(top..next_significant(&tokens, end).unwrap_or(end));
+      }
+    }
+
+    if self.backend.sql_dialect == SqlDialect::TSql && find_top_level_word(&tokens, "TOP").is_none()
+      && let Some(order) = find_top_level_word(&tokens, "ORDER") {
+      self.warn("ORDER BY is not allowed in a view and was removed".to_string());
+      tokens.truncate(order);
+      while tokens.last().is_some_and(|token| token.is_whitespace()) {
+        tokens.pop();
+      }
+    }
+
+    let mut sql = self.translate_tokens(&tokens);
+    if let Some(limit) = limit {
+      sql.push_str(&format!("\nLIMIT {}", limit));
+    }
+    sql
+  }
+
+  fn translate_tokens(&mut self, tokens: &[Token]) -> String {
+    let concatenations = find_concatenations(tokens);
+    if concatenations.is_empty() {
+      return self.translate_operands(tokens);
+    }
+
+    // Access & treats Null as an empty string and converts numbers to text, like CONCAT.
+    let mut sql = String::new();
+    let mut start = 0;
+    for (operands, end) in concatenations {
+      sql.push_str(&self.translate_operands(&tokens[start..operands[0].start]));
+      let operands: Vec<String> = operands.iter().map(|operand| self.translate_tokens(trim_tokens(&tokens[operand.clone()]))).collect();
+      sql.push_str(&format!("CONCAT({})", operands.join(", ")));
+      start = end;
+    }
+    sql.push_str(&self.translate_operands(&tokens[start..]));
+    sql
+  }
+
+  /// Translate tokens that have no concatenation outside of parentheses.
+  fn translate_operands(&mut self, tokens: &[Token]) -> String {
+    let mut sql = String::new();
+    let mut in_like = false;
+    let mut i = 0;
+
+    while i < tokens.len() {
+      let token = &tokens[i];
+      i += 1;
+
+      match token {
+        Token::Whitespace(s) => {
+          sql.push_str(s);
+          continue;
+        }
+        Token::QuotedIdentifier(name) => sql.push_str(&self.translate_name(name)),
+        Token::Identifier(word) => {
+          let upper = word.to_uppercase();
+          let call = next_significant(tokens, i)
+            .filter(|&open| tokens[open].is_symbol("("))
+            .filter(|_| !KEYWORDS.contains(&upper.as_str()) || upper == "LEFT" || upper == "RIGHT");
+          if let Some(close) = call.and_then(|open| matching_paren(tokens, open)) {
+            let arguments = split_arguments(&tokens[call.unwrap() + 1..close]);
+            let original = untokenize(&tokens[i - 1..close + 1]);
+            let in_from = std::mem::replace(&mut self.in_from, false);
+            sql.push_str(&self.translate_function(word, &arguments, &original));
+            self.in_from = in_from;
+            i = close + 1;
+          } else {
+            match upper.as_str() {
+              "TRUE" | "YES" => sql.push_str(if self.is_postgres() { "TRUE" } else { "1" }),
+              "FALSE" | "NO" => sql.push_str(if self.is_postgres() { "FALSE" } else { "0" }),
+              "MOD" => sql.push('%'),
+              "DISTINCTROW" => {
+                i = next_significant(tokens, i).unwrap_or(tokens.len());
+                continue;
+              }
+              "LIKE" => {
+                sql.push_str(word);
+                in_like = true;
+                continue;
+              }
+              _ if KEYWORDS.contains(&upper.as_str()) => {
+                self.clause_keyword(&upper);
+                sql.push_str(word);
+              }
+              _ => sql.push_str(&self.translate_name(word)),
+            }
+          }
+        }
+        Token::String(value) => {
+          if in_like {
+            let pattern = self.translate_like_pattern(value);
+            sql.push_str(&quote_string(&pattern));
+          } else {
+            sql.push_str(&quote_string(value));
+          }
+        }
+        Token::Number(number) => sql.push_str(number),
+        Token::Date(date) => sql.push_str(&self.translate_date(date)),
+        Token::Symbol(symbol) => match symbol.as_str() {
+          "(" if let Some(close) = matching_paren(tokens, i - 1) => {
+            let in_from = self.in_from;
+            sql.push('(');
+            sql.push_str(&self.translate_tokens(&tokens[i..close]));
+            sql.push(')');
+            self.in_from = in_from;
+            self.expect_table = false;
+            i = close + 1;
+          }
+          "," if self.in_from => {
+            self.expect_table = true;
+            sql.push(',');
+          }
+          "\\" => {
+            self.warn("integer division (\\) has no equivalent and was copied unchanged".to_string());
+            sql.push('\\');
+          }
+          "!" => {
+            self.warn("form and control references (!) cannot be translated".to_string());
+            sql.push('!');
+          }
+          "^" if !self.is_postgres() => {
+            self.warn("exponentiation (^) has no equivalent and was copied unchanged".to_string());
+            sql.push('^');
+          }
+          _ => sql.push_str(symbol),
+        },
+      }
+      in_like = false;
+    }
+
+    sql
+  }
+
+  /// Follow the clauses of a statement to find the tables of its FROM clause.
+  fn clause_keyword(&mut self, keyword: &str) {
+    match keyword {
+      "FROM" => {
+        self.in_from = true;
+        self.expect_table = true;
+      }
+      "JOIN" => self.expect_table = self.in_from,
+      "ON" => self.expect_table = false,
+      "SELECT" | "WHERE" | "GROUP" | "HAVING" | "ORDER" | "UNION" => {
+        self.in_from = false;
+        self.expect_table = false;
+      }
+      _ => {}
+    }
+  }
+
+  /// Quote a name, qualifying it with the schema when it is a table in a FROM clause.
+  fn translate_name(&mut self, name: &str) -> String {
+    let quoted = (self.backend.quote_name)(name);
+    if !std::mem::replace(&mut self.expect_table, false) {
+      return quoted;
+    }
+    match self.schema {
+      Some(schema) => format!("{}.{}", (self.backend.quote_name)(schema), quoted),
+      None => quoted,
+    }
+  }
+
+  fn translate_like_pattern(&mut self, pattern: &str) -> String {
+    let mut translated = String::new();
+    for c in pattern.chars() {
+      match c {
+        '*' => translated.push('%'),
+        '?' => translated.push('_'),
+        '%' | '_' if self.is_postgres() => translated.push_str(&format!("\\{}", c)),
+        '%' | '_' => translated.push_str(&format!("[{}]", c)),
+        '#' if !self.is_postgres() => translated.push_str("[0-9]"),
+        '#' | '[' if self.is_postgres() => {
+          self.warn(format!("LIKE pattern \"{}\" uses wildcards that have no equivalent", pattern));
+          translated.push(c);
+        }
+        _ => translated.push(c),
+      }
+    }
+    translated
+  }
+
+  fn translate_date(&mut self, date: &str) -> String {
+    let date = date.trim();
+    let postgres = self.is_postgres();
+
+    for format in DATE_TIME_FORMATS {
+      if let Ok(datetime) = NaiveDateTime::parse_from_str(date, format) {
+        return if postgres {
+          format!("TIMESTAMP '{}'", datetime.format("%Y-%m-%d %H:%M:%S"))
+        } else {
+          format!("CAST('{}' AS DATETIME2)", datetime.format("%Y-%m-%dT%H:%M:%S"))
+        };
+      }
+    }
+
+    for format in DATE_FORMATS {
+      if let Ok(date) = NaiveDate::parse_from_str(date, format) {
+        return if postgres {
+          format!("DATE '{}'", date.format("%Y-%m-%d"))
+        } else {
+          format!("CAST('{}' AS DATE)", date.format("%Y-%m-%d"))
+        };
+      }
+    }
+
+    for format in TIME_FORMATS {
+      if let Ok(time) = NaiveTime::parse_from_str(date, format) {
+        return if postgres {
+          format!("TIME '{}'", time.format("%H:%M:%S"))
+        } else {
+          format!("CAST('{}' AS TIME)", time.format("%H:%M:%S"))
+        };
+      }
+    }
+
+    self.warn(format!("date literal #{}# could not be parsed", date));
+    quote_string(date)
+  }
+
+  fn translate_function(&mut self, name: &str, arguments: &[Vec<Token>], original: &str) -> String {
+    let args: Vec<String> = arguments.iter().map(|argument| self.translate_tokens(trim_tokens(argument))).collect();
+    let postgres = self.is_postgres();
+    let backend = self.backend;
+    let cast = |value: &str, type_name: &str| format!("CAST({} AS {})", value, type_name);
+
+    match (name.to_lowercase().as_str(), args.len()) {
+      ("iif", 3) => format!("CASE WHEN {} THEN {} ELSE {} END", args[0], args[1], args[2]),
+      // Without a default, Nz returns an empty string or zero depending on the type of its value, which isn't known.
+      ("nz", 1) => {
+        self.warn(format!("{} was translated with an empty string default; give Nz a default for other types", original));
+        format!("COALESCE({}, '')", args[0])
+      }
+      ("nz", 2) => format!("COALESCE({}, {})", args[0], args[1]),
+      ("isnull", 1) => format!("({} IS NULL)", args[0]),
+      ("left", 2) | ("right", 2) | ("replace", 3) | ("trim", 1) | ("ltrim", 1) | ("rtrim", 1) | ("abs", 1)
+      | ("round", 1) | ("round", 2) | ("sum", 1) | ("avg", 1) | ("count", 1) | ("min", 1) | ("max", 1) => {
+        format!("{}({})", name.to_uppercase(), args.join(", "))
+      }
+      ("mid", 2) if postgres => format!("SUBSTRING({} FROM {})", args[0], args[1]),
+      ("mid", 2) => format!("SUBSTRING({}, {}, LEN({}))", args[0], args[1], args[0]),
+      ("mid", 3) if postgres => format!("SUBSTRING({} FROM {} FOR {})", args[0], args[1], args[2]),
+      ("mid", 3) => format!("SUBSTRING({}, {}, {})", args[0], args[1], args[2]),
+      ("len", 1) => format!("{}({})", if postgres { "LENGTH" } else { "LEN" }, args[0]),
+      ("ucase", 1) => format!("UPPER({})", args[0]),
+      ("lcase", 1) => format!("LOWER({})", args[0]),
+      ("instr", 2) if postgres => format!("POSITION({} IN {})", args[1], args[0]),
+      ("instr", 2) => format!("CHARINDEX({}, {})", args[1], args[0]),
+      ("space", 1) if postgres => format!("REPEAT(' ', {})", args[0]),
+      ("space", 1) => format!("SPACE({})", args[0]),
+      ("chr", 1) => format!("{}({})", if postgres { "CHR" } else { "CHAR" }, args[0]),
+      ("asc", 1) => format!("ASCII({})", args[0]),
+      ("int", 1) => format!("FLOOR({})", args[0]),
+      ("fix", 1) if postgres => format!("TRUNC({})", args[0]),
+      ("fix", 1) => format!("ROUND({}, 0, 1)", args[0]),
+      ("sgn", 1) => format!("SIGN({})", args[0]),
+      ("sqr", 1) => format!("SQRT({})", args[0]),
+      ("stdev", 1) => format!("{}({})", if postgres { "STDDEV_SAMP" } else { "STDEV" }, args[0]),
+      ("var", 1) => format!("{}({})", if postgres { "VAR_SAMP" } else { "VAR" }, args[0]),
+      ("now", 0) => "CURRENT_TIMESTAMP".to_string(),
+      ("date", 0) if postgres => "CURRENT_DATE".to_string(),
+      ("date", 0) => "CAST(GETDATE() AS DATE)".to_string(),
+      ("year", 1) | ("month", 1) | ("day", 1) if !postgres => format!("{}({})", name.to_uppercase(), args[0]),
+      ("year", 1) | ("month", 1) | ("day", 1) | ("hour", 1) | ("minute", 1) | ("second", 1) if postgres => {
+        format!("EXTRACT({} FROM {})", name.to_uppercase(), args[0])
+      }
+      ("hour", 1) | ("minute", 1) | ("second", 1) => format!("DATEPART({}, {})", name.to_lowercase(), args[0]),
+      ("cstr", 1) => cast(&args[0], backend.mdb_memo.name),
+      ("cbool", 1) => cast(&args[0], backend.mdb_bool.name),
+      ("cbyte", 1) => cast(&args[0], backend.mdb_byte.name),
+      ("cint", 1) => cast(&args[0], backend.mdb_int.name),
+      ("clng", 1) => cast(&args[0], backend.mdb_longint.name),
+      ("csng", 1) => cast(&args[0], backend.mdb_float.name),
+      ("cdbl", 1) => cast(&args[0], backend.mdb_double.name),
+      ("ccur", 1) => cast(&args[0], backend.mdb_money.name),
+      ("cdate", 1) => cast(&args[0], backend.mdb_datetime.name),
+      ("dateadd", 3) => match string_literal(&arguments[0]).and_then(|interval| self.date_part(interval)) {
+        Some((unit, _)) if !postgres => format!("DATEADD({}, {}, {})", unit, args[1], args[2]),
+        Some((_, interval)) => format!("({} + ({}) * INTERVAL '{}')", args[2], args[1], interval),
+        None => self.untranslated(original),
+      },
+      ("datediff", 3) => match string_literal(&arguments[0]).and_then(|interval| self.date_part(interval)) {
+        Some((unit, _)) if !postgres => format!("DATEDIFF({}, {}, {})", unit, args[1], args[2]),
+        Some(("day", _)) => format!("(CAST({} AS DATE) - CAST({} AS DATE))", args[2], args[1]),
+        Some(("year", _)) => format!("(EXTRACT(YEAR FROM {}) - EXTRACT(YEAR FROM {}))", args[2], args[1]),
+        Some(("month", _)) => format!(
+          "((EXTRACT(YEAR FROM {b}) - EXTRACT(YEAR FROM {a})) * 12 + EXTRACT(MONTH FROM {b}) - EXTRACT(MONTH FROM {a}))",
+          a = args[1], b = args[2]),
+        _ => self.untranslated(original),
+      },
+      ("datepart", 2) => match string_literal(&arguments[0]).and_then(|interval| self.date_part(interval)) {
+        Some((unit, _)) if !postgres => format!("DATEPART({}, {})", unit, args[1]),
+        Some(("weekday", _)) => format!("(EXTRACT(DOW FROM {}) + 1)", args[1]),
+        Some(("dayofyear", _)) => format!("EXTRACT(DOY FROM {})", args[1]),
+        Some((unit, _)) => format!("EXTRACT({} FROM {})", unit.to_uppercase(), args[1]),
+        None => self.untranslated(original),
+      },
+      ("format", 2) => match string_literal(&arguments[1]) {
+        Some(format) => match self.translate_format(&args[0], format) {
+          Some(sql) => sql,
+          None => self.untranslated(original),
+        },
+        None => self.untranslated(original),
+      },
+      _ => self.untranslated(original),
+    }
+  }
+
+  fn untranslated(&mut self, original: &str) -> String {
+    self.warn(format!("{} has no translation and was copied unchanged", original));
+    original.to_string()
+  }
+
+  /// The backend date part name and Postgres interval of an Access interval string.
+  fn date_part(&self, interval: &str) -> Option<(&'static str, &'static str)> {
+    Some(match interval.to_lowercase().as_str() {
+      "yyyy" => ("year", "1 year"),
+      "q" => ("quarter", "3 months"),
+      "m" => ("month", "1 month"),
+      "y" => ("dayofyear", "1 day"),
+      "d" => ("day", "1 day"),
+      "w" => ("weekday", "1 day"),
+      "ww" => ("week", "1 week"),
+      "h" => ("hour", "1 hour"),
+      "n" => ("minute", "1 minute"),
+      "s" => ("second", "1 second"),
+      _ => return None,
+    })
+  }
+
+  /// Translate `Format(value, format)` for a literal format string.
+  fn translate_format(&mut self, value: &str, format: &str) -> Option<String> {
+    let postgres = self.is_postgres();
+    let format = match format.to_lowercase().as_str() {
+      "general number" => return Some(format!("CAST({} AS {})", value, self.backend.mdb_memo.name)),
+      "short date" => "mm/dd/yyyy",
+      "medium date" => "dd-mmm-yy",
+      "long date" => "dddd, mmmm d, yyyy",
+      "short time" => "hh:nn",
+      "medium time" => "hh:nn AM/PM",
+      "long time" => "h:nn:ss AM/PM",
+      "fixed" => "0.00",
+      "standard" => "#,##0.00",
+      "currency" => "$#,##0.00",
+      "percent" => "0.00%",
+      _ => format,
+    };
+
+    if format.chars().all(|c| "0#,.%$ ".contains(c)) {
+      return Some(if postgres {
+        let pattern: String = format.chars().filter(|&c| c != '%' && c != '$').map(|c| if c == '#' { '9' } else { c }).collect();
+        let prefix = if format.contains('$') { "'$' || " } else { "" };
+        if format.contains('%') {
+          format!("{}TO_CHAR(({}) * 100, 'FM{}') || '%'", prefix, value, pattern)
+        } else {
+          format!("{}TO_CHAR({}, 'FM{}')", prefix, value, pattern)
+        }
+      } else {
+        format!("FORMAT({}, {})", value, quote_string(format))
+      });
+    }
+
+    let pattern = translate_date_format(format, postgres)?;
+    Some(if postgres {
+      format!("TO_CHAR({}, {})", value, quote_string(&pattern))
+    } else {
+      format!("FORMAT({}, {})", value, quote_string(&pattern))
+    })
+  }
+}
+
+/// Translate an Access date format into a Postgres `TO_CHAR` or .NET `FORMAT` pattern.
+fn translate_date_format(format: &str, postgres: bool) -> Option<String> {
+  let twelve_hour = format.to_lowercase().contains("am/pm");
+  let lower = format.to_lowercase();
+  let mut pattern = String::new();
+  let mut after_hour = false;
+  let mut i = 0;
+
+  while i < lower.len() {
+    let rest = &lower[i..];
+    let part = ["am/pm", "yyyy", "yy", "mmmm", "mmm", "mm", "m", "dddd", "ddd", "dd", "d", "hh", "h", "nn", "n", "ss", "s"]
+      .into_iter()
+      .find(|part| rest.starts_with(part));
+
+    let part = match part {
+      Some(part) => part,
+      None => {
+        let c = rest.chars().next()?;
+        if c.is_alphanumeric() {
+          return None;
+        }
+        pattern.push(c);
+        i += c.len_utf8();
+        continue;
+      }
+    };
+    i += part.len();
+
+    // Access reads m and mm straight after an hour as minutes.
+    let part = match part {
+      "mm" if after_hour => "nn",
+      "m" if after_hour => "n",
+      _ => part,
+    };
+    after_hour = part.starts_with('h');
+
+    pattern.push_str(match (part, postgres) {
+      ("am/pm", true) => "AM",
+      ("am/pm", false) => "tt",
+      ("yyyy", true) => "YYYY",
+      ("yy", true) => "YY",
+      ("mmmm", true) => "FMMonth",
+      ("mmm", true) => "Mon",
+      ("mm", true) => "MM",
+      ("m", true) => "FMMM",
+      ("dddd", true) => "FMDay",
+      ("ddd", true) => "Dy",
+      ("dd", true) => "DD",
+      ("d", true) => "FMDD",
+      ("hh", true) => if twelve_hour { "HH12" } else { "HH24" },
+      ("h", true) => if twelve_hour { "FMHH12" } else { "FMHH24" },
+      ("nn", true) => "MI",
+      ("n", true) => "FMMI",
+      ("ss", true) => "SS",
+      ("s", true) => "FMSS",
+      ("mmmm", false) => "MMMM",
+      ("mmm", false) => "MMM",
+      ("mm", false) => "MM",
+      ("m", false) => "M",
+      ("hh", false) => if twelve_hour { "hh" } else { "HH" },
+      ("h", false) => if twelve_hour { "h" } else { "H" },
+      ("nn", false) => "mm",
+      ("n", false) => "m",
+      (part, _) => part,
+    });
+  }
+
+  Some(pattern)
+}
+
+/// Symbols that end the operands of a `&` concatenation, which binds more loosely than arithmetic. Keywords end them too.
+const CONCATENATION_BOUNDARIES: &[&str] = &[",", ";", "=", "<", ">", "<>", "<=", ">="];
+
+fn is_concatenation_boundary(token: &Token) -> bool {
+  match token {
+    Token::Symbol(symbol) => CONCATENATION_BOUNDARIES.contains(&symbol.as_str()),
+    Token::Identifier(word) => {
+      let upper = word.to_uppercase();
+      (KEYWORDS.contains(&upper.as_str()) && !matches!(upper.as_str(), "LEFT" | "RIGHT" | "NULL")) || upper == "DISTINCTROW"
+    }
+    _ => false,
+  }
+}
+
+/// The top level `a & b & ...` expressions in `tokens`, as the ranges of their operands and the index after the last
+/// operand.
+fn find_concatenations(tokens: &[Token]) -> Vec<(Vec<Range<usize>>, usize)> {
+  let mut concatenations = Vec::new();
+  let mut depth = 0;
+  let mut start = 0;
+  let mut ampersands: Vec<usize> = Vec::new();
+
+  let mut end_expression = |start: usize, end: usize, ampersands: &mut Vec<usize>| {
+    if ampersands.is_empty() {
+      return;
+    }
+    let first = next_significant(tokens, start).unwrap_or(start);
+    let last = (start..end).rev().find(|&i| !tokens[i].is_whitespace()).map_or(end, |i| i + 1);
+    let mut operands = Vec::new();
+    let mut operand_start = first;
+    for &ampersand in ampersands.iter() {
+      operands.push(operand_start..ampersand);
+      operand_start = ampersand + 1;
+    }
+    operands.push(operand_start..last);
+    concatenations.push((operands, last));
+    ampersands.clear();
+  };
+
+  for (i, token) in tokens.iter().enumerate() {
+    if token.is_symbol("(") {
+      depth += 1;
+    } else if token.is_symbol(")") {
+      depth -= 1;
+    } else if depth == 0 && token.is_symbol("&") {
+      ampersands.push(i);
+    } else if depth == 0 && is_concatenation_boundary(token) {
+      end_expression(start, i, &mut ampersands);
+      start = i + 1;
+    }
+  }
+  end_expression(start, tokens.len(), &mut ampersands);
+  concatenations
+}
+
+/// Split the tokens between the parentheses of a call at top level commas.
+fn split_arguments(tokens: &[Token]) -> Vec<Vec<Token>> {
+  if trim_tokens(tokens).is_empty() {
+    return vec![];
+  }
+
+  let mut arguments = vec![vec![]];
+  let mut depth = 0;
+  for token in tokens {
+    if token.is_symbol("(") {
+      depth += 1;
+    } else if token.is_symbol(")") {
+      depth -= 1;
+    } else if depth == 0 && token.is_symbol(",") {
+      arguments.push(vec![]);
+      continue;
+    }
+    arguments.last_mut().unwrap().push(token.clone());
+  }
+  arguments
+}
@@ -1,151 +1,339 @@
-use crate::catalog::{CatalogEntry, TableCatalogEntry};
-use crate::column::Column;
-use crate::data::{mdb_find_page_row_packed};
-use crate::mdbfile::{Mdb, MdbFormatVersion};
-use crate::column::ColumnType;
-use crate::conversion::decode_mdb_string;
-use crate::error::MdbError;
-use crate::map::{UsageMap};
-use crate::utils::get_u16;
-
-pub struct Table {
-  pub name: String,
-  pub row_count: u32,
-  pub variable_column_count: u16,
-  pub column_count: u16,
-  pub first_data_page: u16,
-  pub columns: Vec<Column>,
-  real_index_count: u32,
-  pub current_page_number: u32,
-  pub current_physical_page_number: u16,
-  pub current_row: u16,
-  pub is_temporary_table: bool,
-  pub(crate) strategy: TableStrategy,
-  pub mdb: Mdb,
-
-  pub(crate) first_table_definition_page: u32,
-  pub usage_map: UsageMap,
-}
-
-#[repr(u8)]
-#[derive(PartialEq)]
-pub enum TableStrategy {
-  TableScan = 0,
-  LeafScan = 1,
-  IndexScan = 2,
-}
-
-impl Table {
-  pub fn find_column_index(&self, name: &str) -> Option<usize> {
-    self.columns.iter().position(|col| { col.name.eq(name) })
-  }
-
-  pub fn read_columns(&mut self) -> Result<(), MdbError> {
-    self.mdb.read_page(self.first_table_definition_page)?;
-
-    let mut cur_pos: u16 = self.mdb.format.tab_cols_start_offset as u16 + (self.real_index_count as u16 * self.mdb.format.tab_ridx_entry_size as u16);
-
-    let mut column_buffer: Vec<u8> = vec![0; self.mdb.format.tab_col_entry_size as usize];
-
-    let len = column_buffer.len();
-
-    for _i in 0..self.column_count {
-      self.columns.push(Column::new(self.mdb.encoding));
-    }
-
-    // Column Attributes
-    for column in self.columns.iter_mut() {
-      self.mdb.read_page_if_n(Some(&mut column_buffer), &mut cur_pos, len as u16)?;
-
-      column.column_type = column_buffer[0].try_into().expect("Invalid column type.");
-      column.number = column_buffer[self.mdb.format.column_number_offset];
-      column.row_column_number = get_u16(&column_buffer, self.mdb.format.table_row_column_number_offset);
-
-      match column.column_type {
-        ColumnType::Numeric | ColumnType::Money | ColumnType::Float | ColumnType::Double => {
-          column.scale = column_buffer[self.mdb.format.column_scale_offset];
-          column.precision = column_buffer[self.mdb.format.column_precision_offset];
-        }
-        _ => {}
-      }
-
-      column.is_fixed = column_buffer[self.mdb.format.col_flags_offset] & 0x01 != 0;
-      column.is_long_auto = column_buffer[self.mdb.format.col_flags_offset] & 0x04 == 0;
-      column.is_uuid_auto = column_buffer[self.mdb.format.col_flags_offset] & 0x40 == 0;
-      column.is_hyperlink = column_buffer[self.mdb.format.col_flags_offset] & 0x80 != 0;
-
-      column.fixed_offset = get_u16(&column_buffer, self.mdb.format.table_column_offset_fixed);
-      column.var_col_num = get_u16(&column_buffer, self.mdb.format.tab_col_offset_var);
-
-      column.size = if column.column_type != ColumnType::Bool {get_u16(&column_buffer, self.mdb.format.column_size_offset) } else { 0 };
-    }
-
-    // Column names
-    for column in self.columns.iter_mut() {
-      let name_size: usize;
-
-      if self.mdb.mdb_file.jet_version == MdbFormatVersion::JET3 {
-        name_size = self.mdb.read_page_if_8(&mut cur_pos)? as usize;
-      } else {
-        name_size = self.mdb.read_page_if_16(&mut cur_pos)? as usize;
-      }
-      let mut column_name_buffer: Vec<u8> = vec![];
-      column_name_buffer.resize(name_size as usize, 0);
-      self.mdb.read_page_if_n(Some(&mut column_name_buffer), &mut cur_pos, name_size as u16)?;
-
-      column.name = decode_mdb_string(self.mdb.mdb_file.jet_version, self.mdb.encoding, &column_name_buffer)?;
-    }
-
-    Ok(())
-  }
-
-  /// Load a table from a catalog entry.
-  pub fn from_catalog_entry(entry: CatalogEntry, mdb: &Mdb) -> Result<Table, MdbError> {
-    let mut mdb: Mdb = mdb.clone();
-    let entry: TableCatalogEntry = match entry {
-      CatalogEntry::Table(entry) => entry,
-    };
-
-    mdb.read_page(entry.page)?;
-
-    if mdb.get_u8(0) != 2 {
-      eprintln!("First byte is not equal to 2.");
-      return Err(MdbError::InvalidTableDefinition);
-    }
-
-    let page_row = mdb.get_u32(mdb.format.tab_usage_map_offset);
-    let real_index_count: u32 = mdb.get_u32(mdb.format.real_index_count_offset);
-    let variable_column_count = mdb.get_u16(mdb.format.table_column_count_offset - 2);
-    let column_count = mdb.get_u16(mdb.format.table_column_count_offset);
-
-    // Temporary mdb for getting usage map.
-    let mut usage_map_mdb = mdb.clone();
-
-    let usage_map_row = mdb_find_page_row_packed(&mut usage_map_mdb, page_row)?;
-
-    let usage_map_buffer = Vec::from(&usage_map_mdb.page_buffer[usage_map_row.start as usize..usage_map_row.start as usize + usage_map_row.length as usize]);
-    let usage_map = UsageMap::from_raw(&mut usage_map_mdb, &usage_map_buffer)?;
-
-    let first_data_page = mdb.get_u16(mdb.format.table_first_data_page_offset);
-
-    let table = Table {
-      name: entry.name,
-      row_count: mdb.get_u32(mdb.format.row_count_offset),
-      variable_column_count,
-      column_count,
-      first_data_page,
-      columns: vec![],
-      real_index_count,
-      current_page_number: 0,
-      current_physical_page_number: 0,
-      current_row: 0,
-      is_temporary_table: false,
-      strategy: TableStrategy::TableScan,
-      mdb,
-      first_table_definition_page: entry.page,
-      usage_map
-    };
-
-    Ok(table)
-  }
+use crate::catalog::CatalogEntry;
+use crate::column::{Column, ColumnFlags};
+use crate::data::{mdb_find_page_row_packed};
+use crate::mdbfile::{Mdb, MdbFormatVersion};
+use crate::column::ColumnType;
+use crate::conversion::decode_mdb_string;
+use crate::error::MdbError;
+use crate::index::{Index, IndexColumn, IndexCursor, IndexType};
+use crate::map::{UsageMap};
+use crate::predicate::Filter;
+use crate::property::ObjectProperties;
+use crate::utils::{get_u16, get_u32};
+
+/// The most columns a single index can have.
+const MAX_INDEX_COLUMNS: usize = 10;
+
+/// A real index as stored in the table definition, before it is matched to its logical indexes.
+struct RealIndex {
+  /// Column numbers and whether each is sorted ascending.
+  key_columns: Vec<(u16, bool)>,
+  first_page: u32,
+  flags: u8,
+}
+
+#[derive(Clone)]
+pub struct Table {
+  pub name: String,
+  pub row_count: u32,
+  pub variable_column_count: u16,
+  pub column_count: u16,
+  pub first_data_page: u16,
+  pub columns: Vec<Column>,
+  pub indexes: Vec<Index>,
+  real_index_count: u32,
+  index_count: u32,
+  pub current_page_number: u32,
+  pub current_physical_page_number: u16,
+  pub current_row: u16,
+  pub is_temporary_table: bool,
+  pub(crate) strategy: TableStrategy,
+  pub mdb: Mdb,
+
+  pub(crate) first_table_definition_page: u32,
+  pub usage_map: UsageMap,
+  /// The indexes of the selected columns in the order they were selected, or None when every column is.
+  selection: Option<Vec<usize>>,
+  /// Only rows matching the filter are returned. See `Table::filter`.
+  pub(crate) filter: Option<Filter>,
+  /// The index walked instead of the data pages when the strategy is an index scan.
+  pub(crate) index_cursor: Option<IndexCursor>,
+}
+
+#[repr(u8)]
+#[derive(PartialEq, Copy, Clone)]
+pub enum TableStrategy {
+  TableScan = 0,
+  LeafScan = 1,
+  IndexScan = 2,
+}
+
+impl Table {
+  pub fn find_column_index(&self, name: &str) -> Option<usize> {
+    self.columns.iter().position(|col| { col.name.eq(name) })
+  }
+
+  /// Name the current row by the values of its primary key joined with `-`, or by `number` when the table has no
+  /// primary key, as the files extracted from a row are named.
+  pub fn row_key(&self, number: usize) -> String {
+    let Some(primary_key) = self.indexes.iter().find(|index| index.is_primary_key()) else {
+      return number.to_string();
+    };
+    primary_key.columns.iter()
+      .map(|column| self.find_column_index(&column.name).map(|index| self.columns[index].value().to_string()).unwrap_or_default())
+      .collect::<Vec<_>>()
+      .join("-")
+  }
+
+  /// Columns are required when their Required property is set, or when they are part of an index that doesn't allow
+  /// nulls, such as the primary key. The nullable flag is set on nearly every column, so it isn't used.
+  pub fn is_required(&self, column: &Column, properties: &ObjectProperties) -> bool {
+    let required_property = properties.column(&column.name)
+      .and_then(|block| block.get("Required"))
+      .is_some_and(|property| property.to_bool());
+    let in_required_index = self.indexes.iter()
+      .filter(|index| index.is_primary_key() || index.is_required())
+      .any(|index| index.columns.iter().any(|index_column| index_column.name == column.name));
+    required_property || in_required_index
+  }
+
+  /// Find a column by name, exactly first, then case-insensitively as Access does.
+  pub(crate) fn resolve_column(&self, name: &str) -> Option<usize> {
+    self.find_column_index(name)
+      .or_else(|| self.columns.iter().position(|col| col.name.eq_ignore_ascii_case(name)))
+  }
+
+  /// Decode the selected columns and the columns the filter reads.
+  pub(crate) fn update_decoded_columns(&mut self) {
+    for (index, col) in self.columns.iter_mut().enumerate() {
+      col.decoded = self.selection.as_ref().is_none_or(|selection| selection.contains(&index))
+        || self.filter.as_ref().is_some_and(|filter| filter.columns.contains(&index));
+    }
+  }
+
+  /// Only decode the named columns. Rows hold the selected columns in the given order, and the bytes and memos of
+  /// the other columns are skipped. Names are matched exactly first, then case-insensitively as Access does.
+  pub fn select<I, S>(&mut self, names: I) -> Result<(), MdbError>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    if self.columns.is_empty() {
+      self.read_columns()?;
+    }
+
+    let mut selection = Vec::new();
+    for name in names {
+      let name = name.as_ref();
+      let index = self.resolve_column(name).ok_or(MdbError::ColumnNotFound)?;
+      selection.push(index);
+    }
+
+    self.selection = Some(selection);
+    self.update_decoded_columns();
+    Ok(())
+  }
+
+  /// Decode every column again.
+  pub fn select_all(&mut self) {
+    self.selection = None;
+    self.update_decoded_columns();
+  }
+
+  /// The selected columns, in the order they were selected.
+  pub fn selected_columns(&self) -> impl Iterator<Item = &Column> {
+    let indexes: Vec<usize> = match &self.selection {
+      Some(selection) => selection.clone(),
+      None => (0..self.columns.len()).collect(),
+    };
+    indexes.into_iter().map(|index| &self.columns[index])
+  }
+
+  pub fn read_columns(&mut self) -> Result<(), MdbError> {
+    self.mdb.read_page(self.first_table_definition_page)?;
+
+    let mut cur_pos: u16 = self.mdb.format.tab_cols_start_offset as u16 + (self.real_index_count as u16 * self.mdb.format.tab_ridx_entry_size);
+
+    let mut column_buffer: Vec<u8> = vec![0; self.mdb.format.tab_col_entry_size as usize];
+
+    let len = column_buffer.len();
+
+    for _i in 0..self.column_count {
+      self.columns.push(Column::new(self.mdb.encoding));
+    }
+
+    // Column Attributes
+    for column in self.columns.iter_mut() {
+      self.mdb.read_page_if_n(Some(&mut column_buffer), &mut cur_pos, len as u16)?;
+
+      column.column_type = column_buffer[0].try_into().expect("Invalid column type.");
+      column.number = column_buffer[self.mdb.format.column_number_offset];
+      column.row_column_number = get_u16(&column_buffer, self.mdb.format.table_row_column_number_offset);
+
+      match column.column_type {
+        ColumnType::Numeric | ColumnType::Money | ColumnType::Float | ColumnType::Double => {
+          column.scale = column_buffer[self.mdb.format.column_scale_offset];
+          column.precision = column_buffer[self.mdb.format.column_precision_offset];
+        }
+        // Complex columns keep their id where other columns have the scale and precision.
+        ColumnType::Complex => {
+          column.complex_id = Some(get_u32(&column_buffer, self.mdb.format.column_scale_offset) as i32);
+        }
+        _ => {}
+      }
+
+      // Jet 3 has no extended flags.
+      let extended_flags = if self.mdb.mdb_file.jet_version == MdbFormatVersion::JET3 { 0 } else { column_buffer[self.mdb.format.col_flags_offset + 1] };
+      column.flags = ColumnFlags::for_type(column.column_type, column_buffer[self.mdb.format.col_flags_offset], extended_flags);
+
+      column.fixed_offset = get_u16(&column_buffer, self.mdb.format.table_column_offset_fixed);
+      column.var_col_num = get_u16(&column_buffer, self.mdb.format.tab_col_offset_var);
+
+      column.size = if column.column_type != ColumnType::Bool {get_u16(&column_buffer, self.mdb.format.column_size_offset) } else { 0 };
+
+      // Memo and OLE values are read from their LVAL pages when they are first used, and the values of complex
+      // columns from their flat table.
+      if matches!(column.column_type, ColumnType::Memo | ColumnType::OLE | ColumnType::Complex) {
+        column.mdb = Some(self.mdb.clone());
+      }
+    }
+
+    // Column names
+    for column in self.columns.iter_mut() {
+      let name_size: usize = if self.mdb.mdb_file.jet_version == MdbFormatVersion::JET3 {
+        self.mdb.read_page_if_8(&mut cur_pos)? as usize
+      } else {
+        self.mdb.read_page_if_16(&mut cur_pos)? as usize
+      };
+      let mut column_name_buffer: Vec<u8> = vec![0; name_size];
+      self.mdb.read_page_if_n(Some(&mut column_name_buffer), &mut cur_pos, name_size as u16)?;
+
+      column.name = decode_mdb_string(self.mdb.mdb_file.jet_version, self.mdb.encoding, &column_name_buffer)?;
+    }
+
+    self.read_indexes(&mut cur_pos)?;
+
+    Ok(())
+  }
+
+  /// Read the real and logical indexes, which follow the column names in the table definition.
+  fn read_indexes(&mut self, cur_pos: &mut u16) -> Result<(), MdbError> {
+    let is_jet3 = self.mdb.mdb_file.jet_version == MdbFormatVersion::JET3;
+
+    // Real indexes: the key columns, flags and first page of each.
+    let mut real_indexes: Vec<RealIndex> = Vec::new();
+    for _i in 0..self.real_index_count {
+      if !is_jet3 {
+        self.mdb.read_page_if_n(None, cur_pos, 4)?;
+      }
+
+      let mut key_columns: Vec<(u16, bool)> = Vec::new();
+      for _j in 0..MAX_INDEX_COLUMNS {
+        let column_number = self.mdb.read_page_if_16(cur_pos)?;
+        let order = self.mdb.read_page_if_8(cur_pos)?;
+        if column_number != 0xFFFF {
+          key_columns.push((column_number, order == 1));
+        }
+      }
+
+      // Usage map of the index pages.
+      self.mdb.read_page_if_n(None, cur_pos, 4)?;
+      let mut first_page: [u8; 4] = [0; 4];
+      self.mdb.read_page_if_n(Some(&mut first_page), cur_pos, 4)?;
+      if !is_jet3 {
+        self.mdb.read_page_if_n(None, cur_pos, 4)?;
+      }
+      let flags = self.mdb.read_page_if_8(cur_pos)?;
+      if !is_jet3 {
+        self.mdb.read_page_if_n(None, cur_pos, 5)?;
+      }
+
+      real_indexes.push(RealIndex { key_columns, first_page: u32::from_le_bytes(first_page), flags });
+    }
+
+    // Logical indexes, which point at a real index.
+    let entry_size: usize = if is_jet3 { 20 } else { 28 };
+    let mut logical_indexes: Vec<(u32, u8)> = Vec::new();
+    for _i in 0..self.index_count {
+      let mut entry: Vec<u8> = vec![0; entry_size];
+      self.mdb.read_page_if_n(Some(&mut entry), cur_pos, entry_size as u16)?;
+      let entry = if is_jet3 { &entry[..] } else { &entry[4..] };
+      logical_indexes.push((get_u32(entry, 4), entry[19]));
+    }
+
+    for (real_index_number, index_type) in logical_indexes {
+      let name_size: usize = if is_jet3 {
+        self.mdb.read_page_if_8(cur_pos)? as usize
+      } else {
+        self.mdb.read_page_if_16(cur_pos)? as usize
+      };
+      let mut name_buffer: Vec<u8> = vec![0; name_size];
+      self.mdb.read_page_if_n(Some(&mut name_buffer), cur_pos, name_size as u16)?;
+      let name = decode_mdb_string(self.mdb.mdb_file.jet_version, self.mdb.encoding, &name_buffer)?;
+
+      let real_index = real_indexes.get(real_index_number as usize).ok_or(MdbError::InvalidTableDefinition)?;
+      let columns = real_index.key_columns.iter()
+        .filter_map(|(column_number, ascending)| {
+          self.columns.iter()
+            .find(|column| column.number as u16 == *column_number)
+            .map(|column| IndexColumn { name: column.name.clone(), ascending: *ascending })
+        })
+        .collect();
+
+      self.indexes.push(Index {
+        name,
+        index_type: IndexType::from(index_type),
+        real_index_number,
+        columns,
+        flags: real_index.flags,
+        first_page: real_index.first_page,
+      });
+    }
+
+    Ok(())
+  }
+
+  /// Load a table from a catalog entry.
+  pub fn from_catalog_entry(entry: CatalogEntry, mdb: &Mdb) -> Result<Table, MdbError> {
+    let mut mdb: Mdb = mdb.clone();
+    let CatalogEntry::Table(entry) = entry;
+
+    mdb.read_page(entry.page)?;
+
+    if mdb.get_u8(0) != 2 {
+      eprintln!("First byte is not equal to 2.");
+      return Err(MdbError::InvalidTableDefinition);
+    }
+
+    let page_row = mdb.get_u32(mdb.format.tab_usage_map_offset);
+    let real_index_count: u32 = mdb.get_u32(mdb.format.real_index_count_offset);
+    let index_count: u32 = mdb.get_u32(mdb.format.tab_num_idxs_offset as usize);
+    let variable_column_count = mdb.get_u16(mdb.format.table_column_count_offset - 2);
+    let column_count = mdb.get_u16(mdb.format.table_column_count_offset);
+
+    // Temporary mdb for getting usage map.
+    let mut usage_map_mdb = mdb.clone();
+
+    let usage_map_row = mdb_find_page_row_packed(&mut usage_map_mdb, page_row)?;
+
+    let usage_map_buffer = Vec::from(&usage_map_mdb.page_buffer[usage_map_row.start as usize..usage_map_row.start as usize + usage_map_row.length as usize]);
+    let usage_map = UsageMap::from_raw(&mut usage_map_mdb, &usage_map_buffer)?;
+
+    let first_data_page = mdb.get_u16(mdb.format.table_first_data_page_offset);
+
+    let table = Table {
+      name: entry.name,
+      row_count: mdb.get_u32(mdb.format.row_count_offset),
+      variable_column_count,
+      column_count,
+      first_data_page,
+      columns: vec![],
+      indexes: vec![],
+      real_index_count,
+      index_count,
+      current_page_number: 0,
+      current_physical_page_number: 0,
+      current_row: 0,
+      is_temporary_table: false,
+      strategy: TableStrategy::TableScan,
+      mdb,
+      first_table_definition_page: entry.page,
+      usage_map,
+      selection: None,
+      filter: None,
+      index_cursor: None,
+    };
+
+    Ok(table)
+  }
 }
\ No newline at end of file
@@ -1,83 +1,119 @@
-const NON_LEAP_CALENDAR: [i32; 13] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334, 365];
-const LEAP_CALENDAR: [i32; 13] = [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335, 366];
-
-#[derive(Default)]
-pub struct CDateTime {
-  /// Seconds after the minute [0, 59]
-  pub second: i32,
-  /// Minutes after the hour  [0, 59]
-  pub minute: i32,
-  /// Hours since midnight [0, 23]
-  pub hour: i32,
-  /// Day of the month [1, 12]
-  pub month_day: i32,
-  /// Months since January [0, 11]
-  pub month: i32,
-  /// Years since 1900,
-  pub year: i32,
-  /// Days since Sunday [0, 6]
-  pub week_day: i32,
-  /// days since January 1 – [0, 365],
-  pub year_day: i32,
-  /// Daylight Saving Time flag. The value is Some(true) if DST is in effect, Some(false) if not and None if no information is available
-  pub is_daylight_savings_time: Option<bool>,
-}
-
-impl CDateTime {
-
-  pub fn from_f64(raw: f64) -> CDateTime {
-    let mut datetime = CDateTime::default();
-    let mut q: i32;
-
-    if raw < 0.0 || raw > 1e6 /* About 2700 AD*/ {
-      // TODO: investigate
-      return datetime;
-    }
-
-    let mut year: i32 = 1;
-    let mut day: i32 = raw as i32;
-    let time: i32 = ((raw - day as f64) * 86400.0 + 0.5) as i32;
-    datetime.hour = time / 3600;
-    datetime.minute = (time / 60) % 60;
-    datetime.second = time % 60;
-
-    day += 693593; /* Days from 1/1/1 to 12/31/1899 */
-    datetime.week_day = (day + 1) % 7;
-
-    q = day / 146097;  /* 146097 days in 400 years */
-    year += 400 * q;
-    day -= q * 146097;
-
-    q = day / 36524;  /* 36524 days in 100 years */
-    if q > 3 {
-      q = 3;
-    }
-    year += 100 * q;
-    day -= q * 36524;
-
-    q = day / 1461;  /* 1461 days in 4 years */
-    year += 4 * q;
-    day -= q * 1461;
-
-    q = day / 365;  /* 365 days in 1 year */
-    if q > 3 {
-      q = 3;
-    }
-    year += q;
-    day -= q * 365;
-
-    let cal = if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) { &LEAP_CALENDAR } else { &NON_LEAP_CALENDAR };
-    datetime.month = 0;
-    while datetime.month < 12 {
-      if day < cal[datetime.month as usize + 1] {
-        break;
-      }
-      datetime.month += 1;
-    }
-    datetime.year = year - 1900;
-    datetime.month_day = day - cal[datetime.month as usize] + 1;
-    datetime.year_day = day;
-    datetime.is_daylight_savings_time = None;
-    datetime
-  }
-}
\ No newline at end of file
+use chrono::{Duration, NaiveDate, NaiveDateTime, Timelike};
+
+const NON_LEAP_CALENDAR: [i32; 13] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334, 365];
+const LEAP_CALENDAR: [i32; 13] = [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335, 366];
+
+#[derive(Default)]
+pub struct CDateTime {
+  /// Seconds after the minute [0, 59]
+  pub second: i32,
+  /// Minutes after the hour  [0, 59]
+  pub minute: i32,
+  /// Hours since midnight [0, 23]
+  pub hour: i32,
+  /// Day of the month [1, 12]
+  pub month_day: i32,
+  /// Months since January [0, 11]
+  pub month: i32,
+  /// Years since 1900,
+  pub year: i32,
+  /// Days since Sunday [0, 6]
+  pub week_day: i32,
+  /// days since January 1 – [0, 365],
+  pub year_day: i32,
+  /// Daylight Saving Time flag. The value is Some(true) if DST is in effect, Some(false) if not and None if no information is available
+  pub is_daylight_savings_time: Option<bool>,
+}
+
+impl CDateTime {
+
+  pub fn from_f64(raw: f64) -> CDateTime {
+    let mut datetime = CDateTime::default();
+    let mut q: i32;
+
+    if !(0.0..=1e6 /* About 2700 AD*/).contains(&raw) {
+      // TODO: investigate
+      return datetime;
+    }
+
+    let mut year: i32 = 1;
+    let mut day: i32 = raw as i32;
+    let time: i32 = ((raw - day as f64) * 86400.0 + 0.5) as i32;
+    datetime.hour = time / 3600;
+    datetime.minute = (time / 60) % 60;
+    datetime.second = time % 60;
+
+    day += 693593; /* Days from 1/1/1 to 12/31/1899 */
+    datetime.week_day = (day + 1) % 7;
+
+    q = day / 146097;  /* 146097 days in 400 years */
+    year += 400 * q;
+    day -= q * 146097;
+
+    q = day / 36524;  /* 36524 days in 100 years */
+    if q > 3 {
+      q = 3;
+    }
+    year += 100 * q;
+    day -= q * 36524;
+
+    q = day / 1461;  /* 1461 days in 4 years */
+    year += 4 * q;
+    day -= q * 1461;
+
+    q = day / 365;  /* 365 days in 1 year */
+    if q > 3 {
+      q = 3;
+    }
+    year += q;
+    day -= q * 365;
+
+    let cal = if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) { &LEAP_CALENDAR } else { &NON_LEAP_CALENDAR };
+    datetime.month = 0;
+    while datetime.month < 12 {
+      if day < cal[datetime.month as usize + 1] {
+        break;
+      }
+      datetime.month += 1;
+    }
+    datetime.year = year - 1900;
+    datetime.month_day = day - cal[datetime.month as usize] + 1;
+    datetime.year_day = day;
+    datetime.is_daylight_savings_time = None;
+    datetime
+  }
+
+  /// Convert to a `NaiveDateTime`. Returns `None` for dates outside the range handled by `from_f64`.
+  pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+    chrono::NaiveDate::from_ymd_opt(1900 + self.year, (self.month + 1) as u32, self.month_day as u32)?
+      .and_hms_opt(self.hour as u32, self.minute as u32, self.second as u32)
+  }
+}
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+const MAX_DAYS: f64 = 1e8;
+
+fn access_epoch() -> NaiveDateTime {
+  NaiveDate::from_ymd_opt(1899, 12, 30).unwrap().and_hms_opt(0, 0, 0).unwrap()
+}
+
+/// Decode an Access date, stored as the number of days since 12/30/1899. The whole part counts days before or after
+/// that date, and the fraction is always the time after midnight, so -1.25 is 12/29/1899 6:00.
+pub fn datetime_from_f64(raw: f64) -> Option<NaiveDateTime> {
+  // Far beyond the years Access allows, and beyond what chrono can represent.
+  if !raw.is_finite() || raw.abs() > MAX_DAYS {
+    return None;
+  }
+  let days = raw.trunc();
+  let seconds = ((raw - days).abs() * SECONDS_PER_DAY).round();
+  access_epoch()
+    .checked_add_signed(Duration::days(days as i64))?
+    .checked_add_signed(Duration::seconds(seconds as i64))
+}
+
+/// The number of days since 12/30/1899, as Access stores dates. This is the inverse of `datetime_from_f64`.
+pub fn datetime_to_f64(datetime: &NaiveDateTime) -> f64 {
+  let days = datetime.date().signed_duration_since(access_epoch().date()).num_days() as f64;
+  let time = (datetime.num_seconds_from_midnight() as f64 + datetime.nanosecond() as f64 / 1e9) / SECONDS_PER_DAY;
+  if days < 0.0 { days - time } else { days + time }
+}
@@ -1,18 +1,43 @@
-pub fn get_u64(buf: &[u8], offset: usize) -> u64 {
-  (buf[offset] as u64) +
-    ((buf[offset + 1] as u64) << 8) +
-    ((buf[offset + 2] as u64) << 16) +
-    ((buf[offset + 3] as u64) << 24) +
-    ((buf[offset + 4] as u64) << 32) +
-    ((buf[offset + 5] as u64) << 40) +
-    ((buf[offset + 6] as u64) << 48) +
-    ((buf[offset + 7] as u64) << 56)
-}
-
-pub fn get_u32(buf: &[u8], offset: usize) -> u32 {
-  (buf[offset] as u32) + ((buf[offset + 1] as u32) << 8) + ((buf[offset + 2] as u32) << 16) + ((buf[offset + 3] as u32) << 24)
-}
-
-pub fn get_u16(buf: &[u8], offset: usize) -> u16 {
-  (buf[offset] as u16) + ((buf[offset + 1] as u16) << 8)
-}
\ No newline at end of file
+pub fn get_u64(buf: &[u8], offset: usize) -> u64 {
+  (buf[offset] as u64) +
+    ((buf[offset + 1] as u64) << 8) +
+    ((buf[offset + 2] as u64) << 16) +
+    ((buf[offset + 3] as u64) << 24) +
+    ((buf[offset + 4] as u64) << 32) +
+    ((buf[offset + 5] as u64) << 40) +
+    ((buf[offset + 6] as u64) << 48) +
+    ((buf[offset + 7] as u64) << 56)
+}
+
+pub fn get_u32(buf: &[u8], offset: usize) -> u32 {
+  (buf[offset] as u32) + ((buf[offset + 1] as u32) << 8) + ((buf[offset + 2] as u32) << 16) + ((buf[offset + 3] as u32) << 24)
+}
+
+pub fn get_u16(buf: &[u8], offset: usize) -> u16 {
+  (buf[offset] as u16) + ((buf[offset + 1] as u16) << 8)
+}
+
+/// Device names that Windows reserves, with or without an extension.
+const RESERVED_FILE_NAMES: &[&str] = &[
+  "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+  "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Replace the characters that aren't allowed in file names on Windows, and path separators everywhere. Empty names,
+/// `.` and `..` become `_`, and so do reserved device names such as `CON`, keeping any extension.
+pub fn sanitize_file_name(name: &str) -> String {
+  let name: String = name.chars()
+    .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+    .collect();
+  if name.is_empty() || name == "." || name == ".." {
+    return "_".to_string();
+  }
+  let (stem, extension) = name.split_once('.').map_or((name.as_str(), None), |(stem, extension)| (stem, Some(extension)));
+  if RESERVED_FILE_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem.trim_end())) {
+    return match extension {
+      Some(extension) => format!("_.{}", extension),
+      None => "_".to_string(),
+    };
+  }
+  name
+}
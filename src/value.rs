@@ -0,0 +1,224 @@
+use std::fmt::{Display, Formatter};
+
+use chrono::NaiveDateTime;
+
+/// Currency values are stored as integers scaled by this factor.
+pub const CURRENCY_SCALE: i64 = 10000;
+
+/// A decoded column value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+  Null,
+  Bool(bool),
+  U8(u8),
+  I16(i16),
+  I32(i32),
+  /// Not produced by any column type yet; reserved for Large Number columns.
+  I64(i64),
+  F32(f32),
+  F64(f64),
+  /// A currency value in ten-thousandths, so 1.5 is stored as 15000.
+  Currency(i64),
+  /// A decimal number: `value` divided by 10 to the power of `scale`.
+  Decimal { value: i128, scale: u8 },
+  DateTime(NaiveDateTime),
+  /// A Date/Time Extended value, with a precision of 100 nanoseconds.
+  ExtendedDateTime(NaiveDateTime),
+  Guid([u8; 16]),
+  Text(String),
+  Binary(Vec<u8>),
+  /// The values of a multivalued field.
+  List(Vec<Value>),
+}
+
+impl Value {
+  pub fn is_null(&self) -> bool {
+    matches!(self, Value::Null)
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Value::Text(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  pub fn as_bytes(&self) -> Option<&[u8]> {
+    match self {
+      Value::Binary(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Value::Bool(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// The value of any integer type.
+  pub fn as_i64(&self) -> Option<i64> {
+    match self {
+      Value::U8(value) => Some(*value as i64),
+      Value::I16(value) => Some(*value as i64),
+      Value::I32(value) => Some(*value as i64),
+      Value::I64(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  /// The value of any numeric type, which may lose precision for currency and decimals.
+  pub fn as_f64(&self) -> Option<f64> {
+    match self {
+      Value::F32(value) => Some(*value as f64),
+      Value::F64(value) => Some(*value),
+      Value::Currency(value) => Some(*value as f64 / CURRENCY_SCALE as f64),
+      Value::Decimal { value, scale } => Some(*value as f64 / 10f64.powi(*scale as i32)),
+      _ => self.as_i64().map(|value| value as f64),
+    }
+  }
+
+  pub fn as_list(&self) -> Option<&[Value]> {
+    match self {
+      Value::List(values) => Some(values),
+      _ => None,
+    }
+  }
+
+  pub fn as_datetime(&self) -> Option<NaiveDateTime> {
+    match self {
+      Value::DateTime(value) | Value::ExtendedDateTime(value) => Some(*value),
+      _ => None,
+    }
+  }
+}
+
+impl From<bool> for Value {
+  fn from(value: bool) -> Self {
+    Value::Bool(value)
+  }
+}
+
+impl From<i32> for Value {
+  fn from(value: i32) -> Self {
+    Value::I32(value)
+  }
+}
+
+impl From<i64> for Value {
+  fn from(value: i64) -> Self {
+    Value::I64(value)
+  }
+}
+
+impl From<f64> for Value {
+  fn from(value: f64) -> Self {
+    Value::F64(value)
+  }
+}
+
+impl From<&str> for Value {
+  fn from(value: &str) -> Self {
+    Value::Text(value.to_string())
+  }
+}
+
+impl From<String> for Value {
+  fn from(value: String) -> Self {
+    Value::Text(value)
+  }
+}
+
+impl From<NaiveDateTime> for Value {
+  fn from(value: NaiveDateTime) -> Self {
+    Value::DateTime(value)
+  }
+}
+
+impl Display for Value {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Value::Null => write!(f, "NULL"),
+      Value::Bool(value) => write!(f, "{}", value),
+      Value::U8(value) => write!(f, "{}", value),
+      Value::I16(value) => write!(f, "{}", value),
+      Value::I32(value) => write!(f, "{}", value),
+      Value::I64(value) => write!(f, "{}", value),
+      Value::F32(value) => write!(f, "{}", value),
+      Value::F64(value) => write!(f, "{}", value),
+      Value::Currency(value) => write!(f, "{}", format_scaled(*value as i128, 4)),
+      Value::Decimal { value, scale } => write!(f, "{}", format_scaled(*value, *scale)),
+      Value::DateTime(value) => write!(f, "{}", value.format("%m/%d/%Y %H:%M:%S")),
+      Value::ExtendedDateTime(value) => write!(f, "{}", value.format("%Y-%m-%dT%H:%M:%S%.f+00:00")),
+      Value::Guid(value) => write!(f, "{}", format_guid(value)),
+      Value::Text(value) => write!(f, "{}", value),
+      Value::Binary(value) => {
+        for byte in value {
+          write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+      }
+      Value::List(values) => {
+        write!(f, "[")?;
+        for (index, value) in values.iter().enumerate() {
+          if index != 0 {
+            write!(f, ",")?;
+          }
+          write_json(f, value)?;
+        }
+        write!(f, "]")
+      }
+    }
+  }
+}
+
+/// Write a value of a list as JSON. Numbers and booleans are written as they are, and everything else as a string.
+fn write_json(f: &mut Formatter<'_>, value: &Value) -> std::fmt::Result {
+  match value {
+    Value::Null => write!(f, "null"),
+    Value::Bool(_) | Value::U8(_) | Value::I16(_) | Value::I32(_) | Value::I64(_) | Value::Currency(_)
+    | Value::Decimal { .. } | Value::List(_) => write!(f, "{}", value),
+    Value::F32(_) | Value::F64(_) if value.as_f64().is_some_and(f64::is_finite) => write!(f, "{}", value),
+    Value::F32(_) | Value::F64(_) => write!(f, "null"),
+    _ => {
+      write!(f, "\"")?;
+      for c in value.to_string().chars() {
+        match c {
+          '"' => write!(f, "\\\"")?,
+          '\\' => write!(f, "\\\\")?,
+          '\n' => write!(f, "\\n")?,
+          '\r' => write!(f, "\\r")?,
+          '\t' => write!(f, "\\t")?,
+          c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+          c => write!(f, "{}", c)?,
+        }
+      }
+      write!(f, "\"")
+    }
+  }
+}
+
+/// Format a GUID in registry format. The first three groups are stored little endian.
+pub fn format_guid(guid: &[u8; 16]) -> String {
+  format!("{{{:02X}{:02X}{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+          guid[3], guid[2], guid[1], guid[0],
+          guid[5], guid[4],
+          guid[7], guid[6],
+          guid[8], guid[9],
+          guid[10], guid[11], guid[12], guid[13], guid[14], guid[15])
+}
+
+/// Format an integer scaled by 10 to the power of `scale`, keeping every fractional digit.
+fn format_scaled(value: i128, scale: u8) -> String {
+  let digits = value.unsigned_abs().to_string();
+  let sign = if value < 0 { "-" } else { "" };
+  let scale = scale as usize;
+  if scale == 0 {
+    return format!("{}{}", sign, digits);
+  }
+
+  let digits = format!("{:0>width$}", digits, width = scale + 1);
+  let (whole, fraction) = digits.split_at(digits.len() - scale);
+  format!("{}{}.{}", sign, whole, fraction)
+}
@@ -0,0 +1,519 @@
+//! A small ACCDB database with attachment, multivalued, append only memo and calculated columns, which the sample
+//! database lacks. Access isn't available to save one, so the pages are written as file-format-notes.md describes,
+//! after the header page of the sample database.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::NaiveDateTime;
+use mdbtools::column::ColumnType;
+
+const PAGE_SIZE: usize = 4096;
+/// The usage maps of every table are rows of this page.
+const USAGE_MAP_PAGE: usize = 1;
+const USAGE_MAP_SIZE: usize = 32;
+
+const FIXED: u8 = 0x01;
+const NULLABLE: u8 = 0x02;
+const AUTO_LONG: u8 = 0x04;
+const CALCULATED: u8 = 0xc0;
+
+const SYSTEM_OBJECT: i32 = 0x80000000u32 as i32;
+const PRIMARY_KEY: u8 = 0x01;
+
+/// The flat tables of the complex columns of Items.
+pub const FILES_TABLE: &str = "f_3C2E8A1B5D7F4E6A9B0C1D2E3F4A5B6C_Files";
+pub const COLORS_TABLE: &str = "f_3C2E8A1B5D7F4E6A9B0C1D2E3F4A5B6C_Colors";
+pub const NOTES_HISTORY_TABLE: &str = "f_3C2E8A1B5D7F4E6A9B0C1D2E3F4A5B6C_NotesHistory";
+
+pub const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR not much of a picture";
+
+/// The path of the database, written once per test binary.
+pub fn complex_database() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!("mdbtools-complex-{}.accdb", std::process::id()));
+        std::fs::write(&path, database()).expect("Failed to write the complex database");
+        path
+    })
+}
+
+/// Encode FileData as Access does: the content is a header with its length, a flag and the extension, then the file.
+pub fn attachment_data(extension: &str, file: &[u8], compressed: bool) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut header: Vec<u8> = Vec::new();
+    for unit in extension.encode_utf16().chain([0]) {
+        header.extend_from_slice(&unit.to_le_bytes());
+    }
+    let mut content = ((header.len() + 8) as u32).to_le_bytes().to_vec();
+    content.extend_from_slice(&1u32.to_le_bytes());
+    content.extend_from_slice(&header);
+    content.extend_from_slice(file);
+
+    let mut encoded = (compressed as u32).to_le_bytes().to_vec();
+    encoded.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    if compressed {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&content).unwrap();
+        encoded.extend(encoder.finish().unwrap());
+    } else {
+        encoded.extend_from_slice(&content);
+    }
+    encoded
+}
+
+struct ColumnDef {
+    name: &'static str,
+    column_type: ColumnType,
+    flags: u8,
+    extended_flags: u8,
+    /// The scale and precision of numeric columns, or the complex id of complex columns.
+    misc: [u8; 4],
+}
+
+impl ColumnDef {
+    fn new(name: &'static str, column_type: ColumnType) -> ColumnDef {
+        let fixed = !matches!(column_type, ColumnType::Text | ColumnType::Memo | ColumnType::OLE | ColumnType::Binary);
+        ColumnDef { name, column_type, flags: if fixed { FIXED | NULLABLE } else { NULLABLE }, extended_flags: 0, misc: [0; 4] }
+    }
+
+    fn auto_number(name: &'static str) -> ColumnDef {
+        ColumnDef { flags: FIXED | NULLABLE | AUTO_LONG, ..ColumnDef::new(name, ColumnType::LongInt) }
+    }
+
+    fn complex(name: &'static str, complex_id: i32) -> ColumnDef {
+        ColumnDef { flags: FIXED | NULLABLE | AUTO_LONG, misc: complex_id.to_le_bytes(), ..ColumnDef::new(name, ColumnType::Complex) }
+    }
+
+    /// Calculated columns are stored as variable length columns, whatever their result type.
+    fn calculated(name: &'static str, column_type: ColumnType, scale: u8, precision: u8) -> ColumnDef {
+        ColumnDef { flags: NULLABLE, extended_flags: CALCULATED, misc: [scale, precision, 0, 0], ..ColumnDef::new(name, column_type) }
+    }
+
+    fn is_fixed(&self) -> bool {
+        self.flags & FIXED != 0
+    }
+
+    fn size(&self) -> u16 {
+        match self.column_type {
+            ColumnType::Bool => 0,
+            ColumnType::Byte => 1,
+            ColumnType::Int => 2,
+            ColumnType::LongInt | ColumnType::Float | ColumnType::Complex => 4,
+            ColumnType::Money | ColumnType::Double | ColumnType::Datetime => 8,
+            ColumnType::ReplicationId => 16,
+            ColumnType::Numeric => 17,
+            ColumnType::Text => 510,
+            _ => 0,
+        }
+    }
+}
+
+type Cell = Option<Vec<u8>>;
+
+struct TableDef {
+    name: &'static str,
+    flags: i32,
+    columns: Vec<ColumnDef>,
+    primary_key: Option<&'static str>,
+    rows: Vec<Vec<Cell>>,
+    properties: Option<Vec<u8>>,
+}
+
+impl TableDef {
+    fn new(name: &'static str, flags: i32, columns: Vec<ColumnDef>) -> TableDef {
+        TableDef { name, flags, columns, primary_key: None, rows: Vec::new(), properties: None }
+    }
+
+    /// The fixed offset of each fixed column and the number of each variable one.
+    fn layout(&self) -> Vec<u16> {
+        let (mut fixed_offset, mut var_col_num) = (0, 0);
+        self.columns.iter()
+            .map(|column| {
+                let slot = if column.is_fixed() { &mut fixed_offset } else { &mut var_col_num };
+                let position = *slot;
+                *slot += if column.is_fixed() { column.size() } else { 1 };
+                position
+            })
+            .collect()
+    }
+
+    fn var_column_count(&self) -> usize {
+        self.columns.iter().filter(|column| !column.is_fixed()).count()
+    }
+
+    /// A row is the column count, the fixed columns, the variable columns, their offsets from the last, the variable
+    /// column count and the null mask.
+    fn encode_row(&self, cells: &[Cell]) -> Vec<u8> {
+        let layout = self.layout();
+        let fixed_size: usize = self.columns.iter().filter(|column| column.is_fixed()).map(|column| column.size() as usize).sum();
+        let mut row = (self.columns.len() as u16).to_le_bytes().to_vec();
+        row.resize(2 + fixed_size, 0);
+
+        let mut var_data: Vec<Option<&[u8]>> = vec![None; self.var_column_count()];
+        let mut null_mask = vec![0u8; self.columns.len().div_ceil(8)];
+        for (number, (column, cell)) in self.columns.iter().zip(cells).enumerate() {
+            let Some(data) = cell else { continue };
+            null_mask[number / 8] |= 1 << (number % 8);
+            if column.is_fixed() {
+                let start = 2 + layout[number] as usize;
+                row[start..start + data.len()].copy_from_slice(data);
+            } else {
+                var_data[layout[number] as usize] = Some(data);
+            }
+        }
+
+        let mut offsets: Vec<u16> = Vec::new();
+        for data in var_data {
+            offsets.push(row.len() as u16);
+            row.extend_from_slice(data.unwrap_or_default());
+        }
+        offsets.push(row.len() as u16);
+        for offset in offsets.iter().rev() {
+            row.extend_from_slice(&offset.to_le_bytes());
+        }
+        row.extend_from_slice(&(self.var_column_count() as u16).to_le_bytes());
+        row.extend(null_mask);
+        row
+    }
+
+    fn definition(&self, usage_map_row: usize) -> Vec<u8> {
+        let index_count = self.primary_key.is_some() as u32;
+        let mut page = vec![0u8; PAGE_SIZE];
+        page[0] = 0x02;
+        page[1] = 0x01;
+        page[16..20].copy_from_slice(&(self.rows.len() as u32).to_le_bytes());
+        page[20..24].copy_from_slice(&(self.rows.len() as u32 + 1).to_le_bytes());
+        page[40] = if self.flags & SYSTEM_OBJECT != 0 { 0x53 } else { 0x4e };
+        page[41..43].copy_from_slice(&(self.columns.len() as u16).to_le_bytes());
+        page[43..45].copy_from_slice(&(self.var_column_count() as u16).to_le_bytes());
+        page[45..47].copy_from_slice(&(self.columns.len() as u16).to_le_bytes());
+        page[47..51].copy_from_slice(&index_count.to_le_bytes());
+        page[51..55].copy_from_slice(&index_count.to_le_bytes());
+        let usage_map = ((USAGE_MAP_PAGE as u32) << 8) | usage_map_row as u32;
+        page[55..59].copy_from_slice(&usage_map.to_le_bytes());
+        page[59..63].copy_from_slice(&usage_map.to_le_bytes());
+
+        // The row count of each real index, then the columns and their names.
+        let mut pos = 63 + index_count as usize * 12;
+        for ((number, column), position) in self.columns.iter().enumerate().zip(self.layout()) {
+            let entry = &mut page[pos..pos + 25];
+            entry[0] = column.column_type as u8;
+            entry[5..7].copy_from_slice(&(number as u16).to_le_bytes());
+            entry[7..9].copy_from_slice(&(if column.is_fixed() { 0 } else { position }).to_le_bytes());
+            entry[9..11].copy_from_slice(&(number as u16).to_le_bytes());
+            entry[11..15].copy_from_slice(&column.misc);
+            entry[15] = column.flags;
+            entry[16] = column.extended_flags;
+            entry[21..23].copy_from_slice(&(if column.is_fixed() { position } else { 0 }).to_le_bytes());
+            entry[23..25].copy_from_slice(&column.size().to_le_bytes());
+            pos += 25;
+        }
+        for column in &self.columns {
+            pos = write_name(&mut page, pos, column.name);
+        }
+
+        if let Some(key) = self.primary_key {
+            // The real index: its key columns, no index pages and unique, required keys.
+            let number = self.columns.iter().position(|column| column.name == key).unwrap() as u16;
+            let entry = &mut page[pos..pos + 52];
+            entry[4..34].fill(0xff);
+            entry[4..6].copy_from_slice(&number.to_le_bytes());
+            entry[6] = 1;
+            entry[46] = 0x09;
+            pos += 52;
+
+            // The logical index pointing at it.
+            page[pos + 23] = PRIMARY_KEY;
+            pos += 28;
+            pos = write_name(&mut page, pos, "PrimaryKey");
+        }
+        page[8..12].copy_from_slice(&(pos as u32 - 8).to_le_bytes());
+        page
+    }
+}
+
+fn write_name(page: &mut [u8], pos: usize, name: &str) -> usize {
+    let name = text(name).unwrap();
+    page[pos..pos + 2].copy_from_slice(&(name.len() as u16).to_le_bytes());
+    page[pos + 2..pos + 2 + name.len()].copy_from_slice(&name);
+    pos + 2 + name.len()
+}
+
+/// Pack rows onto data pages from the end of each page, the offset of every row following the row count.
+fn data_pages(table_definition_page: usize, rows: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let mut pages: Vec<Vec<u8>> = Vec::new();
+    let (mut count, mut start) = (0, 0);
+    for row in rows {
+        if pages.is_empty() || 14 + (count + 1) * 2 + row.len() > start {
+            let mut page = vec![0u8; PAGE_SIZE];
+            page[0] = 0x01;
+            page[1] = 0x01;
+            page[4..8].copy_from_slice(&(table_definition_page as u32).to_le_bytes());
+            pages.push(page);
+            (count, start) = (0, PAGE_SIZE);
+        }
+        let page = pages.last_mut().unwrap();
+        start -= row.len();
+        page[start..start + row.len()].copy_from_slice(row);
+        page[14 + count * 2..16 + count * 2].copy_from_slice(&(start as u16).to_le_bytes());
+        count += 1;
+        page[12..14].copy_from_slice(&(count as u16).to_le_bytes());
+        let free_space = start - 14 - count * 2;
+        page[2..4].copy_from_slice(&(free_space as u16).to_le_bytes());
+    }
+    pages
+}
+
+pub fn long(value: i32) -> Cell {
+    Some(value.to_le_bytes().to_vec())
+}
+
+pub fn int(value: i16) -> Cell {
+    Some(value.to_le_bytes().to_vec())
+}
+
+pub fn double(value: f64) -> Cell {
+    Some(value.to_le_bytes().to_vec())
+}
+
+/// Days since 1899-12-30, the time of day as the fraction.
+pub fn datetime(value: &str) -> Cell {
+    let value = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap();
+    let epoch = NaiveDateTime::parse_from_str("1899-12-30 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap();
+    double((value - epoch).num_seconds() as f64 / 86400.0)
+}
+
+pub fn text(value: &str) -> Cell {
+    Some(value.encode_utf16().flat_map(u16::to_le_bytes).collect())
+}
+
+/// A memo or OLE value stored in the row, after its length and the inline flag.
+pub fn long_value(data: &[u8]) -> Cell {
+    let mut value = (data.len() as u32 | 0x80000000).to_le_bytes().to_vec();
+    value.extend_from_slice(&[0; 8]);
+    value.extend_from_slice(data);
+    Some(value)
+}
+
+pub fn memo(value: &str) -> Cell {
+    long_value(&text(value).unwrap())
+}
+
+/// The result of a calculated column follows 16 bytes and its length.
+pub fn calculated(result: Cell) -> Cell {
+    let result = result?;
+    let mut value = vec![0u8; 16];
+    value.extend_from_slice(&(result.len() as u32).to_le_bytes());
+    value.extend_from_slice(&result);
+    value.extend_from_slice(&[0; 3]);
+    Some(value)
+}
+
+/// A calculated decimal is its length, scale and sign, then the digits in the word order of numeric columns.
+pub fn calculated_decimal(digits: u128, scale: u8, negative: bool) -> Cell {
+    let mut result = 18u16.to_le_bytes().to_vec();
+    result.push(scale);
+    result.push(negative as u8);
+    for word in (0..4).rev() {
+        result.extend_from_slice(&((digits >> (word * 32)) as u32).to_le_bytes());
+    }
+    calculated(Some(result))
+}
+
+/// The name, type and value of a property.
+type Property<'a> = (&'a str, ColumnType, &'a str);
+
+/// Property blocks of the columns of a table: the names of the properties, then a block per column.
+fn properties(columns: &[(&str, &[Property])]) -> Vec<u8> {
+    let mut names: Vec<&str> = Vec::new();
+    for (_, properties) in columns {
+        for (name, _, _) in properties.iter() {
+            if !names.contains(name) {
+                names.push(name);
+            }
+        }
+    }
+
+    let chunk = |chunk_type: u16, payload: Vec<u8>| {
+        let mut chunk = ((payload.len() + 6) as u32).to_le_bytes().to_vec();
+        chunk.extend_from_slice(&chunk_type.to_le_bytes());
+        chunk.extend(payload);
+        chunk
+    };
+    let mut data = b"MR2\0".to_vec();
+    let mut payload: Vec<u8> = Vec::new();
+    for name in &names {
+        let name = text(name).unwrap();
+        payload.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        payload.extend(name);
+    }
+    data.extend(chunk(0x80, payload));
+
+    for (column, properties) in columns {
+        let column = text(column).unwrap();
+        let mut payload = ((column.len() + 6) as u32).to_le_bytes().to_vec();
+        payload.extend_from_slice(&(column.len() as u16).to_le_bytes());
+        payload.extend(column);
+        for (name, data_type, value) in properties.iter() {
+            let value = text(value).unwrap();
+            payload.extend_from_slice(&((value.len() + 8) as u16).to_le_bytes());
+            payload.push(0);
+            payload.push(*data_type as u8);
+            payload.extend_from_slice(&(names.iter().position(|other| other == name).unwrap() as u16).to_le_bytes());
+            payload.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            payload.extend(value);
+        }
+        data.extend(chunk(0x01, payload));
+    }
+    data
+}
+
+fn attachment_row(id: i32, row: i32, file_name: &str, data: Vec<u8>, time_stamp: &str) -> Vec<Cell> {
+    let extension = file_name.rsplit('.').next().unwrap();
+    vec![
+        long_value(&data), long(0), text(file_name), datetime(time_stamp), text(extension), None, long(id), long(row),
+    ]
+}
+
+/// The tables after MSysObjects. Each table definition is on page 2 onwards in this order, which makes the page its
+/// id.
+fn tables() -> Vec<TableDef> {
+    use ColumnType::*;
+
+    let mut complex_columns = TableDef::new("MSysComplexColumns", SYSTEM_OBJECT, vec![
+        ColumnDef::new("ColumnName", Text),
+        ColumnDef::auto_number("ComplexID"),
+        ColumnDef::new("ComplexTypeObjectID", LongInt),
+        ColumnDef::new("ConceptualTableID", LongInt),
+        ColumnDef::new("FlatTableID", LongInt),
+    ]);
+    complex_columns.rows = vec![
+        vec![text("Files"), long(1), long(5), long(7), long(8)],
+        vec![text("Colors"), long(2), long(4), long(7), long(9)],
+        vec![text("NotesHistory"), long(3), long(6), long(7), long(10)],
+    ];
+
+    let text_type = TableDef::new("MSysComplexType_Text", SYSTEM_OBJECT, vec![ColumnDef::new("Value", Text)]);
+    let attachment_columns = || vec![
+        ColumnDef::new("FileData", OLE),
+        ColumnDef::new("FileFlags", LongInt),
+        ColumnDef::new("FileName", Text),
+        ColumnDef::new("FileTimeStamp", Datetime),
+        ColumnDef::new("FileType", Text),
+        ColumnDef::new("FileURL", Memo),
+    ];
+    let attachment_type = TableDef::new("MSysComplexType_Attachment", SYSTEM_OBJECT, attachment_columns());
+    let version_history_columns = || vec![ColumnDef::new("Notes", Memo), ColumnDef::new("Modified", Datetime)];
+    let version_history_type = TableDef::new("MSysComplexType_VersionHistory", SYSTEM_OBJECT, version_history_columns());
+
+    let mut items = TableDef::new("Items", 0, vec![
+        ColumnDef::auto_number("ID"),
+        ColumnDef::new("Name", Text),
+        ColumnDef::new("Price", Double),
+        ColumnDef::new("Qty", Int),
+        ColumnDef::calculated("Total", Double, 0, 0),
+        ColumnDef::calculated("Rate", Numeric, 0, 18),
+        ColumnDef::new("Notes", Memo),
+        ColumnDef::complex("Files", 1),
+        ColumnDef::complex("Colors", 2),
+        ColumnDef::complex("NotesHistory", 3),
+    ]);
+    items.primary_key = Some("ID");
+    items.rows = vec![
+        vec![
+            long(1), text("Widget"), double(10.5), int(2), calculated(double(21.0)), calculated_decimal(525, 2, false),
+            memo("Checked twice"), long(1), long(1), long(1),
+        ],
+        vec![
+            long(2), text("Gadget"), double(4.25), int(4), calculated(double(17.0)), calculated_decimal(10625, 4, false),
+            None, long(2), long(2), long(2),
+        ],
+        vec![
+            long(3), text("Gizmo"), double(-3.0), int(2), calculated(double(-6.0)), calculated_decimal(15, 1, true),
+            memo("Spare"), long(3), long(3), long(3),
+        ],
+    ];
+    items.properties = Some(properties(&[
+        ("Name", &[("Caption", Text, "Item name")]),
+        ("Total", &[("Expression", Memo, "[Price]*[Qty]")]),
+        ("Rate", &[("Expression", Memo, "[Price]/[Qty]")]),
+    ]));
+
+    let mut files = TableDef::new(FILES_TABLE, SYSTEM_OBJECT, attachment_columns());
+    files.columns.extend([ColumnDef::auto_number("ID"), ColumnDef::new("_Files", LongInt)]);
+    files.rows = vec![
+        attachment_row(1, 1, "notes.txt", attachment_data("txt", b"Remember the milk.\n", true), "2021-03-01 09:00:00"),
+        attachment_row(2, 1, "logo.png", attachment_data("png", PNG, false), "2021-03-01 09:05:00"),
+        attachment_row(3, 2, "manual.pdf", attachment_data("pdf", b"%PDF-1.4 manual", true), "2021-03-02 14:00:00"),
+    ];
+
+    let mut colors = TableDef::new(COLORS_TABLE, SYSTEM_OBJECT, vec![ColumnDef::new("Value", Text)]);
+    colors.columns.extend([ColumnDef::auto_number("ID"), ColumnDef::new("_Colors", LongInt)]);
+    colors.rows = vec![
+        vec![text("Green"), long(1), long(2)],
+        vec![text("Red"), long(2), long(1)],
+        vec![text("Blue"), long(3), long(1)],
+    ];
+
+    let mut notes_history = TableDef::new(NOTES_HISTORY_TABLE, SYSTEM_OBJECT, version_history_columns());
+    notes_history.columns.extend([ColumnDef::auto_number("ID"), ColumnDef::new("_NotesHistory", LongInt)]);
+    notes_history.rows = vec![
+        vec![memo("Checked twice"), datetime("2021-03-02 10:30:00"), long(1), long(1)],
+        vec![memo("Spare"), datetime("2021-04-01 08:00:00"), long(2), long(3)],
+        vec![memo("First draft"), datetime("2021-03-01 09:00:00"), long(3), long(1)],
+    ];
+
+    vec![
+        complex_columns, text_type, attachment_type, version_history_type, items, files, colors, notes_history,
+    ]
+}
+
+/// The header page of the sample database, marked as Access 2010 for the calculated columns, then the usage maps,
+/// the table definitions and the data pages.
+fn database() -> Vec<u8> {
+    let sample = std::fs::read("testdata/ASampleDatabase.accdb").expect("Failed to read the sample database");
+    let mut header = sample[..PAGE_SIZE].to_vec();
+    header[0x14] = 0x03;
+
+    let mut system_objects = TableDef::new("MSysObjects", SYSTEM_OBJECT, vec![
+        ColumnDef::new("Id", ColumnType::LongInt),
+        ColumnDef::new("ParentId", ColumnType::LongInt),
+        ColumnDef::new("Name", ColumnType::Text),
+        ColumnDef::new("Type", ColumnType::Int),
+        ColumnDef::new("Flags", ColumnType::LongInt),
+        ColumnDef::new("LvProp", ColumnType::OLE),
+    ]);
+    let mut tables = tables();
+    system_objects.rows.push(vec![long(2), long(0), text("MSysObjects"), int(1), long(SYSTEM_OBJECT), None]);
+    for (index, table) in tables.iter().enumerate() {
+        system_objects.rows.push(vec![
+            long(index as i32 + 3), long(0), text(table.name), int(1), long(table.flags),
+            table.properties.as_deref().and_then(long_value),
+        ]);
+    }
+    tables.insert(0, system_objects);
+
+    let mut pages: Vec<Vec<u8>> = vec![header, Vec::new()];
+    let mut usage_maps: Vec<Vec<u8>> = Vec::new();
+    let first_data_page = 2 + tables.len();
+    let mut data: Vec<Vec<u8>> = Vec::new();
+    for (index, table) in tables.iter().enumerate() {
+        pages.push(table.definition(index));
+
+        let rows: Vec<Vec<u8>> = table.rows.iter().map(|row| table.encode_row(row)).collect();
+        let mut usage_map = vec![0u8; 5 + USAGE_MAP_SIZE];
+        for page in data_pages(index + 2, &rows) {
+            let number = first_data_page + data.len();
+            usage_map[5 + number / 8] |= 1 << (number % 8);
+            data.push(page);
+        }
+        usage_maps.push(usage_map);
+    }
+    pages[USAGE_MAP_PAGE] = data_pages(0, &usage_maps).remove(0);
+    pages.extend(data);
+    pages.concat()
+}
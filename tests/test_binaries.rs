@@ -0,0 +1,528 @@
+mod fixture;
+
+use std::process::Command;
+
+const SAMPLE_DB: &str = "testdata/ASampleDatabase.accdb";
+
+fn run(binary: &str, args: &[&str]) -> String {
+    let output = Command::new(binary)
+        .args(args)
+        .output()
+        .unwrap_or_else(|e| panic!("Failed to run {}: {}", binary, e));
+    assert!(
+        output.status.success(),
+        "{} failed: {}",
+        binary,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("Output is not UTF-8")
+}
+
+#[test]
+fn test_tables_type_filter() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-tables"),
+        &["-f", SAMPLE_DB, "-1", "-T", "-t", "query"],
+    );
+    assert_eq!(
+        output,
+        "qryComputerHardwareInOwnerOrder (Query)\nqryCostsSummedByOwner (Query)\nqryGSTCalculations (Query)\n"
+    );
+
+    let output = run(env!("CARGO_BIN_EXE_mdb-tables"), &["-f", SAMPLE_DB, "-1"]);
+    assert_eq!(output, "Asset Items\n");
+}
+
+#[test]
+fn test_queries_listing_and_sql() {
+    let output = run(env!("CARGO_BIN_EXE_mdb-queries"), &["-f", SAMPLE_DB, "-1"]);
+    assert_eq!(
+        output,
+        "qryComputerHardwareInOwnerOrder\nqryCostsSummedByOwner\nqryGSTCalculations\n"
+    );
+
+    // Hidden queries are listed with the same switch as mdb-tables.
+    let output = run(env!("CARGO_BIN_EXE_mdb-queries"), &["-f", SAMPLE_DB, "-1", "-H"]);
+    assert!(output.lines().any(|name| name == "~sq_ffrmAssetInventory"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-queries"),
+        &["-f", SAMPLE_DB, "-q", "qryGSTCalculations"],
+    );
+    assert!(output.contains("[Cost]/1.22 AS [Ex Tax]"));
+    assert!(output.ends_with("FROM [Asset Items];\n"));
+}
+
+#[test]
+fn test_schema_views() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "--views", "-T", "qryCostsSummedByOwner"],
+    );
+    assert_eq!(
+        output,
+        "CREATE OR REPLACE VIEW \"qryCostsSummedByOwner\" AS\n\
+         SELECT \"Asset Items\".\"Owner\", \"Asset Items\".\"Asset Category\", SUM(\"Asset Items\".\"Cost\") AS \"SumOfCost\"\n\
+         FROM \"Asset Items\"\n\
+         GROUP BY \"Asset Items\".\"Owner\", \"Asset Items\".\"Asset Category\";\n\n"
+    );
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "mssql", "--views", "-T", "qryComputerHardwareInOwnerOrder"],
+    );
+    assert!(output.starts_with("GO\n-- WARNING: ORDER BY is not allowed in a view and was removed\nCREATE VIEW"));
+    assert!(output.ends_with(";\nGO\n\n"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "--views", "-s", "assets", "-T", "qryCostsSummedByOwner"],
+    );
+    assert!(output.starts_with("CREATE OR REPLACE VIEW \"assets\".\"qryCostsSummedByOwner\" AS\n"));
+    assert!(output.contains("\nFROM \"assets\".\"Asset Items\"\n"));
+}
+
+#[test]
+fn test_schema_relationships() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "MSysAccessStorage"],
+    );
+    assert!(output.ends_with(
+        "ALTER TABLE \"MSysAccessStorage\" ADD CONSTRAINT \"MSysAccessStorageSTGREL\" FOREIGN KEY (\"ParentId\") \
+         REFERENCES \"MSysAccessStorage\" (\"Id\") ON DELETE CASCADE;\n\n"
+    ));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "MSysAccessStorage", "--no-relations"],
+    );
+    assert!(!output.contains("FOREIGN KEY"));
+}
+
+#[test]
+fn test_schema_constraints() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items", "--no-comments"],
+    );
+    assert_eq!(
+        output,
+        "CREATE TABLE IF NOT EXISTS \"Asset Items\" (\n  \
+         \"Asset No\" VARCHAR(5) NOT NULL,\n  \
+         \"Asset Category\" VARCHAR(20),\n  \
+         \"Make\" VARCHAR(25),\n  \
+         \"Model\" VARCHAR(25),\n  \
+         \"Description\" VARCHAR(40),\n  \
+         \"Owner\" VARCHAR(20),\n  \
+         \"Serial No\" VARCHAR(15),\n  \
+         \"Acquired\" TIMESTAMP WITHOUT TIME ZONE,\n  \
+         \"Cost\" NUMERIC(15,4) DEFAULT 0,\n  \
+         \"Warranty\" INTEGER DEFAULT 0,\n  \
+         \"Tax Scale\" VARCHAR(1),\n  \
+         \"Supplier No\" VARCHAR(5),\n  \
+         \"Comments\" TEXT,\n  \
+         CONSTRAINT \"Asset Items_PrimaryKey\" PRIMARY KEY (\"Asset No\")\n\
+         );\n\n"
+    );
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &[
+            "-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items",
+            "--no-primary-keys", "--no-not-null", "--no-default-values",
+        ],
+    );
+    assert!(!output.contains("PRIMARY KEY"));
+    assert!(!output.contains("NOT NULL"));
+    assert!(!output.contains("DEFAULT"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "mssql", "-T", "MSysAccessStorage", "--no-relations"],
+    );
+    assert!(output.contains("[Id] INT IDENTITY(1,1) NOT NULL"));
+    assert!(output.contains("CONSTRAINT [MSysAccessStorage_ParentIdName] UNIQUE ([ParentId], [Name])"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "mssql", "-T", "MSysAccessStorage", "--no-identity", "--no-indexes"],
+    );
+    assert!(output.contains("[Id] INT NOT NULL"));
+    assert!(!output.contains("UNIQUE"));
+}
+
+#[test]
+fn test_prop() {
+    let output = run(env!("CARGO_BIN_EXE_mdb-prop"), &["-f", SAMPLE_DB]);
+    assert!(output.starts_with("name: MSysDb\n\tAccessVersion: 09.50\n\tBuild: 24\n"));
+
+    let output = run(env!("CARGO_BIN_EXE_mdb-prop"), &["-f", SAMPLE_DB, "-T", "Asset Items", "-c", "Cost"]);
+    assert_eq!(
+        output,
+        "name: Cost\n\
+         \tColumnWidth: -1\n\
+         \tColumnOrder: 0\n\
+         \tColumnHidden: no\n\
+         \tDescription: Records the purchase price of the asset\n\
+         \tFormat: $#,##0.00;-$#,##0.00\n\
+         \tDecimalPlaces: 255\n\
+         \tDefaultValue: 0\n\
+         \tRequired: no\n\
+         \tGUID: {F1E766DC-BC61-11D3-9F44-0090272F540F}\n"
+    );
+
+    let output = run(env!("CARGO_BIN_EXE_mdb-prop"), &["-f", SAMPLE_DB, "-T", "SummaryInfo", "--showtype"]);
+    assert!(output.contains("\tTitle (0x0a): Rostrum Assets\n"));
+}
+
+#[test]
+fn test_schema_comments() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items"],
+    );
+    assert!(output.contains(
+        ");\nCOMMENT ON COLUMN \"Asset Items\".\"Asset No\" IS 'Records the asset number';\n"
+    ));
+    assert!(output.ends_with(
+        "COMMENT ON COLUMN \"Asset Items\".\"Comments\" IS 'Records miscellaneous comments';\n\n"
+    ));
+    assert_eq!(output.matches("COMMENT ON COLUMN").count(), 13);
+    assert!(!output.contains("COMMENT ON TABLE"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "mssql", "-T", "Asset Items"],
+    );
+    assert!(output.contains(
+        "EXEC sys.sp_addextendedproperty @name = N'MS_Description', @value = N'Records the asset number', \
+         @level0type = N'SCHEMA', @level0name = N'dbo', @level1type = N'TABLE', @level1name = N'Asset Items', \
+         @level2type = N'COLUMN', @level2name = N'Asset No';\n"
+    ));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items", "--no-comments"],
+    );
+    assert!(!output.contains("COMMENT"));
+}
+
+#[test]
+fn test_export_captions() {
+    // The sample columns have no captions, so their names are kept.
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "--captions"],
+    );
+    assert!(output.starts_with(
+        "Asset No,Asset Category,Make,Model,Description,Owner,Serial No,Acquired,Cost,Warranty,Tax Scale,Supplier No,Comments\n"
+    ));
+}
+
+#[test]
+fn test_column_flags_output() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "--types"],
+    );
+    let header = output.lines().next().unwrap();
+    assert!(header.starts_with("Asset No(text/nullable/compressed unicode),"));
+    assert!(header.contains(",Cost(money/fixed/nullable),"));
+    assert!(header.ends_with(",Comments(memo/nullable)"));
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-schema"),
+        &["-f", SAMPLE_DB, "-b", "postgres", "-T", "MSysAccessStorage", "--types", "--no-relations"],
+    );
+    assert!(output.contains("-- Id: long int (nullable, auto long)\n"));
+    assert!(output.contains("-- Lv: ole (nullable)\n"));
+}
+
+#[test]
+fn test_export_table_name_case() {
+    let exact = run(env!("CARGO_BIN_EXE_mdb-export"), &["-f", SAMPLE_DB, "-T", "Asset Items"]);
+    let lowercase = run(env!("CARGO_BIN_EXE_mdb-export"), &["-f", SAMPLE_DB, "-T", "asset items"]);
+    assert_eq!(exact, lowercase);
+}
+
+#[test]
+fn test_codegen() {
+    let codegen = env!("CARGO_BIN_EXE_mdb-codegen");
+
+    let output = run(codegen, &["-f", SAMPLE_DB]);
+    assert!(output.starts_with(
+        "/// The \"Asset Items\" table.\n\
+         #[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]\n\
+         pub struct AssetItems {\n    \
+         #[serde(rename = \"Asset No\")]\n    \
+         pub asset_no: String,\n    \
+         #[serde(rename = \"Asset Category\")]\n    \
+         pub asset_category: Option<String>,\n"
+    ));
+    assert!(output.contains("    pub acquired: Option<chrono::NaiveDateTime>,\n"));
+    assert!(output.contains("    pub cost: Option<rust_decimal::Decimal>,\n"));
+    assert!(output.contains("    pub warranty: Option<i32>,\n"));
+    assert_eq!(output.matches("#[serde(rename").count(), 13);
+
+    let output = run(codegen, &["-f", SAMPLE_DB, "-T", "Asset Items", "-t", "sqlx"]);
+    assert!(output.contains("#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]\n"));
+    assert!(output.contains("    #[sqlx(rename = \"Serial No\")]\n    pub serial_no: Option<String>,\n"));
+
+    let output = run(codegen, &["-f", SAMPLE_DB, "-t", "diesel"]);
+    assert!(output.starts_with("diesel::table! {\n    #[sql_name = \"Asset Items\"]\n    asset_items (asset_no) {\n"));
+    assert!(output.contains("        #[sql_name = \"Asset No\"]\n        asset_no -> Text,\n"));
+    assert!(output.contains("        cost -> Nullable<Numeric>,\n"));
+    assert!(output.contains("#[diesel(table_name = asset_items)]\npub struct AssetItems {\n"));
+    assert!(output.contains("    pub cost: Option<bigdecimal::BigDecimal>,\n"));
+
+    let output = run(codegen, &["-f", SAMPLE_DB, "-t", "sea-orm"]);
+    assert!(output.contains("    #[sea_orm(table_name = \"Asset Items\")]\n"));
+    assert!(output.contains(
+        "        #[sea_orm(primary_key, auto_increment = false, column_name = \"Asset No\")]\n        pub asset_no: String,\n"
+    ));
+    assert!(output.contains("    impl ActiveModelBehavior for ActiveModel {}\n"));
+
+    // Multivalued fields are lists of their value column.
+    let database = fixture::complex_database().to_str().unwrap();
+    let output = run(codegen, &["-f", database]);
+    assert!(output.contains("    #[serde(rename = \"Colors\")]\n    pub colors: Option<Vec<String>>,\n"));
+    assert!(output.contains("    pub rate: Option<rust_decimal::Decimal>,\n"));
+    let output = run(codegen, &["-f", database, "-t", "diesel"]);
+    assert!(output.contains("        colors -> Nullable<Array<Text>>,\n"));
+    assert!(output.contains("    pub colors: Option<Vec<String>>,\n"));
+
+    // AutoNumber columns can have the same flags as complex columns.
+    let output = run(codegen, &["-f", database, "-t", "sea-orm"]);
+    assert!(output.contains("        #[sea_orm(primary_key, column_name = \"ID\")]\n        pub id: i32,\n"));
+    assert!(output.contains("        #[sea_orm(column_name = \"Files\")]\n        pub files: Option<i32>,\n"));
+}
+
+#[test]
+fn test_export_generated_columns() {
+    let database = fixture::complex_database().to_str().unwrap();
+    let schema = run(env!("CARGO_BIN_EXE_mdb-schema"), &["-f", database, "-b", "postgres", "-T", "Items"]);
+    assert!(schema.contains("  \"Total\" DOUBLE PRECISION GENERATED ALWAYS AS (\"Price\"*\"Qty\") STORED,\n"));
+    assert!(schema.contains(" GENERATED ALWAYS AS (\"Price\"/\"Qty\") STORED,\n"));
+
+    // The AutoNumber ID has the same flags as the complex columns.
+    let schema = run(env!("CARGO_BIN_EXE_mdb-schema"), &["-f", database, "-b", "postgres", "-T", "Items", "--types"]);
+    assert!(schema.contains("-- ID: long int (fixed, nullable, auto long)\n"));
+    assert!(schema.contains("-- Files: complex (complex)\n"));
+    assert!(schema.contains("  \"ID\" INTEGER GENERATED BY DEFAULT AS IDENTITY NOT NULL,\n"));
+    assert!(schema.contains("  \"Files\" INTEGER,\n"));
+
+    // Postgres computes generated columns, so their stored results are left out.
+    let export_args = ["-f", database, "-b", "postgres", "-T", "Items", "-c", "ID,Total,Rate"];
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &export_args);
+    assert_eq!(output, "INSERT INTO \"Items\" (\"ID\")\nVALUES\n  (1),\n  (2),\n  (3);\n");
+
+    // They are kept for schemas without generated columns, and by backends without them.
+    let values = "VALUES\n  (1,21,5.25),\n  (2,17,1.0625),\n  (3,-6,-1.5);\n";
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &[&export_args[..], &["--no-generated"]].concat());
+    assert_eq!(output, format!("INSERT INTO \"Items\" (\"ID\", \"Total\", \"Rate\")\n{}", values));
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &["-f", database, "-b", "mssql", "-T", "Items", "-c", "ID,Total,Rate"]);
+    assert_eq!(output, format!("INSERT INTO [Items] ([ID], [Total], [Rate])\n{}", values));
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &["-f", database, "-T", "Items", "-c", "ID,Total,Rate"]);
+    assert_eq!(output, "ID,Total,Rate\n1,21,5.25\n2,17,1.0625\n3,-6,-1.5\n");
+}
+
+#[test]
+fn test_export_columns() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "--columns", "owner,Asset No,Cost"],
+    );
+    let lines: Vec<&str> = output.lines().take(3).collect();
+    assert_eq!(lines, vec!["Owner,Asset No,Cost", "\"Sales\",\"30050\",1995.5000", "\"Sales\",\"30051\",2450.0000"]);
+    assert_eq!(output.lines().count(), 66);
+
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "-c", "Owner,Comments", "-b", "postgres"],
+    );
+    assert!(output.starts_with("INSERT INTO \"Asset Items\" (\"Owner\", \"Comments\")\nVALUES\n  ('Sales',NULL),\n"));
+}
+
+#[test]
+fn test_export_where() {
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "-c", "Asset No,Acquired", "--where", "[Asset No] BETWEEN '30100' AND '30102'"],
+    );
+    assert_eq!(
+        output,
+        "Asset No,Acquired\n\"30100\",\"06/22/1999 00:00:00\"\n\"30101\",\"06/23/1999 00:00:00\"\n\"30102\",\"06/24/1999 00:00:00\"\n"
+    );
+
+    // No INSERT is written when nothing matches.
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-export"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "-b", "postgres", "-w", "Cost < 0"],
+    );
+    assert_eq!(output, "");
+}
+
+#[test]
+fn test_blobs() {
+    let output_dir = std::env::temp_dir().join(format!("mdb-blobs-test-{}", std::process::id()));
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-blobs"),
+        &["-f", SAMPLE_DB, "-T", "MSysAccessStorage", "-o", output_dir.to_str().unwrap()],
+    );
+
+    let mut database = mdbtools::database::Database::open(SAMPLE_DB).unwrap();
+    let mut table = database.table("MSysAccessStorage").unwrap();
+    let mut written = output.lines();
+    for row in table.rows() {
+        // Files are named by the primary key, Id.
+        let row = row.unwrap();
+        if let mdbtools::value::Value::Binary(expected) = &row["Lv"] {
+            let path = written.next().unwrap();
+            assert!(path.ends_with(&format!("/{}-Lv.bin", row["Id"])), "{}", path);
+            assert_eq!(&std::fs::read(path).unwrap(), expected);
+        }
+    }
+    assert_eq!(written.next(), None);
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_attachments() {
+    let output_dir = std::env::temp_dir().join(format!("mdb-attachments-test-{}", std::process::id()));
+    // The sample database has no attachment columns.
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-attachments"),
+        &["-f", SAMPLE_DB, "-o", output_dir.to_str().unwrap()],
+    );
+    assert_eq!(output, "");
+    let output = run(
+        env!("CARGO_BIN_EXE_mdb-attachments"),
+        &["-f", SAMPLE_DB, "-T", "Asset Items", "-o", output_dir.to_str().unwrap()],
+    );
+    assert_eq!(output, "");
+    assert!(!output_dir.exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdb-attachments"))
+        .args(["-f", SAMPLE_DB, "-T", "Missing"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Table not found.\n");
+
+    // Files are written to a directory for the primary key of each row.
+    let database = fixture::complex_database().to_str().unwrap();
+    let output = run(env!("CARGO_BIN_EXE_mdb-attachments"), &["-f", database, "-o", output_dir.to_str().unwrap()]);
+    let written: Vec<&str> = output.lines().collect();
+    let expected = ["Items/1/notes.txt", "Items/1/logo.png", "Items/2/manual.pdf"];
+    assert_eq!(written.len(), expected.len());
+    for (path, expected) in written.iter().zip(expected) {
+        assert!(path.ends_with(expected), "{}", path);
+    }
+    assert_eq!(std::fs::read(written[0]).unwrap(), b"Remember the milk.\n");
+    assert_eq!(std::fs::read(written[1]).unwrap(), fixture::PNG);
+    std::fs::remove_dir_all(output_dir).unwrap();
+}
+
+#[test]
+fn test_normalize_complex() {
+    // Without complex columns, the schema and rows are the same.
+    let schema_args = ["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items"];
+    let normalized = run(env!("CARGO_BIN_EXE_mdb-schema"), &[&schema_args[..], &["--normalize-complex"]].concat());
+    assert_eq!(normalized, run(env!("CARGO_BIN_EXE_mdb-schema"), &schema_args));
+
+    let export_args = ["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items"];
+    let normalized = run(env!("CARGO_BIN_EXE_mdb-export"), &[&export_args[..], &["--normalize-complex"]].concat());
+    assert_eq!(normalized, run(env!("CARGO_BIN_EXE_mdb-export"), &export_args));
+
+    // Child tables are keyed by the primary key, and can't be written to the same CSV.
+    let output = Command::new(env!("CARGO_BIN_EXE_mdb-export"))
+        .args(["-f", SAMPLE_DB, "-T", "Asset Items", "--normalize-complex"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Complex columns can only be normalized with a SQL backend.\n");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdb-export"))
+        .args(["-f", SAMPLE_DB, "-b", "postgres", "-T", "Asset Items", "-c", "Make", "--normalize-complex"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "The primary key must be exported to normalize complex columns.\n");
+
+    // The values of each row are keyed by its primary key, after the rows of the table.
+    let database = fixture::complex_database().to_str().unwrap();
+    let export_args = ["-f", database, "-b", "postgres", "-T", "Items", "--normalize-complex"];
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &[&export_args[..], &["-c", "ID,Name,Colors"]].concat());
+    assert_eq!(
+        output,
+        "INSERT INTO \"Items\" (\"ID\", \"Name\")\nVALUES\n  (1,'Widget'),\n  (2,'Gadget'),\n  (3,'Gizmo');\n\
+         INSERT INTO \"Items_Colors\" (\"ID\", \"Value\")\nVALUES\n  (1,'Red'),\n  (1,'Blue'),\n  (2,'Green');\n"
+    );
+
+    // Only the values of the rows matching the condition are exported.
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &[&export_args[..], &["-w", "Price > 5"]].concat());
+    assert!(output.contains("INSERT INTO \"Items_Files\" (\"ID\", \"FileData\", \"FileFlags\", \"FileName\", \"FileTimeStamp\", \"FileType\", \"FileURL\")\n"));
+    assert!(output.contains(",0,'logo.png','03/01/2021 09:05:00','png',NULL);\n"));
+    assert!(output.contains("INSERT INTO \"Items_NotesHistory\" (\"ID\", \"Notes\", \"Modified\")\nVALUES\n  (1,'Checked twice','03/02/2021 10:30:00'),\n"));
+    assert!(!output.contains("manual.pdf") && !output.contains("Green") && !output.contains("Spare"));
+
+    // Child tables only reference the primary key when it is emitted.
+    let schema_args = ["-f", database, "-b", "postgres", "-T", "Items", "--normalize-complex"];
+    let output = run(env!("CARGO_BIN_EXE_mdb-schema"), &schema_args);
+    assert!(output.contains("  CONSTRAINT \"Items_Colors_Items\" FOREIGN KEY (\"ID\") REFERENCES \"Items\" (\"ID\") ON DELETE CASCADE\n"));
+    let output = run(env!("CARGO_BIN_EXE_mdb-schema"), &[&schema_args[..], &["--no-primary-keys"]].concat());
+    assert!(!output.contains("REFERENCES"));
+    assert!(output.contains("-- WARNING: the primary key of Items is not emitted, so the foreign key of Items_Colors was left out.\n"));
+}
+
+#[test]
+fn test_memo_history() {
+    let export = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_mdb-export"))
+            .args([&["-f", SAMPLE_DB, "-T", "Asset Items"][..], args].concat())
+            .output()
+            .unwrap()
+    };
+
+    // Comments is a memo, but isn't append only.
+    let output = export(&["--memo-history", "Comments"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Comments is not an append only memo.\n");
+
+    let output = export(&["--memo-history", "Make"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Make is not an append only memo.\n");
+
+    let output = export(&["--memo-history", "Missing"]);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "Column not found.\n");
+
+    // The history replaces the complex columns, so they can't be normalized as well.
+    let output = export(&["-b", "postgres", "--memo-history", "Comments", "--normalize-complex"]);
+    assert!(!output.status.success());
+
+    // Each version is a row, oldest first. Rows without a history are exported once.
+    let database = fixture::complex_database().to_str().unwrap();
+    let export_args = ["-f", database, "-T", "Items", "-c", "ID,Notes", "--memo-history", "Notes"];
+    let output = run(env!("CARGO_BIN_EXE_mdb-export"), &export_args);
+    assert_eq!(
+        output,
+        "ID,Notes,Notes_Modified\n\
+         1,\"First draft\",\"03/01/2021 09:00:00\"\n\
+         1,\"Checked twice\",\"03/02/2021 10:30:00\"\n\
+         2,,\n\
+         3,\"Spare\",\"04/01/2021 08:00:00\"\n"
+    );
+
+    // Tables created by mdb-schema have no Notes_Modified column, so SQL backends use the child table instead.
+    let output = Command::new(env!("CARGO_BIN_EXE_mdb-export"))
+        .args([&export_args[..], &["-b", "postgres"]].concat())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Memo history can only be exported to CSV. Use --normalize-complex with a SQL backend.\n"
+    );
+}
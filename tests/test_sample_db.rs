@@ -1,8 +1,22 @@
-use mdbtools;
+mod fixture;
+
+use fixture::attachment_data;
 use mdbtools::backend;
-use mdbtools::catalog::{read_catalog, CatalogEntry};
-use mdbtools::column::ColumnType;
+use mdbtools::catalog::{read_catalog, read_system_objects, CatalogEntry, ObjectType};
+use mdbtools::column::{ColumnFlags, ColumnType};
+use mdbtools::database::Database;
+use mdbtools::error::MdbError;
+use mdbtools::mdbfile::MdbFormatVersion;
+use mdbtools::property::{
+    read_database_properties, read_properties, read_table_properties, ObjectProperties, Property, PropertyBlock, PropertyValue,
+};
+use mdbtools::predicate::{CompareOp, Predicate};
+use mdbtools::query::{read_queries, QueryType};
+use mdbtools::relationship::read_relationships;
+use mdbtools::row::Row;
+use mdbtools::sql::{translate, translate_in_schema};
 use mdbtools::table::Table;
+use mdbtools::value::Value;
 use std::path::PathBuf;
 
 use std::fmt::Write;
@@ -21,7 +35,7 @@ fn get_table(mdb: &mut mdbtools::mdbfile::Mdb, table_name: &str) -> Table {
             CatalogEntry::Table(table) if table.name == table_name => Some(table),
             _ => None,
         })
-        .expect(&format!("Table '{}' not found", table_name));
+        .unwrap_or_else(|| panic!("Table '{}' not found", table_name));
 
     Table::from_catalog_entry(CatalogEntry::Table(table_entry), mdb).expect("Failed to read table")
 }
@@ -98,11 +112,7 @@ fn table_to_csv(table: &mut Table) -> String {
     let mut output = String::new();
     let mut first_row = true;
 
-    loop {
-        let _row = match table.fetch_row() {
-            Ok(row) => row,
-            Err(_) => break, // No more rows
-        };
+    while table.fetch_row().is_ok() {
 
         if !first_row {
             writeln!(output).unwrap();
@@ -166,3 +176,1055 @@ fn should_quote(column_type: ColumnType) -> bool {
             | ColumnType::ExtendedDatetime
     )
 }
+
+#[test]
+fn test_system_objects() {
+    let mut mdb = open_sample_db();
+    let objects = read_system_objects(&mut mdb).expect("Failed to read system objects");
+
+    let table = objects
+        .iter()
+        .find(|object| object.name == "Asset Items")
+        .expect("Asset Items not found");
+    assert_eq!(table.object_type, ObjectType::Table);
+    assert!(!table.is_system_table());
+    assert_eq!(
+        table.date_create.unwrap().to_string(),
+        "1999-12-27 14:54:12"
+    );
+    assert!(table.lv_prop.as_ref().unwrap().starts_with(b"MR2\0"));
+
+    let query = objects
+        .iter()
+        .find(|object| object.name == "qryCostsSummedByOwner")
+        .expect("Query not found");
+    assert_eq!(query.object_type, ObjectType::Query);
+
+    let form = objects
+        .iter()
+        .find(|object| object.name == "frmAssetInventory")
+        .expect("Form not found");
+    assert_eq!(form.object_type, ObjectType::Form);
+}
+
+#[test]
+fn test_queries() {
+    let mut mdb = open_sample_db();
+    let queries = read_queries(&mut mdb).expect("Failed to read queries");
+
+    let query = queries
+        .iter()
+        .find(|query| query.name == "qryComputerHardwareInOwnerOrder")
+        .expect("Query not found");
+    assert_eq!(query.query_type, QueryType::Select);
+    assert_eq!(query.source_names(), vec!["Asset Items".to_string()]);
+    assert_eq!(
+        query.to_sql(),
+        "SELECT [Asset Items].[Asset No], [Asset Items].[Owner], [Asset Items].[Asset Category], [Asset Items].[Make], [Asset Items].[Model], [Asset Items].[Cost]\n\
+         FROM [Asset Items]\n\
+         WHERE ((([Asset Items].[Asset Category])=\"Computer Hardware\"))\n\
+         ORDER BY [Asset Items].[Owner];"
+    );
+
+    let query = queries
+        .iter()
+        .find(|query| query.name == "qryCostsSummedByOwner")
+        .expect("Query not found");
+    assert_eq!(
+        query.to_sql(),
+        "SELECT [Asset Items].[Owner], [Asset Items].[Asset Category], Sum([Asset Items].[Cost]) AS SumOfCost\n\
+         FROM [Asset Items]\n\
+         GROUP BY [Asset Items].[Owner], [Asset Items].[Asset Category];"
+    );
+
+    let query = queries
+        .iter()
+        .find(|query| query.name == "~sq_ffrmAssetInventory")
+        .expect("Query not found");
+    assert!(query.is_hidden());
+    assert_eq!(query.to_sql(), "SELECT DISTINCTROW *\nFROM [Asset Items];");
+
+    // TOP is kept alongside DISTINCT and DISTINCTROW.
+    let mut query = query.clone();
+    let flag_row = query.rows.iter_mut().find(|row| row.attribute == 3).expect("Flag row not found");
+    flag_row.flag = Some(flag_row.flag.unwrap_or(0) | 0x10 | 0x20);
+    flag_row.name1 = Some("10".to_string());
+    assert_eq!(query.to_sql(), "SELECT DISTINCTROW TOP 10 PERCENT *\nFROM [Asset Items];");
+}
+
+#[test]
+fn test_translate_access_sql() {
+    let sql = "SELECT IIf([Cost]>100, \"High\", \"Low\") AS Band, Nz([Owner], \"\") & \" \" & Left([Make], 3), \
+               DateAdd(\"m\", 1, [Acquired]), Format([Acquired], \"yyyy-mm-dd\")\n\
+               FROM [Asset Items]\n\
+               WHERE [Make] LIKE \"Del*\" AND [Acquired] > #12/31/1999# AND [Retired] = False;";
+
+    let translation = translate(sql, &backend::POSTGRES_BACKEND).expect("Failed to translate");
+    assert!(translation.warnings.is_empty());
+    assert_eq!(
+        translation.sql,
+        "SELECT CASE WHEN \"Cost\">100 THEN 'High' ELSE 'Low' END AS \"Band\", CONCAT(COALESCE(\"Owner\", ''), ' ', LEFT(\"Make\", 3)), \
+         (\"Acquired\" + (1) * INTERVAL '1 month'), TO_CHAR(\"Acquired\", 'YYYY-MM-DD')\n\
+         FROM \"Asset Items\"\n\
+         WHERE \"Make\" LIKE 'Del%' AND \"Acquired\" > DATE '1999-12-31' AND \"Retired\" = FALSE"
+    );
+
+    let translation = translate(sql, &backend::MSSQL_BACKEND).expect("Failed to translate");
+    assert!(translation.sql.contains("DATEADD(month, 1, [Acquired]), FORMAT([Acquired], 'yyyy-MM-dd')"));
+    assert!(translation.sql.ends_with("[Acquired] > CAST('1999-12-31' AS DATE) AND [Retired] = 0"));
+
+    let translation = translate_in_schema(
+        "SELECT [A].[Id], [B].[Name] FROM ([A] INNER JOIN [B] ON [A].[Id] = [B].[Id]), C AS D \
+         WHERE [A].[Id] IN (SELECT [Id] FROM [E] WHERE Left([Name], 1) = \"x\")",
+        &backend::MSSQL_BACKEND,
+        Some("sales"),
+    ).expect("Failed to translate");
+    assert_eq!(
+        translation.sql,
+        "SELECT [A].[Id], [B].[Name] FROM ([sales].[A] INNER JOIN [sales].[B] ON [A].[Id] = [B].[Id]), [sales].[C] AS [D] \
+         WHERE [A].[Id] IN (SELECT [Id] FROM [sales].[E] WHERE LEFT([Name], 1) = 'x')"
+    );
+
+    // & concatenates more loosely than arithmetic and more tightly than comparisons.
+    let translation = translate(
+        "SELECT ([Make] & \"-\" & ([Cost] + 1)) AS Label FROM [Asset Items] WHERE [Make] & [Model] = \"x\"",
+        &backend::MSSQL_BACKEND,
+    ).expect("Failed to translate");
+    assert_eq!(
+        translation.sql,
+        "SELECT (CONCAT([Make], '-', ([Cost] + 1))) AS [Label] FROM [Asset Items] WHERE CONCAT([Make], [Model]) = 'x'"
+    );
+
+    // Nz without a default can't know whether its value is text or a number.
+    let translation = translate("SELECT Nz([Cost], 0), Nz([Cost]) FROM [Asset Items]", &backend::POSTGRES_BACKEND)
+        .expect("Failed to translate");
+    assert_eq!(translation.sql, "SELECT COALESCE(\"Cost\", 0), COALESCE(\"Cost\", '') FROM \"Asset Items\"");
+    assert_eq!(
+        translation.warnings,
+        vec!["Nz([Cost]) was translated with an empty string default; give Nz a default for other types".to_string()]
+    );
+
+    let translation = translate("SELECT First([Cost]) FROM [Asset Items]", &backend::POSTGRES_BACKEND)
+        .expect("Failed to translate");
+    assert_eq!(
+        translation.warnings,
+        vec!["First([Cost]) has no translation and was copied unchanged".to_string()]
+    );
+}
+
+#[test]
+fn test_calculated_value() {
+    use mdbtools::column::unwrap_calculated_value;
+    use mdbtools::property::{Property, PropertyBlock};
+
+    // The result follows a 20 byte header ending with its length, and is followed by 3 more bytes.
+    let mut data = vec![0u8; 16];
+    data.extend(8u32.to_le_bytes());
+    data.extend(1.5f64.to_le_bytes());
+    data.extend([0u8; 3]);
+    assert_eq!(unwrap_calculated_value(&data), Some(&1.5f64.to_le_bytes()[..]));
+    assert_eq!(unwrap_calculated_value(&data[..20]), None);
+    assert_eq!(unwrap_calculated_value(&data[..10]), None);
+
+    let block = PropertyBlock {
+        name: "Total".to_string(),
+        properties: vec![Property {
+            name: "Expression".to_string(),
+            data_type: ColumnType::Memo as u8,
+            ddl: false,
+            value: PropertyValue::Text("[Cost]*[Tax Scale]".to_string()),
+        }],
+    };
+    let expression = block.expression().unwrap();
+    let translation = translate(expression, &backend::POSTGRES_BACKEND).expect("Failed to translate");
+    assert!(translation.warnings.is_empty());
+    assert_eq!(translation.sql, "\"Cost\"*\"Tax Scale\"");
+    assert_eq!(PropertyBlock::default().expression(), None);
+
+    // Calculated decimals store their own scale, whatever the scale of the column.
+    let mut database = Database::open(fixture::complex_database()).unwrap();
+    let mut table = database.table("Items").unwrap();
+    let values: Vec<(String, String)> = table.rows()
+        .map(|row| {
+            let row = row.unwrap();
+            (row["Total"].to_string(), row["Rate"].to_string())
+        })
+        .collect();
+    let expected = [("21", "5.25"), ("17", "1.0625"), ("-6", "-1.5")];
+    assert_eq!(values, expected.map(|(total, rate)| (total.to_string(), rate.to_string())));
+    assert_eq!(table.columns[table.find_column_index("Rate").unwrap()].scale, 0);
+}
+
+#[test]
+fn test_relationships() {
+    let mut mdb = open_sample_db();
+    let relationships = read_relationships(&mut mdb).expect("Failed to read relationships");
+
+    assert_eq!(relationships.len(), 1);
+    let relationship = &relationships[0];
+    assert_eq!(relationship.from_table, "MSysAccessStorage");
+    assert_eq!(relationship.from_columns, vec!["ParentId".to_string()]);
+    assert_eq!(relationship.to_table, "MSysAccessStorage");
+    assert_eq!(relationship.to_columns, vec!["Id".to_string()]);
+    assert!(relationship.enforces_integrity());
+    assert!(relationship.cascades_deletes());
+    assert!(!relationship.cascades_updates());
+}
+
+#[test]
+fn test_indexes_and_properties() {
+    let mut mdb = open_sample_db();
+    let mut table = get_table(&mut mdb, "Asset Items");
+    table.read_columns().expect("Failed to read columns");
+
+    assert_eq!(table.indexes.len(), 1);
+    let index = &table.indexes[0];
+    assert_eq!(index.name, "PrimaryKey");
+    assert!(index.is_primary_key());
+    assert!(index.is_unique());
+    assert_eq!(index.columns.len(), 1);
+    assert_eq!(index.columns[0].name, "Asset No");
+
+    let system_objects = read_system_objects(&mut mdb).expect("Failed to read system objects");
+    let object = system_objects
+        .iter()
+        .find(|object| object.name == "Asset Items")
+        .expect("Asset Items not found");
+    let properties = read_properties(&mdb, object).expect("Failed to read properties");
+    let cost = properties.column("Cost").expect("Cost properties not found");
+    assert_eq!(cost.get("DefaultValue").unwrap().as_str(), Some("0"));
+    assert!(!cost.get("Required").unwrap().to_bool());
+
+    // Only the primary key is required; the nullable flag doesn't matter.
+    let column = |name: &str| &table.columns[table.find_column_index(name).unwrap()];
+    assert!(table.is_required(column("Asset No"), &properties));
+    assert!(!table.is_required(column("Cost"), &properties));
+    assert!(!table.is_required(column("Make"), &properties));
+}
+
+#[test]
+fn test_typed_properties() {
+    let mut mdb = open_sample_db();
+    let system_objects = read_system_objects(&mut mdb).expect("Failed to read system objects");
+
+    let database = read_database_properties(&mdb, &system_objects).expect("Failed to read database properties");
+    assert_eq!(database.get("AccessVersion").unwrap().as_str(), Some("09.50"));
+    assert_eq!(database.get("Row Limit").unwrap().value, PropertyValue::LongInt(10000));
+    assert_eq!(database.get("AllowFullMenus").unwrap().value, PropertyValue::Bool(true));
+
+    let table = read_table_properties(&mdb, &system_objects, "Asset Items")
+        .expect("Failed to read table properties")
+        .expect("Asset Items not found");
+    assert_eq!(table.object.get("Orientation").unwrap().value, PropertyValue::Byte(0));
+    assert_eq!(table.object.get("GUID").unwrap().value.to_string(), "{F1E766D3-BC61-11D3-9F44-0090272F540F}");
+    assert_eq!(table.columns.len(), 13);
+
+    let cost = table.column("cost").expect("Cost properties not found");
+    assert_eq!(cost.get("Description").unwrap().as_str(), Some("Records the purchase price of the asset"));
+    assert_eq!(cost.get("Format").unwrap().as_str(), Some("$#,##0.00;-$#,##0.00"));
+    assert_eq!(cost.get("DecimalPlaces").unwrap().value, PropertyValue::Byte(255));
+    assert_eq!(cost.get("ColumnWidth").unwrap().to_i64(), Some(-1));
+
+    assert!(read_table_properties(&mdb, &system_objects, "No Such Table").unwrap().is_none());
+}
+
+#[test]
+fn test_column_caption() {
+    let caption = |value: &str| Property {
+        name: "Caption".to_string(),
+        data_type: ColumnType::Text as u8,
+        ddl: false,
+        value: PropertyValue::Text(value.to_string()),
+    };
+    let properties = ObjectProperties {
+        object: PropertyBlock::default(),
+        columns: vec![
+            PropertyBlock { name: "Cost".to_string(), properties: vec![caption("Purchase Price")] },
+            PropertyBlock { name: "Notes".to_string(), properties: vec![caption("")] },
+        ],
+    };
+    assert_eq!(properties.caption("cost"), Some("Purchase Price"));
+    assert_eq!(properties.caption("Notes"), None);
+    assert_eq!(properties.caption("Missing"), None);
+}
+
+#[test]
+fn test_column_flags() {
+    let mut mdb = open_sample_db();
+    let mut table = get_table(&mut mdb, "Asset Items");
+    table.read_columns().expect("Failed to read columns");
+
+    let asset_no = &table.columns[table.find_column_index("Asset No").unwrap()];
+    assert!(!asset_no.flags.is_fixed());
+    assert!(asset_no.flags.is_nullable());
+    assert!(asset_no.flags.is_compressed_unicode());
+    assert!(!asset_no.flags.is_long_auto());
+    assert!(!asset_no.flags.is_hyperlink());
+
+    let cost = &table.columns[table.find_column_index("Cost").unwrap()];
+    assert_eq!(cost.flags.names(), vec!["fixed", "nullable"]);
+
+    let mut storage = get_table(&mut mdb, "MSysAccessStorage");
+    storage.read_columns().expect("Failed to read columns");
+    let id = &storage.columns[storage.find_column_index("Id").unwrap()];
+    assert!(id.flags.is_long_auto());
+    assert!(!id.flags.is_uuid_auto());
+
+    // A complex column and an AutoNumber long integer can have the same flags.
+    let complex = ColumnFlags::for_type(ColumnType::Complex, 0x07, 0);
+    assert!(complex.is_complex());
+    assert!(!complex.is_long_auto());
+    assert_eq!(complex.to_string(), "complex");
+    let auto_number = ColumnFlags::for_type(ColumnType::LongInt, 0x07, 0);
+    assert!(!auto_number.is_complex());
+    assert!(auto_number.is_long_auto());
+    assert_eq!(auto_number.to_string(), "fixed, nullable, auto long");
+    assert!(ColumnFlags::new(0x02, 0xC0).is_calculated());
+    assert!(ColumnFlags::new(0x80, 0).is_hyperlink());
+}
+
+#[test]
+fn test_values() {
+    let mut mdb = open_sample_db();
+    let mut table = get_table(&mut mdb, "Asset Items");
+    table.read_columns().expect("Failed to read columns");
+    table.fetch_row().expect("Failed to fetch row");
+
+    let value = |name: &str| table.columns[table.find_column_index(name).unwrap()].value();
+    assert_eq!(value("Asset No"), Value::Text("30050".to_string()));
+    assert_eq!(value("Cost"), Value::Currency(19955000));
+    assert_eq!(value("Cost").as_f64(), Some(1995.5));
+    assert_eq!(value("Warranty"), Value::I32(12));
+    assert_eq!(value("Warranty").as_i64(), Some(12));
+    assert_eq!(
+        value("Acquired").as_datetime().unwrap().to_string(),
+        "1997-09-02 00:00:00"
+    );
+    assert!(value("Comments").is_null());
+    assert_eq!(table.columns[table.find_column_index("Cost").unwrap()].to_string(), "1995.5000");
+}
+
+#[test]
+fn test_value_display() {
+    assert_eq!(Value::Currency(24500000).to_string(), "2450.0000");
+    assert_eq!(Value::Currency(500).to_string(), "0.0500");
+    assert_eq!(Value::Currency(-500).to_string(), "-0.0500");
+    assert_eq!(Value::Decimal { value: 12345, scale: 2 }.to_string(), "123.45");
+    assert_eq!(Value::Decimal { value: -5, scale: 3 }.to_string(), "-0.005");
+    assert_eq!(Value::Decimal { value: 42, scale: 0 }.to_string(), "42");
+    assert_eq!(
+        Value::Guid([0x0d, 0x0c, 0x0b, 0x0a, 0x02, 0x01, 0x04, 0x03, 0, 1, 2, 3, 4, 5, 6, 7]).to_string(),
+        "{0A0B0C0D-0102-0304-0001-020304050607}"
+    );
+    assert_eq!(Value::Binary(vec![0x00, 0x0f, 0xff]).to_string(), "000fff");
+    assert_eq!(Value::Bool(true).to_string(), "true");
+    assert_eq!(Value::U8(7).to_string(), "7");
+}
+
+#[test]
+fn test_access_dates() {
+    use mdbtools::time::{datetime_from_f64, datetime_to_f64};
+
+    let date = |raw: f64| datetime_from_f64(raw).unwrap().to_string();
+    assert_eq!(date(0.0), "1899-12-30 00:00:00");
+    assert_eq!(date(35675.5), "1997-09-02 12:00:00");
+    // Before 12/30/1899 the fraction is still the time after midnight.
+    assert_eq!(date(-1.25), "1899-12-29 06:00:00");
+    assert_eq!(date(-657434.0), "0100-01-01 00:00:00");
+    assert_eq!(date(2958465.0), "9999-12-31 00:00:00");
+    assert!(datetime_from_f64(f64::NAN).is_none());
+
+    for raw in [0.0, 35675.5, -1.25, -0.5 - 36522.0, -657434.0, 2958465.75] {
+        assert_eq!(datetime_to_f64(&datetime_from_f64(raw).unwrap()), raw);
+    }
+}
+
+#[test]
+fn test_list_value_display() {
+    let list = Value::List(vec![
+        Value::Text("Say \"hi\"\\\n".to_string()),
+        Value::I32(-4),
+        Value::Null,
+        Value::F64(1.5),
+        Value::F64(f64::NAN),
+        Value::Currency(15000),
+        Value::Bool(false),
+    ]);
+    assert_eq!(list.to_string(), r#"["Say \"hi\"\\\n",-4,null,1.5,null,1.5000,false]"#);
+    assert_eq!(list.as_list().map(|values| values.len()), Some(7));
+    assert_eq!(Value::List(vec![]).to_string(), "[]");
+
+    let list = [Value::Text("a,\"b\"".to_string()), Value::Null, Value::Text("back\\slash".to_string())];
+    assert_eq!(backend::postgres_array_literal(&list), r#"{"a,\"b\"",NULL,"back\\slash"}"#);
+    assert_eq!(backend::postgres_array_literal(&[]), "{}");
+}
+
+#[test]
+fn test_numeric_column_value() {
+    // 123.45 at scale 2: a sign byte, then four little endian words, most significant first.
+    let mut buffer = vec![0u8; 17];
+    buffer[13..17].copy_from_slice(&12345u32.to_le_bytes());
+    assert_eq!(mdbtools::numeric::numeric_column_to_string(&buffer, 2), "123.45");
+    buffer[0] = 0x80;
+    buffer[9..13].copy_from_slice(&1u32.to_le_bytes());
+    assert_eq!(mdbtools::numeric::numeric_column_value(&buffer), Some(-(12345 + (1i128 << 32))));
+
+    // Calculated numerics have their length, scale and sign before the same digits.
+    use mdbtools::numeric::calculated_numeric_value;
+    let mut buffer = [&18u16.to_le_bytes()[..], &[2, 1], &[0; 12], &12345u32.to_le_bytes()].concat();
+    assert_eq!(calculated_numeric_value(&buffer), Some((-12345, 2)));
+    buffer[3] = 0;
+    buffer[8..12].copy_from_slice(&1u32.to_le_bytes());
+    assert_eq!(calculated_numeric_value(&buffer), Some(((1i128 << 64) + 12345, 2)));
+    // Shorter values have fewer words of digits.
+    let short = [&6u16.to_le_bytes()[..], &[0, 0], &7u32.to_le_bytes()].concat();
+    assert_eq!(calculated_numeric_value(&short), Some((7, 0)));
+    assert_eq!(calculated_numeric_value(&buffer[..3]), None);
+}
+
+#[test]
+fn test_rows() {
+    let mut mdb = open_sample_db();
+    let mut table = get_table(&mut mdb, "Asset Items");
+
+    // Columns are read on demand.
+    let rows: Vec<_> = table.rows().collect::<Result<_, _>>().expect("Failed to read rows");
+    assert_eq!(rows.len(), 65);
+
+    let first = &rows[0];
+    assert_eq!(first.len(), 13);
+    assert_eq!(first.names()[0], "Asset No");
+    assert_eq!(first[0], Value::Text("30050".to_string()));
+    assert_eq!(first["Cost"], Value::Currency(19955000));
+    assert_eq!(first.get_by_name("warranty"), Some(&Value::I32(12)));
+    assert_eq!(first.get_by_name("No Such Column"), None);
+    assert_eq!(first.get(13), None);
+    assert!(first["Comments"].is_null());
+    assert_eq!(first.iter().nth(1).unwrap(), ("Asset Category", &Value::Text("Computer Hardware".to_string())));
+
+    // A second scan restarts from the first row.
+    let again: Vec<_> = table.rows().collect::<Result<_, _>>().expect("Failed to read rows");
+    assert_eq!(again, rows);
+
+    // Stopping early and scanning again also restarts.
+    assert_eq!(table.rows().nth(2).unwrap().unwrap(), rows[2]);
+    assert_eq!(table.rows().next().unwrap().unwrap(), rows[0]);
+}
+
+#[test]
+fn test_database() {
+    let mut database = Database::open("testdata/ASampleDatabase.accdb").expect("Failed to open database");
+
+    let names: Vec<String> = database.tables().unwrap().iter().map(|object| object.name.clone()).collect();
+    assert_eq!(names, vec!["Asset Items".to_string()]);
+
+    // Names are matched case-insensitively, and the cached definition scans the same rows.
+    let mut table = database.table("asset items").expect("Failed to load table");
+    assert_eq!(table.name, "Asset Items");
+    assert_eq!(table.columns.len(), 13);
+    let rows: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+    let mut cached = database.table("Asset Items").expect("Failed to load table");
+    assert_eq!(cached.rows().collect::<Result<Vec<_>, _>>().unwrap(), rows);
+
+    assert!(database.table("MSysAccessStorage").is_ok());
+    assert!(matches!(database.table("No Such Table"), Err(MdbError::TableNotFound)));
+
+    let properties = database.table_properties("ASSET ITEMS").unwrap();
+    assert_eq!(properties.column("Cost").unwrap().get("DefaultValue").unwrap().as_str(), Some("0"));
+
+    assert_eq!(database.queries().unwrap().len(), 6);
+    assert_eq!(database.relationships().unwrap().len(), 1);
+
+    let info = database.info().expect("Failed to read info");
+    assert_eq!(info.version, MdbFormatVersion::Accdb2007);
+    assert_eq!(info.page_size, 4096);
+    assert_eq!(info.page_count, 133);
+    assert_eq!(info.properties.get("AccessVersion").unwrap().as_str(), Some("09.50"));
+}
+
+#[test]
+fn test_open_options() {
+    use mdbtools::options::OpenOptions;
+    use std::io::Cursor;
+
+    let path = "testdata/ASampleDatabase.accdb";
+    let bytes = std::fs::read(path).unwrap();
+    let mut expected = Database::open(path).unwrap();
+    let expected_rows: Vec<_> = expected.table("Asset Items").unwrap().rows().collect::<Result<_, _>>().unwrap();
+
+    // A database without a password opens with or without one.
+    let mdb = OpenOptions::new().password("anything").open(path).expect("Failed to open database");
+    assert!(!mdb.mdb_file.has_password());
+    assert_eq!(mdb.mdb_file.len(), bytes.len() as u64);
+
+    // Reading from memory, with or without the page cache, gives the same rows as reading the file.
+    for cache_size in [0, 4] {
+        let mdb = OpenOptions::new().page_cache_size(cache_size).open_reader(Cursor::new(bytes.clone())).unwrap();
+        let mut database = Database::from_mdb(mdb);
+        let rows: Vec<_> = database.table("Asset Items").unwrap().rows().collect::<Result<_, _>>().unwrap();
+        assert_eq!(rows, expected_rows);
+    }
+
+    let mut database = OpenOptions::new().show_system_tables(true).open_database(path).unwrap();
+    let names: Vec<String> = database.tables().unwrap().iter().map(|object| object.name.clone()).collect();
+    assert!(names.contains(&"Asset Items".to_string()));
+    assert!(names.contains(&"MSysObjects".to_string()));
+
+    let mdb = OpenOptions::new().encoding(encoding_rs::WINDOWS_1252).open(path).unwrap();
+    assert_eq!(mdb.encoding, encoding_rs::WINDOWS_1252);
+
+    // Strict mode reads the sample cleanly.
+    let mut strict = OpenOptions::new().strict(true).open_database(path).unwrap();
+    let rows: Vec<_> = strict.table("Asset Items").unwrap().rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows, expected_rows);
+}
+
+#[test]
+fn test_open_options_long_value_limit() {
+    use mdbtools::options::OpenOptions;
+
+    let path = "testdata/ASampleDatabase.accdb";
+    let read_data = |options: &OpenOptions| -> Result<Vec<Value>, MdbError> {
+        let mut database = options.open_database(path)?;
+        let mut table = database.table("MSysAccessStorage")?;
+        table.rows().map(|row| row.map(|row| row["Lv"].clone())).collect()
+    };
+
+    let full = read_data(&OpenOptions::new()).unwrap();
+    assert!(full.iter().any(|value| value.as_bytes().is_some_and(|bytes| bytes.len() > 16)));
+
+    let limited = read_data(OpenOptions::new().max_long_value_size(Some(16))).unwrap();
+    for (full, limited) in full.iter().zip(&limited) {
+        match (full.as_bytes(), limited.as_bytes()) {
+            (Some(full), Some(limited)) => assert_eq!(limited, &full[..full.len().min(16)]),
+            _ => assert_eq!(full, limited),
+        }
+    }
+
+    let strict = read_data(OpenOptions::new().max_long_value_size(Some(16)).strict(true));
+    assert!(matches!(strict, Err(MdbError::LongValueTooLarge)));
+}
+
+#[test]
+fn test_open_options_password() {
+    use mdbtools::options::OpenOptions;
+    use std::io::Cursor;
+
+    // The header is RC4 encrypted and the stored password of the sample is empty, so xoring the password into the
+    // encrypted bytes stores it.
+    let mut bytes = std::fs::read("testdata/ASampleDatabase.accdb").unwrap();
+    for (index, unit) in "secret".encode_utf16().enumerate() {
+        bytes[0x42 + index * 2] ^= unit as u8;
+        bytes[0x42 + index * 2 + 1] ^= (unit >> 8) as u8;
+    }
+
+    let open = |options: &OpenOptions| options.open_reader(Cursor::new(bytes.clone()));
+    assert!(matches!(open(OpenOptions::new().password("Secret")), Err(MdbError::InvalidPassword)));
+    assert!(open(&OpenOptions::new()).unwrap().mdb_file.has_password());
+
+    let mut database = Database::from_mdb(open(OpenOptions::new().password("secret")).unwrap());
+    assert!(database.mdb().mdb_file.has_password());
+    assert_eq!(database.table("Asset Items").unwrap().rows().count(), 65);
+}
+
+#[test]
+fn test_select_columns() {
+    let mut database = Database::open("testdata/ASampleDatabase.accdb").unwrap();
+    let mut table = database.table("Asset Items").unwrap();
+    let all: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+
+    table.select(["owner", "Asset No", "Cost", "Comments"]).unwrap();
+    let names: Vec<&str> = table.selected_columns().map(|column| column.name.as_str()).collect();
+    assert_eq!(names, vec!["Owner", "Asset No", "Cost", "Comments"]);
+
+    let selected: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(selected.len(), all.len());
+    for (row, full) in selected.iter().zip(&all) {
+        assert_eq!(row.names(), &["Owner", "Asset No", "Cost", "Comments"]);
+        for name in ["Owner", "Asset No", "Cost", "Comments"] {
+            assert_eq!(row[name], full[name]);
+        }
+    }
+
+    // Unselected columns are skipped while cracking the row.
+    table.rewind();
+    table.fetch_row().unwrap();
+    let make = table.find_column_index("Make").unwrap();
+    assert!(table.columns[make].buffer.value.is_empty());
+    assert_eq!(table.columns[make].value(), Value::Null);
+
+    assert!(matches!(table.select(["Asset No", "Nope"]), Err(MdbError::ColumnNotFound)));
+
+    table.select_all();
+    let rows: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+    assert_eq!(rows, all);
+}
+
+#[test]
+fn test_predicate_parse() {
+    let predicate = Predicate::parse("[Owner] = 'Sales' AND (Cost BETWEEN 10 AND 20.5 OR Make NOT LIKE \"Geo*\")").unwrap();
+    let expected = Predicate::compare("Owner", CompareOp::Eq, "Sales").and(
+        Predicate::compare("Cost", CompareOp::Ge, 10i64)
+            .and(Predicate::compare("Cost", CompareOp::Le, 20.5))
+            .or(Predicate::like("Make", "Geo*").negate()),
+    );
+    assert_eq!(predicate, expected);
+    assert_eq!(predicate.columns(), vec!["Owner", "Cost", "Make"]);
+
+    let predicate = Predicate::parse("Acquired >= #1999-06-19# AND Comments IS NOT NULL AND Id IN (1, -2)").unwrap();
+    let date = chrono::NaiveDate::from_ymd_opt(1999, 6, 19).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    let expected = Predicate::compare("Acquired", CompareOp::Ge, date)
+        .and(Predicate::is_not_null("Comments"))
+        .and(Predicate::is_in("Id", [1i64, -2]));
+    assert_eq!(predicate, expected);
+
+    for invalid in ["", "Owner", "Owner = ", "Owner = 'Sales' AND", "(Owner = 'Sales'", "Owner NOT = 1", "1 = Owner"] {
+        assert!(matches!(Predicate::parse(invalid), Err(MdbError::InvalidSql)), "{}", invalid);
+    }
+}
+
+#[test]
+fn test_table_filter() {
+    let mut database = Database::open("testdata/ASampleDatabase.accdb").unwrap();
+    let mut table = database.table("Asset Items").unwrap();
+    let all: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+
+    let mut check = |condition: &str, expected: &dyn Fn(&Row) -> bool| {
+        table.filter(Predicate::parse(condition).unwrap()).unwrap();
+        let rows: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+        let matching: Vec<_> = all.iter().filter(|row| expected(row)).cloned().collect();
+        assert!(!matching.is_empty(), "{}", condition);
+        assert_eq!(rows, matching, "{}", condition);
+    };
+
+    let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(0, 0, 0).unwrap();
+    check("[owner] = 'sales' AND Acquired > #6/19/1999#", &|row| {
+        row["Owner"] == Value::from("Sales") && row["Acquired"].as_datetime().unwrap() > date(1999, 6, 19)
+    });
+    check("[Asset No] IN ('30050', '30060', '30118')", &|row| ["30050", "30118"].contains(&row["Asset No"].as_str().unwrap()));
+    check("Make LIKE 'geo*' OR Model LIKE '[0-9]##*'", &|row| {
+        row["Make"].as_str().unwrap().to_lowercase().starts_with("geo") || row["Model"].as_str().unwrap().starts_with("220")
+    });
+    check("Cost >= 1000 AND NOT Cost > '2000'", &|row| (1000.0..=2000.0).contains(&row["Cost"].as_f64().unwrap()));
+    check("Comments IS NULL", &|_| true);
+    // Number columns compared with a literal are read from their bytes.
+    check("Warranty > 0 AND Warranty <= '24'", &|row| (1..=24).contains(&row["Warranty"].as_i64().unwrap()));
+    check("Warranty < 0.5", &|row| row["Warranty"].as_i64().unwrap() == 0);
+
+    // Comparisons with null are unknown, so neither a condition nor its negation matches.
+    table.filter(Predicate::parse("Comments = 'x' OR NOT Comments = 'x'").unwrap()).unwrap();
+    assert_eq!(table.rows().count(), 0);
+
+    // Filtered columns are read even when they aren't selected.
+    table.select(["Asset No"]).unwrap();
+    table.filter(Predicate::parse("Owner = 'Sales' AND Cost < 300").unwrap()).unwrap();
+    let rows: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+    let expected: Vec<_> = all.iter()
+        .filter(|row| row["Owner"] == Value::from("Sales") && row["Cost"].as_f64().unwrap() < 300.0)
+        .map(|row| row["Asset No"].clone())
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(rows.iter().map(|row| row["Asset No"].clone()).collect::<Vec<_>>(), expected);
+    assert_eq!(rows[0].names(), &["Asset No"]);
+
+    assert!(matches!(table.filter(Predicate::is_null("Nope")), Err(MdbError::ColumnNotFound)));
+    table.clear_filter();
+    table.select_all();
+    assert_eq!(table.rows().collect::<Result<Vec<_>, _>>().unwrap(), all);
+}
+
+#[test]
+fn test_table_filter_index_scan() {
+    let mut mdb = open_sample_db();
+    let mut table = get_table(&mut mdb, "MSysObjects");
+    table.read_columns().unwrap();
+    table.select(["Id", "Name"]).unwrap();
+    let all: Vec<_> = table.rows().collect::<Result<_, _>>().unwrap();
+    let id = |row: &Row| row["Id"].as_i64().unwrap();
+
+    for (condition, expected) in [
+        ("Id = 2", (2, 2)),
+        ("Id > 5 AND Id <= 37", (6, 37)),
+        ("Id < -2147483640", (i64::MIN, -2147483641)),
+        ("Id BETWEEN 251658241 AND 251658245", (251658241, 251658245)),
+        ("Id IN (3, 37, 251658242)", (3, 251658242)),
+        ("Id >= 268435456", (268435456, i64::MAX)),
+        ("Id < 4.5", (i64::MIN, 4)),
+    ] {
+        table.filter(Predicate::parse(condition).unwrap()).unwrap();
+        assert_eq!(table.scan_index().map(|index| index.name.as_str()), Some("Id"), "{}", condition);
+
+        let mut ids: Vec<i64> = table.rows().map(|row| id(&row.unwrap())).collect();
+        ids.sort();
+        let mut matching: Vec<i64> = all.iter().map(id).filter(|id| (expected.0..=expected.1).contains(id)).collect();
+        if condition.contains("IN") {
+            matching.retain(|id| [3, 37, 251658242].contains(id));
+        }
+        matching.sort();
+        assert!(!matching.is_empty(), "{}", condition);
+        assert_eq!(ids, matching, "{}", condition);
+    }
+
+    // Conditions without a bound on an indexed number column scan the data pages.
+    table.filter(Predicate::parse("Id <> 2 OR Name = 'Tables'").unwrap()).unwrap();
+    assert!(table.scan_index().is_none());
+    assert_eq!(table.rows().count(), all.len() - 1);
+}
+
+#[test]
+fn test_lazy_long_values() {
+    use mdbtools::long_value::{LongValueStorage, LONG_VALUE_HEADER_SIZE};
+    use mdbtools::options::OpenOptions;
+
+    let path = "testdata/ASampleDatabase.accdb";
+    let mut database = Database::open(path).unwrap();
+    let mut table = database.table("MSysAccessStorage").unwrap();
+    let lv = table.find_column_index("Lv").unwrap();
+
+    let mut storages = Vec::new();
+    while table.fetch_row().is_ok() {
+        let column = &table.columns[lv];
+        let Some(header) = column.long_value_header() else {
+            assert_eq!(column.value(), Value::Null);
+            continue;
+        };
+        assert_eq!(header.raw[..], column.buffer.value[..LONG_VALUE_HEADER_SIZE]);
+        let bytes = column.long_value().unwrap();
+        assert_eq!(bytes.len(), header.length());
+        assert_eq!(column.try_value().unwrap(), Value::Binary(bytes.to_vec()));
+        storages.push(header.storage());
+    }
+    assert!(storages.contains(&LongValueStorage::Inline));
+    assert!(storages.iter().any(|storage| *storage != LongValueStorage::Inline));
+
+    // Rows are fetched without reading their long values, so an oversized one is only an error when it is used.
+    // The catalog has long values too, so it is read with the default options.
+    let entry = read_catalog(&mut open_sample_db()).unwrap().into_iter()
+        .find(|entry| matches!(entry, CatalogEntry::Table(table) if table.name == "MSysAccessStorage"))
+        .unwrap();
+    let mdb = OpenOptions::new().max_long_value_size(Some(16)).strict(true).open(path).unwrap();
+    let mut table = Table::from_catalog_entry(entry, &mdb).unwrap();
+    table.read_columns().unwrap();
+    let mut errors = 0;
+    while table.fetch_row().is_ok() {
+        let column = &table.columns[lv];
+        if column.long_value_header().is_some_and(|header| header.length() > 16) {
+            assert!(matches!(column.try_value(), Err(MdbError::LongValueTooLarge)));
+            assert_eq!(column.value(), Value::Null);
+            errors += 1;
+        }
+    }
+    assert!(errors > 0);
+}
+
+#[test]
+fn test_long_value_reader() {
+    use mdbtools::long_value::LongValueStorage;
+    use mdbtools::options::OpenOptions;
+    use std::io::Read;
+
+    // The reader isn't limited, as it doesn't hold the whole value.
+    let mut database = OpenOptions::new().max_long_value_size(Some(16)).open_database("testdata/ASampleDatabase.accdb").unwrap();
+    let mut table = database.table("MSysAccessStorage").unwrap();
+    let lv = table.find_column_index("Lv").unwrap();
+    let name = table.find_column_index("Name").unwrap();
+    let unlimited = Database::open("testdata/ASampleDatabase.accdb").unwrap()
+        .table("MSysAccessStorage").unwrap()
+        .rows().map(|row| row.unwrap()["Lv"].clone()).collect::<Vec<_>>();
+
+    let mut storages = Vec::new();
+    for expected in unlimited {
+        table.fetch_row().unwrap();
+        let column = &table.columns[lv];
+        let mut reader = column.long_value_reader().unwrap();
+        let mut bytes = Vec::new();
+        let mut chunk = [0; 7];
+        loop {
+            let read = reader.read(&mut chunk).unwrap();
+            if read == 0 {
+                break;
+            }
+            bytes.extend_from_slice(&chunk[..read]);
+        }
+
+        match expected {
+            Value::Binary(expected) => {
+                assert_eq!(bytes, expected);
+                assert_eq!(reader.len(), expected.len());
+                assert_eq!(column.long_value().unwrap(), &expected[..expected.len().min(16)]);
+                storages.push(column.long_value_header().unwrap().storage());
+            }
+            _ => assert!(bytes.is_empty()),
+        }
+    }
+    assert!(storages.contains(&LongValueStorage::Inline));
+    assert!(storages.contains(&LongValueStorage::SinglePage));
+
+    table.rewind();
+    table.fetch_row().unwrap();
+    assert!(matches!(table.columns[name].long_value_reader(), Err(MdbError::UnhandledType)));
+    assert!(matches!(table.columns[name].get_memo_string(&table.mdb), Err(MdbError::UnhandledType)));
+}
+
+/// Build an OLE Object field as Access stores it: the Access header, then an OLE 1.0 object.
+fn ole_field(class: &str, format: u32, topic: &str, native: &[u8]) -> Vec<u8> {
+    let sized = |text: &str| -> Vec<u8> {
+        if text.is_empty() {
+            return 0u32.to_le_bytes().to_vec();
+        }
+        let mut bytes = (text.len() as u32 + 1).to_le_bytes().to_vec();
+        bytes.extend_from_slice(text.as_bytes());
+        bytes.push(0);
+        bytes
+    };
+
+    let pretty = b"Thing\0";
+    let class_z = format!("{}\0", class);
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&0x1C15u16.to_le_bytes());
+    bytes.extend_from_slice(&((20 + pretty.len() + class_z.len()) as u16).to_le_bytes());
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&(pretty.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&(class_z.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&20u16.to_le_bytes());
+    bytes.extend_from_slice(&((20 + pretty.len()) as u16).to_le_bytes());
+    bytes.extend_from_slice(&(-1i32).to_le_bytes());
+    bytes.extend_from_slice(pretty);
+    bytes.extend_from_slice(class_z.as_bytes());
+
+    bytes.extend_from_slice(&0x0501u32.to_le_bytes());
+    bytes.extend_from_slice(&format.to_le_bytes());
+    bytes.extend(sized(class));
+    bytes.extend(sized(topic));
+    bytes.extend(sized(""));
+    if format != 1 {
+        bytes.extend_from_slice(&(native.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(native);
+    }
+    bytes
+}
+
+#[test]
+fn test_sanitize_file_name() {
+    use mdbtools::utils::sanitize_file_name;
+
+    assert_eq!(sanitize_file_name("a/b:c?.txt"), "a_b_c_.txt");
+    assert_eq!(sanitize_file_name(""), "_");
+    assert_eq!(sanitize_file_name("."), "_");
+    assert_eq!(sanitize_file_name(".."), "_");
+    assert_eq!(sanitize_file_name("con"), "_");
+    assert_eq!(sanitize_file_name("NUL.txt"), "_.txt");
+    assert_eq!(sanitize_file_name("COM1"), "_");
+    assert_eq!(sanitize_file_name("console.log"), "console.log");
+    assert_eq!(sanitize_file_name(".hidden"), ".hidden");
+}
+
+#[test]
+fn test_ole_object_parse() {
+    use mdbtools::ole::{OleContentType, OleObject, OleObjectReader};
+    use std::io::Read;
+
+    let mut package = vec![2, 0];
+    package.extend_from_slice(b"report.txt\0C:\\docs\\report.txt\0");
+    package.extend_from_slice(&0x0003_0000u32.to_le_bytes());
+    package.extend_from_slice(&19u32.to_le_bytes());
+    package.extend_from_slice(b"C:\\temp\\report.txt\0");
+    package.extend_from_slice(&5u32.to_le_bytes());
+    package.extend_from_slice(b"hello");
+    let object = OleObject::parse(&ole_field("Package", 2, "", &package)).unwrap();
+    assert_eq!(object.pretty_name, "Thing");
+    assert_eq!(object.class_name, "Package");
+    assert_eq!(object.content_type, OleContentType::Package);
+    assert_eq!(object.file_name.as_deref(), Some("report.txt"));
+    assert_eq!(object.file_path.as_deref(), Some("C:\\docs\\report.txt"));
+    assert_eq!(object.data, b"hello");
+    assert_eq!(object.extension().as_deref(), Some("txt"));
+
+    let object = OleObject::parse(&ole_field("PBrush", 2, "", b"BM\x10\x00\x00\x00rest")).unwrap();
+    assert_eq!(object.content_type, OleContentType::Bitmap);
+    assert_eq!(object.data, b"BM\x10\x00\x00\x00rest");
+    assert_eq!(object.extension().as_deref(), Some("bmp"));
+
+    let object = OleObject::parse(&ole_field("AcroExch.Document.7", 2, "", b"\xd0\xcf\x11\xe0 %PDF-1.4 body %%EOF\n tail")).unwrap();
+    assert_eq!(object.content_type, OleContentType::Pdf);
+    assert_eq!(object.data, b"%PDF-1.4 body %%EOF\n");
+    assert_eq!(object.extension().as_deref(), Some("pdf"));
+
+    let object = OleObject::parse(&ole_field("Word.Document.8", 2, "", b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1doc")).unwrap();
+    assert_eq!(object.content_type, OleContentType::Word);
+    assert_eq!(object.extension().as_deref(), Some("doc"));
+
+    let object = OleObject::parse(&ole_field("Excel.Sheet.8", 1, "C:\\books\\budget.xls", b"")).unwrap();
+    assert_eq!(object.content_type, OleContentType::Link);
+    assert_eq!(object.file_path.as_deref(), Some("C:\\books\\budget.xls"));
+    assert!(object.data.is_empty());
+
+    assert!(matches!(OleObject::parse(b"not an OLE object at all"), Err(MdbError::InvalidOleObject)));
+    let truncated = ole_field("Package", 2, "", &package);
+    assert!(matches!(OleObject::parse(&truncated[..truncated.len() - 3]), Err(MdbError::InvalidOleObject)));
+
+    // The reader streams the embedded file after the headers.
+    let field = ole_field("Package", 2, "", &package);
+    let mut reader = OleObjectReader::new(&field[..]).unwrap();
+    assert_eq!(reader.object().file_name.as_deref(), Some("report.txt"));
+    assert!(reader.object().data.is_empty());
+    assert_eq!(reader.extension().as_deref(), Some("txt"));
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"hello");
+
+    let long_package = [&package[..package.len() - 9], &100u32.to_le_bytes(), &[b'x'; 100]].concat();
+    let long_field = ole_field("Package", 2, "", &long_package);
+    let mut reader = OleObjectReader::new(&long_field[..long_field.len() - 3]).unwrap();
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert!(matches!(MdbError::from(err), MdbError::InvalidOleObject));
+}
+
+#[test]
+fn test_attachment_data_decode() {
+    use mdbtools::complex::decode_attachment_data;
+
+    let text = b"The quick brown fox jumps over the lazy dog. ".repeat(20);
+    let compressed = attachment_data("txt", &text, true);
+    assert!(compressed.len() < text.len());
+    assert_eq!(decode_attachment_data(&compressed).unwrap(), text);
+
+    // Files that are already compressed are stored as they are.
+    let jpeg = b"\xff\xd8\xff\xe0 not much of a picture";
+    assert_eq!(decode_attachment_data(&attachment_data("jpg", jpeg, false)).unwrap(), jpeg);
+    assert_eq!(decode_attachment_data(&attachment_data("txt", b"", true)).unwrap(), b"");
+
+    assert!(matches!(decode_attachment_data(b"\x01\x00"), Err(MdbError::InvalidAttachment)));
+    let mut unknown = attachment_data("txt", b"data", false);
+    unknown[0] = 7;
+    assert!(matches!(decode_attachment_data(&unknown), Err(MdbError::InvalidAttachment)));
+    let truncated = attachment_data("txt", &text, true);
+    assert!(matches!(decode_attachment_data(&truncated[..truncated.len() / 2]), Err(MdbError::InvalidAttachment)));
+}
+
+#[test]
+fn test_attachments_lookup() {
+    let mut database = Database::open("testdata/ASampleDatabase.accdb").unwrap();
+    // The sample database has the complex type tables, but no complex columns.
+    assert!(database.complex_columns().unwrap().is_empty());
+    assert!(database.table("Asset Items").unwrap().columns.iter().all(|column| column.complex_id.is_none()));
+
+    assert!(matches!(database.attachments("Asset Items", "Make"), Err(MdbError::UnhandledType)));
+    assert!(matches!(database.attachments("Asset Items", "Missing"), Err(MdbError::ColumnNotFound)));
+    assert!(matches!(database.attachments("Missing", "Make"), Err(MdbError::TableNotFound)));
+}
+
+#[test]
+fn test_attachments() {
+    let mut database = Database::open(fixture::complex_database()).unwrap();
+    let mut attachments = database.attachments("Items", "Files").unwrap();
+
+    let files = attachments.get(1).unwrap();
+    let names: Vec<&str> = files.iter().map(|file| file.file_name.as_str()).collect();
+    assert_eq!(names, ["notes.txt", "logo.png"]);
+    // Text is compressed, and files that are already compressed are stored as they are.
+    assert_eq!(files[0].data, b"Remember the milk.\n");
+    assert_eq!(files[0].file_type, "txt");
+    assert_eq!(files[0].time_stamp.unwrap().to_string(), "2021-03-01 09:00:00");
+    assert_eq!(files[0].flags, Some(0));
+    assert_eq!(files[0].file_url, None);
+    assert_eq!(files[1].data, fixture::PNG);
+
+    let files = attachments.get(2).unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!((files[0].file_name.as_str(), &files[0].data[..]), ("manual.pdf", &b"%PDF-1.4 manual"[..]));
+    assert!(attachments.get(3).unwrap().is_empty());
+
+    // The other complex columns aren't attachments.
+    assert!(matches!(database.attachments("Items", "Colors"), Err(MdbError::UnhandledType)));
+    assert!(matches!(database.attachments("Items", "Name"), Err(MdbError::UnhandledType)));
+}
+
+#[test]
+fn test_multivalued_values() {
+    let mut database = Database::open(fixture::complex_database()).unwrap();
+    let mut table = database.table("Items").unwrap();
+    let colors: Vec<Value> = table.rows().map(|row| row.unwrap()["Colors"].clone()).collect();
+    let text = |value: &str| Value::Text(value.to_string());
+    assert_eq!(colors, [Value::List(vec![text("Red"), text("Blue")]), Value::List(vec![text("Green")]), Value::List(vec![])]);
+
+    // The values are looked up by the complex value of the row, whatever order the flat table has them in.
+    let values = database.complex_values("Items", "Colors").unwrap();
+    let rows = values.rows(1).unwrap();
+    assert_eq!(rows.iter().map(|row| row["Value"].clone()).collect::<Vec<_>>(), [text("Red"), text("Blue")]);
+    assert_eq!(rows[0].names(), ["Value"]);
+    assert_eq!(values.rows(2).unwrap().len(), 1);
+    assert!(values.rows(4).unwrap().is_empty());
+}
+
+#[test]
+fn test_complex_values_shared() {
+    fn assert_sync<T: Send + Sync>() {}
+    assert_sync::<Table>();
+    assert_sync::<Database>();
+
+    // The flat table is read once for the database, and its rows can be read from other threads.
+    let mut database = Database::open(fixture::complex_database()).unwrap();
+    let values = database.complex_values("Items", "Colors").unwrap();
+    assert!(std::sync::Arc::ptr_eq(&values, &database.complex_values("Items", "Colors").unwrap()));
+    let mut table = database.table("Items").unwrap();
+    let colors = std::thread::spawn(move || table.rows().map(|row| row.unwrap()["Colors"].clone()).collect::<Vec<_>>());
+    assert_eq!(colors.join().unwrap()[1], Value::List(vec![Value::Text("Green".to_string())]));
+    assert_eq!(values.rows(1).unwrap().len(), 2);
+}
+
+#[test]
+fn test_memo_history_lookup() {
+    let mut database = Database::open("testdata/ASampleDatabase.accdb").unwrap();
+    // Comments is a memo without a version history, and Make isn't a memo.
+    assert!(matches!(database.memo_history("Asset Items", "Comments"), Err(MdbError::UnhandledType)));
+    assert!(matches!(database.memo_history("Asset Items", "Make"), Err(MdbError::UnhandledType)));
+    assert!(matches!(database.memo_history("Asset Items", "Missing"), Err(MdbError::ColumnNotFound)));
+}
+
+#[test]
+fn test_memo_history() {
+    let mut database = Database::open(fixture::complex_database()).unwrap();
+    let history = database.memo_history("Items", "Notes").unwrap();
+    assert_eq!(history.column(), "NotesHistory");
+
+    // The versions are sorted by date, whatever order they were stored in.
+    let versions: Vec<(String, String)> = history.get(1).unwrap().into_iter()
+        .map(|version| (version.value, version.modified.unwrap().to_string()))
+        .collect();
+    assert_eq!(versions, [
+        ("First draft".to_string(), "2021-03-01 09:00:00".to_string()),
+        ("Checked twice".to_string(), "2021-03-02 10:30:00".to_string()),
+    ]);
+    assert!(history.get(2).unwrap().is_empty());
+    assert_eq!(history.get(3).unwrap().len(), 1);
+
+    // Name is a text column, not an append only memo.
+    assert!(matches!(database.memo_history("Items", "Name"), Err(MdbError::UnhandledType)));
+}
+
+#[test]
+fn test_complex_column() {
+    use mdbtools::column::Column;
+    use mdbtools::complex::{ComplexColumn, ComplexColumnKind};
+
+    let mut value = Column::new(encoding_rs::WINDOWS_1252);
+    value.name = "Value".to_string();
+    value.column_type = ColumnType::Text;
+    let mut complex_column = ComplexColumn {
+        name: "Colors".to_string(),
+        complex_id: 3,
+        table: "Items".to_string(),
+        flat_table: "f_0C8D5E9A3F2B4C1D9E7A6B5C4D3E2F1A_Colors".to_string(),
+        type_table: "MSysComplexType_Text".to_string(),
+        kind: ComplexColumnKind::MultiValue,
+        foreign_key: "_Colors".to_string(),
+        value_columns: vec![value],
+    };
+    assert_eq!(complex_column.child_table_name(), "Items_Colors");
+    assert_eq!(complex_column.value_column().map(|column| column.name.as_str()), Some("Value"));
+
+    // Only multivalued fields have a single value column.
+    complex_column.kind = ComplexColumnKind::Attachment;
+    assert!(complex_column.value_column().is_none());
+}
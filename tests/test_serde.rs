@@ -0,0 +1,117 @@
+#![cfg(feature = "serde")]
+
+use chrono::NaiveDateTime;
+use mdbtools::database::Database;
+use mdbtools::error::MdbError;
+use mdbtools::row::Row;
+use mdbtools::value::Value;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn open_sample_database() -> Database {
+    Database::open("testdata/ASampleDatabase.accdb").expect("Failed to open database")
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetItem {
+    #[serde(rename = "Asset No")]
+    asset_no: String,
+    // Matched to "Asset Category" ignoring case and punctuation.
+    asset_category: Option<String>,
+    make: Option<String>,
+    acquired: Option<NaiveDateTime>,
+    cost: Option<Decimal>,
+    warranty: Option<i64>,
+    comments: Option<String>,
+}
+
+#[test]
+fn test_deserialize_rows() {
+    let mut database = open_sample_database();
+    let items: Vec<AssetItem> = database.table("Asset Items").unwrap().deserialize().unwrap();
+    assert_eq!(items.len(), 65);
+
+    let first = &items[0];
+    assert_eq!(first.asset_no, "30050");
+    assert_eq!(first.asset_category.as_deref(), Some("Computer Hardware"));
+    assert_eq!(first.make.as_deref(), Some("GEO Rocket"));
+    assert_eq!(
+        first.acquired,
+        Some(NaiveDateTime::parse_from_str("1997-09-02 00:00:00", "%Y-%m-%d %H:%M:%S").unwrap())
+    );
+    assert_eq!(first.cost, Some(Decimal::from_str("1995.5").unwrap()));
+    assert_eq!(first.warranty, Some(12));
+    assert_eq!(first.comments, None);
+
+    // Tuples read the leading columns in order.
+    let mut table = database.table("Asset Items").unwrap();
+    let row = table.rows().next().unwrap().unwrap();
+    let (asset_no, category): (String, String) = row.deserialize().unwrap();
+    assert_eq!((asset_no.as_str(), category.as_str()), ("30050", "Computer Hardware"));
+}
+
+#[test]
+fn test_deserialize_binary_and_guid() {
+    #[derive(Deserialize)]
+    struct Object {
+        name: String,
+        #[serde(rename = "Lv")]
+        lv: Option<Vec<u8>>,
+    }
+
+    let mut database = open_sample_database();
+    let objects: Vec<Object> = database.table("MSysAccessStorage").unwrap().deserialize().unwrap();
+    assert!(objects.iter().any(|object| object.name == "MSysAccessStorage_ROOT"));
+    assert!(objects.iter().any(|object| object.lv.as_ref().is_some_and(|lv| !lv.is_empty())));
+
+    let guid = Value::Guid([
+        0xDC, 0x66, 0xE7, 0xF1, 0x61, 0xBC, 0xD3, 0x11, 0x9F, 0x44, 0x00, 0x90, 0x27, 0x2F, 0x54, 0x0F,
+    ]);
+    let row = Row::new(Arc::from(vec!["GUID".to_string()]), vec![guid]);
+    let (id,): (Uuid,) = row.deserialize().unwrap();
+    assert_eq!(id, Uuid::parse_str("F1E766DC-BC61-11D3-9F44-0090272F540F").unwrap());
+}
+
+#[test]
+fn test_deserialize_list() {
+    // Multivalued fields deserialize into sequences.
+    let colors = Value::List(vec![Value::Text("Red".to_string()), Value::Text("Blue".to_string())]);
+    let sizes = Value::List(vec![Value::I16(8), Value::I16(10)]);
+    let row = Row::new(Arc::from(vec!["Colors".to_string(), "Sizes".to_string()]), vec![colors, sizes]);
+    let (colors, sizes): (Vec<String>, Vec<i32>) = row.deserialize().unwrap();
+    assert_eq!(colors, ["Red", "Blue"]);
+    assert_eq!(sizes, [8, 10]);
+}
+
+#[test]
+fn test_deserialize_errors() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Wrong {
+        #[serde(rename = "Asset No")]
+        asset_no: String,
+        // Null in every row.
+        comments: String,
+    }
+
+    let mut database = open_sample_database();
+    let err = database.table("Asset Items").unwrap().deserialize::<Wrong>().unwrap_err();
+    let MdbError::Deserialize(err) = err else {
+        panic!("Unexpected error {:?}", err);
+    };
+    assert_eq!(err.table.as_deref(), Some("Asset Items"));
+    assert_eq!(err.row, Some(0));
+    assert_eq!(err.column.as_deref(), Some("Comments"));
+    assert!(err.to_string().starts_with("table \"Asset Items\", row 0, column \"Comments\", "));
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Missing {
+        serial: String,
+    }
+    let err = database.table("Asset Items").unwrap().deserialize::<Missing>().unwrap_err();
+    assert!(matches!(err, MdbError::Deserialize(err) if err.column.is_none() && err.message == "missing field `serial`"));
+}